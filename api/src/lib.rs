@@ -0,0 +1,129 @@
+// request and response bodies for the webserver's json api. the server and the client both
+// use these, so the two can't drift apart
+use bankaccounts::{AccountType, BankAccount, Transaction};
+use chrono::NaiveDate;
+use money::Money;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FutureValueRequest {
+    pub present_value: Money,
+    // added at the end of every month
+    #[serde(default)]
+    pub monthly_contribution: Money,
+    // percent per year, compounded monthly
+    pub annual_rate: f64,
+    pub years: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FutureValue {
+    pub future_value: Money,
+    pub contributions: Money,
+    pub interest: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmortizationRequest {
+    pub principal: Money,
+    pub annual_rate: f64,
+    pub years: u32,
+    #[serde(default)]
+    pub extra_payment: Money,
+    // the loan's start; the first payment is due a month later
+    pub start: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaymentRow {
+    pub number: u32,
+    pub date: NaiveDate,
+    pub payment: Money,
+    pub principal: Money,
+    pub interest: Money,
+    pub remaining: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Amortization {
+    pub monthly_payment: Money,
+    pub total_interest: Money,
+    pub total_paid: Money,
+    pub payoff_date: NaiveDate,
+    pub payments: Vec<PaymentRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NewAccountKind {
+    Checking { overdraft_limit: Money, overdraft_fee: Money },
+    Cd { term_months: u32, early_withdrawal_fee: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewAccount {
+    pub name: String,
+    pub balance: Money,
+    pub interest_rate: f64,
+    pub kind: NewAccountKind,
+}
+
+impl NewAccount {
+    pub fn open(&self) -> BankAccount {
+        match self.kind {
+            NewAccountKind::Checking { overdraft_limit, overdraft_fee } =>
+                BankAccount::new_checking(&self.name, self.balance, self.interest_rate, overdraft_limit, overdraft_fee),
+            NewAccountKind::Cd { term_months, early_withdrawal_fee } =>
+                BankAccount::new_cd(&self.name, self.balance, self.interest_rate, term_months, early_withdrawal_fee),
+        }
+    }
+}
+
+// fields left out are unchanged
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccountUpdate {
+    pub name: Option<String>,
+    pub interest_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountSummary {
+    pub name: String,
+    pub kind: String,
+    pub balance: Money,
+    pub interest_rate: f64,
+}
+
+impl From<&BankAccount> for AccountSummary {
+    fn from(account: &BankAccount) -> Self {
+        AccountSummary {
+            name: account.get_name().to_string(),
+            kind: account.kind().to_string(),
+            balance: account.get_balance(),
+            interest_rate: account.interest_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDetail {
+    #[serde(flatten)]
+    pub summary: AccountSummary,
+    pub account_type: AccountType,
+    pub transactions: Vec<Transaction>,
+}
+
+impl From<&BankAccount> for AccountDetail {
+    fn from(account: &BankAccount) -> Self {
+        AccountDetail {
+            summary: AccountSummary::from(account),
+            account_type: account.account_type.clone(),
+            transactions: account.transactions.clone(),
+        }
+    }
+}
+
+// the body of every non-2xx response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub error: String,
+}
@@ -0,0 +1,56 @@
+// converts between nominal dollars and dollars as of a base date ("today's dollars") under
+// a constant annual inflation rate
+use chrono::{DateTime, Utc};
+use money::{Money, Rounding};
+use serde::{Deserialize, Serialize};
+
+const DAYS_PER_YEAR: f64 = 365.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Inflation {
+    // e.g. 0.03 for 3% a year
+    pub annual_rate: f64,
+    pub base: DateTime<Utc>,
+}
+
+impl Inflation {
+    pub fn new(annual_rate: f64, base: DateTime<Utc>) -> Self {
+        Inflation { annual_rate, base }
+    }
+
+    pub fn deflator(&self, when: DateTime<Utc>) -> f64 {
+        // how many nominal dollars at `when` buy what one dollar bought at the base date
+        let years = (when - self.base).num_days() as f64 / DAYS_PER_YEAR;
+        (1.0 + self.annual_rate).powf(years)
+    }
+
+    pub fn real(&self, nominal: Money, when: DateTime<Utc>) -> Money {
+        nominal.mul_rate(1.0 / self.deflator(when), Rounding::HalfEven)
+    }
+
+    pub fn nominal(&self, real: Money, when: DateTime<Utc>) -> Money {
+        real.mul_rate(self.deflator(when), Rounding::HalfEven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn deflates_and_inflates() {
+        let base = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let inflation = Inflation::new(0.03, base);
+        let later = base + chrono::Duration::days((DAYS_PER_YEAR * 10.0) as i64);
+
+        assert!((inflation.deflator(later) - 1.03f64.powi(10)).abs() < 1e-3);
+        assert_eq!(inflation.real(Money::dollars(1000.0), base), Money::dollars(1000.0));
+        // about $1,343.92 ten years out buys what $1,000 does now
+        let nominal = inflation.nominal(Money::dollars(1000.0), later);
+        assert!((nominal - Money::dollars(1343.92)).abs() < Money::dollars(0.10));
+        assert_eq!(inflation.real(nominal, later), Money::dollars(1000.0));
+        // before the base date money was worth more
+        assert!(inflation.real(Money::dollars(1000.0), base - chrono::Duration::days(365)) > Money::dollars(1000.0));
+    }
+}
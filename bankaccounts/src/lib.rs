@@ -0,0 +1,544 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::fmt;
+use money::{Money, Rounding};
+use simclock::{Clock, Frequency, Recurrence};
+use inflation::Inflation;
+
+pub mod inflation;
+pub mod person;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TransactionType {
+    Deposit,
+    Withdrawal,
+    Interest,
+    Fee,
+    Tax,
+    Sale,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub transaction_type: TransactionType,
+    pub amount: Money,
+    pub timestamp: DateTime<Utc>,
+    pub description: Option<String>
+}
+
+impl Transaction {
+    // the change this transaction made to the balance: money in is positive, money out negative
+    pub fn signed_amount(&self) -> Money {
+        match self.transaction_type {
+            TransactionType::Deposit | TransactionType::Interest | TransactionType::Sale => self.amount,
+            TransactionType::Withdrawal | TransactionType::Fee | TransactionType::Tax => -self.amount,
+        }
+    }
+}
+
+// a deposit or withdrawal that repeats on a schedule, applied as a clock passes each due date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringTransaction {
+    pub transaction_type: TransactionType,
+    pub amount: Money,
+    pub description: Option<String>,
+    pub schedule: Recurrence,
+}
+
+#[derive(Debug)]
+pub enum DepositError {
+    NegativeAmount(Money),
+}
+
+#[derive(Debug)]
+pub enum WithdrawalError {
+    InsufficientFunds { requested: Money, available: Money },
+    NegativeAmount(Money),
+}
+
+#[derive(Debug)]
+pub enum TransferError {
+    InsufficientFunds { requested: Money, available: Money },
+    NegativeAmount(Money),
+    DepositFailed,
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::InsufficientFunds { requested, available } => 
+                write!(f, "transfer failed - insufficient funds: requested {}, available {}", requested, available),
+            TransferError::NegativeAmount(amt) => 
+                write!(f, "cannot transfer negative amount: {}", amt),
+            TransferError::DepositFailed => 
+                write!(f, "transfer failed during deposit phase"),
+        }
+    }
+}
+
+impl fmt::Display for WithdrawalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WithdrawalError::InsufficientFunds { requested, available } => 
+                write!(f, "insufficient funds: requested {}, available {}", requested, available),
+            WithdrawalError::NegativeAmount(amt) => 
+                write!(f, "cannot withdraw negative amount: {}", amt),
+        }
+    }
+}
+
+impl fmt::Display for DepositError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DepositError::NegativeAmount(amt) => 
+                write!(f, "cannot deposit a negative amount: {}", amt),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum AccountType {
+    Checking {
+        overdraft_limit: Money,
+        overdraft_fee: Money,
+    },
+    CD {
+        maturity_date: DateTime<Utc>,
+        early_withdrawal_fee: f64,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BankAccount {
+    pub name: String,
+    pub account_type: AccountType,
+    pub starting_balance: Money,
+    pub balance: Money,
+    pub interest_rate: f64,
+    pub transactions: Vec<Transaction>,
+    #[serde(default)]
+    pub recurring: Vec<RecurringTransaction>,
+    // when the next month of interest is credited by accrue_until
+    #[serde(default)]
+    pub next_accrual: Option<Recurrence>,
+}
+
+impl BankAccount {
+    pub fn new_checking(name: &str, balance: Money, interest_rate: f64, overdraft_limit: Money, overdraft_fee: Money) -> Self {
+        BankAccount {
+            name: name.to_string(),
+            starting_balance: balance,
+            balance,
+            interest_rate,
+            transactions: Vec::new(),
+            recurring: Vec::new(),
+            next_accrual: None,
+            account_type: AccountType::Checking{
+                overdraft_limit,
+                overdraft_fee,
+            },
+        }
+    }
+
+    pub fn new_cd(name: &str, balance: Money, interest_rate: f64, term_months: u32, early_withdrawal_fee: f64) -> Self {
+        let maturity_date = Utc::now() + chrono::Duration::days(term_months as i64 * 30);
+        BankAccount {
+            name: name.to_string(),
+            starting_balance: balance,
+            balance,
+            interest_rate,
+            transactions: Vec::new(),
+            recurring: Vec::new(),
+            next_accrual: None,
+            account_type: AccountType::CD {
+                maturity_date,
+                early_withdrawal_fee,
+            },
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_balance(&self) -> Money {
+        self.balance
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self.account_type {
+            AccountType::Checking { .. } => "Checking",
+            AccountType::CD { .. } => "CD",
+        }
+    }
+
+    pub fn calc_cost_basis(&self) -> Money {
+        let mut cost_basis = self.starting_balance;
+        println!("Initial Cost Basis: {}", cost_basis);
+        for trans in &self.transactions {
+            match trans.transaction_type {
+                TransactionType::Deposit => cost_basis += trans.amount,
+                TransactionType::Fee => cost_basis -= trans.amount,
+                TransactionType::Tax => cost_basis -= trans.amount,
+                TransactionType::Withdrawal => cost_basis -= trans.amount,
+                _ => (),
+            };
+        }
+        println!("Final Cost Basis: {}", cost_basis);
+
+        cost_basis
+    }
+
+    pub fn calc_capital_gains(&self, transaction: &Transaction) -> Money {
+        println!("{:?}", transaction);
+        let cost_basis = self.calc_cost_basis();
+        let zero = Money::zero(self.balance.currency());
+        if cost_basis > self.balance {
+            return zero;
+        }
+        let cb_ratio = (self.balance - cost_basis).ratio(self.starting_balance);
+        match transaction.transaction_type {
+            TransactionType::Withdrawal | TransactionType::Fee => transaction.amount.mul_rate(cb_ratio * 0.15, Rounding::HalfEven),
+            _ => zero
+        }
+    }
+
+    pub fn accrue(&mut self) -> Money {
+        self.credit_interest(self.interest_rate / 100.0, Utc::now());
+        self.balance
+    }
+
+    fn credit_interest(&mut self, rate: f64, when: DateTime<Utc>) {
+        let interest_amount = self.balance.mul_rate(rate, Rounding::HalfEven);
+        if interest_amount.is_positive() {
+            self.balance += interest_amount;
+            self.transactions.push(Transaction {
+                transaction_type: TransactionType::Interest,
+                amount: interest_amount,
+                timestamp: when,
+                description: Some(format!("Interest at {}%", self.interest_rate)),
+            });
+        }
+    }
+
+    pub fn add_recurring(&mut self, transaction_type: TransactionType, amount: Money, note: Option<&str>, schedule: Recurrence) -> Result<(), DepositError> {
+        if amount.is_negative() {
+            return Err(DepositError::NegativeAmount(amount));
+        }
+        self.recurring.push(RecurringTransaction {
+            transaction_type,
+            amount,
+            description: note.map(|n| n.to_string()),
+            schedule,
+        });
+        Ok(())
+    }
+
+    pub fn accrue_until(&mut self, clock: &Clock) -> Money {
+        // credits a twelfth of the annual rate for every month the clock has passed. the
+        // first call only starts the schedule, a month after the clock's current time
+        let mut schedule = self.next_accrual
+            .unwrap_or_else(|| Recurrence::new(clock.now() + chrono::Months::new(1), Frequency::Monthly));
+        for when in schedule.due(clock) {
+            self.credit_interest(self.interest_rate / 100.0 / 12.0, when);
+        }
+        self.next_accrual = Some(schedule);
+        self.balance
+    }
+
+    pub fn advance(&mut self, clock: &Clock) -> Vec<WithdrawalError> {
+        // applies every recurring transaction due by the clock's time in date order, timestamped
+        // when it fell due. withdrawals that can't be covered are skipped and returned
+        let mut due: Vec<(DateTime<Utc>, usize)> = Vec::new();
+        for (i, recurring) in self.recurring.iter_mut().enumerate() {
+            due.extend(recurring.schedule.due(clock).into_iter().map(|when| (when, i)));
+        }
+        due.sort_by_key(|(when, i)| (*when, *i));
+
+        let mut failures = Vec::new();
+        for (when, i) in due {
+            let RecurringTransaction { transaction_type, amount, description, .. } = self.recurring[i].clone();
+            match transaction_type {
+                TransactionType::Deposit => {
+                    // amounts were checked when the transaction was added
+                    let _ = self.deposit_at(amount, description, when);
+                }
+                TransactionType::Withdrawal => {
+                    if let Err(e) = self.withdraw_at(amount, description, when) {
+                        failures.push(e);
+                    }
+                }
+                transaction_type => {
+                    let transaction = Transaction { transaction_type, amount, timestamp: when, description };
+                    self.balance += transaction.signed_amount();
+                    self.transactions.push(transaction);
+                }
+            }
+        }
+        failures
+    }
+
+    pub fn deposit(&mut self, amount: Money, note: Option<&str>) -> Result<Money,DepositError> {
+        self.deposit_at(amount, note.map(|n| n.to_string()), Utc::now())
+    }
+
+    pub fn deposit_on(&mut self, clock: &Clock, amount: Money, note: Option<&str>) -> Result<Money,DepositError> {
+        self.deposit_at(amount, note.map(|n| n.to_string()), clock.now())
+    }
+
+    fn deposit_at(&mut self, amount: Money, note: Option<String>, when: DateTime<Utc>) -> Result<Money,DepositError> {
+        if amount.is_negative() {
+            Err(DepositError::NegativeAmount(amount))
+        } else {
+            self.balance += amount;
+
+            self.transactions.push(Transaction {
+                transaction_type: TransactionType::Deposit,
+                amount,
+                timestamp: when,
+                description: note,
+            });
+
+            Ok(amount)
+        }
+    }
+
+    pub fn withdraw(&mut self, amount: Money, note: Option<&str>) -> Result<Money,WithdrawalError> {
+        self.withdraw_at(amount, note.map(|n| n.to_string()), Utc::now())
+    }
+
+    pub fn withdraw_on(&mut self, clock: &Clock, amount: Money, note: Option<&str>) -> Result<Money,WithdrawalError> {
+        self.withdraw_at(amount, note.map(|n| n.to_string()), clock.now())
+    }
+
+    fn withdraw_at(&mut self, amount: Money, note: Option<String>, when: DateTime<Utc>) -> Result<Money,WithdrawalError> {
+        if amount.is_negative() {
+            return Err(WithdrawalError::NegativeAmount(amount));
+        }
+        
+        match &mut self.account_type {
+            AccountType::Checking { overdraft_limit, overdraft_fee } => {
+                if amount > self.balance + *overdraft_limit {
+                    return Err(WithdrawalError::InsufficientFunds{requested: amount, available: self.balance});
+                }
+                if amount > self.balance {
+                    self.balance -= *overdraft_fee;
+                    self.transactions.push(Transaction {
+                        transaction_type: TransactionType::Fee,
+                        amount: *overdraft_fee,
+                        timestamp: when,
+                        description: Some("Overdraft fee".to_string()),
+                    });
+                }
+            },
+            AccountType::CD {maturity_date, early_withdrawal_fee} => {
+                if when < *maturity_date {
+                    let penalty = amount.mul_rate(*early_withdrawal_fee, Rounding::HalfUp);
+
+                    if amount + penalty > self.balance {
+                        return Err(WithdrawalError::InsufficientFunds{ requested: amount+penalty, available: self.balance});
+                    }
+
+                    self.balance -= penalty;
+
+                    self.transactions.push( Transaction {
+                        transaction_type: TransactionType::Fee,
+                        amount: penalty,
+                        timestamp: when,
+                        description: Some(format!("Early withdrawal fee {:.1}% of {}", *early_withdrawal_fee*100.0, amount)),
+                    });
+                } else {
+                    if amount > self.balance {
+                        return Err(WithdrawalError::InsufficientFunds{requested: amount, available: self.balance});
+                    }
+                }
+            },
+        }
+        self.balance -= amount;
+
+        self.transactions.push(Transaction {
+            transaction_type: TransactionType::Withdrawal,
+            amount,
+            timestamp: when,
+            description: note,
+        });
+
+        Ok(amount)
+    }
+
+    pub fn transfer(&mut self, other: &mut Self, amount: Money) -> Result<Money,TransferError> {
+        if amount.is_negative() {
+            return Err(TransferError::NegativeAmount(amount));
+        }
+
+        let note = format!("transfer to {}", other.get_name());
+        let withdrawn_amount = self.withdraw(amount, Some(&note)).map_err(|err| match err {
+            WithdrawalError::InsufficientFunds { requested, available } => 
+                TransferError::InsufficientFunds { requested, available },
+            WithdrawalError::NegativeAmount(amt) => TransferError::NegativeAmount(amt),
+        })?;
+
+        let note = format!("transfer from {}", self.get_name());
+        match other.deposit(withdrawn_amount, Some(&note)) {
+            Ok(deposited_amount) => Ok(deposited_amount),
+            Err(_) => {
+                let _ = self.deposit(withdrawn_amount, None).unwrap();
+                Err(TransferError::DepositFailed)
+            }
+        }
+    }
+
+    pub fn load_accounts_from_json(filepath: &str) -> serde_json::Result<Vec<BankAccount>> {
+        let json_data = fs::read_to_string(filepath)
+            .map_err(serde_json::Error::io)?;
+        let accounts: Vec<BankAccount> = serde_json::from_str(&json_data)?;
+
+        Ok(accounts)
+    }
+
+    pub fn write_json_to_file(&self, filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(&self)?;
+        let mut file = File::create(filepath)
+            .map_err(serde_json::Error::io)?;
+
+        file.write_all(json_data.as_bytes())
+            .map_err(serde_json::Error::io)?;
+
+        Ok(())
+    }
+
+    pub fn write_accounts_json_to_file(accounts: &[BankAccount], filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(accounts)?;
+        let mut file = File::create(filepath)
+            .map_err(serde_json::Error::io)?;
+
+        file.write_all(json_data.as_bytes())
+            .map_err(serde_json::Error::io)?;
+
+        Ok(())
+    }
+
+    pub fn summarize_transactions(&self) -> HashMap<TransactionType, Money> {
+        let mut summary = HashMap::new();
+
+        for transaction in &self.transactions {
+            *summary.entry(transaction.transaction_type.clone()).or_insert(Money::zero(transaction.amount.currency())) += transaction.amount;
+        }
+
+        summary
+    }
+
+    pub fn generate_statement(&self, start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>) -> String {
+        self.statement(start_date, end_date, None)
+    }
+
+    pub fn generate_real_statement(&self, start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>, inflation: &Inflation) -> String {
+        // the usual statement plus each amount and balance in dollars as of the inflation base date
+        self.statement(start_date, end_date, Some(inflation))
+    }
+
+    fn statement(&self, start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>, inflation: Option<&Inflation>) -> String {
+        let mut statement = format!("Statement for: {}\n", self.name);
+        match inflation {
+            None => {
+                statement.push_str("Date                  | Type       | Amount      | Balance      | Description\n");
+                statement.push_str("----------------------|------------|-------------|--------------|------------\n");
+            }
+            Some(inflation) => {
+                statement.push_str(&format!("Real amounts in {} dollars at {:.1}% inflation\n",
+                    inflation.base.format("%Y-%m-%d"), inflation.annual_rate * 100.0));
+                statement.push_str("Date                  | Type       | Amount      | Balance      | Real Amount | Real Balance | Description\n");
+                statement.push_str("----------------------|------------|-------------|--------------|-------------|--------------|------------\n");
+            }
+        }
+        
+        let mut running_balance = self.starting_balance;
+
+        for transaction in &self.transactions {
+            // Skip if before start_date or after end_date
+            if (start_date.is_some() && transaction.timestamp < start_date.unwrap()) ||
+            (end_date.is_some() && transaction.timestamp > end_date.unwrap()) {
+                continue;
+            }
+            running_balance += transaction.signed_amount();
+            let real = inflation.map(|i| format!("{:>11} | {:>11}  | ",
+                i.real(transaction.amount, transaction.timestamp),
+                i.real(running_balance, transaction.timestamp)));
+            statement.push_str(&format!("{}   | {:10} | {:>11} | {:>11}  | {}{}\n",
+                transaction.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                format!("{:?}", transaction.transaction_type),
+                transaction.amount,
+                running_balance,
+                real.unwrap_or_default(),
+                transaction.description.as_deref().unwrap_or("")
+            ));
+        }
+        
+        statement.push_str(&format!("\nCurrent Balance: {}", self.balance));
+        statement
+    }
+
+    pub fn reset(&mut self) {
+        self.transactions = Vec::<Transaction>::new();
+        self.balance = self.starting_balance;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn recurring_transactions_follow_the_clock() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut clock = Clock::starting_at(start);
+        let mut account = BankAccount::new_checking("Checking", Money::dollars(100.0), 12.0, Money::dollars(0.0), Money::dollars(0.0));
+        account.add_recurring(TransactionType::Deposit, Money::dollars(1000.0), Some("paycheck"),
+            Recurrence::new(start + chrono::Days::new(14), Frequency::Monthly)).unwrap();
+        account.add_recurring(TransactionType::Withdrawal, Money::dollars(900.0), Some("rent"),
+            Recurrence::new(start, Frequency::Monthly)).unwrap();
+        assert!(account.add_recurring(TransactionType::Deposit, Money::dollars(-1.0), None, Recurrence::new(start, Frequency::Daily)).is_err());
+        account.accrue_until(&clock);
+
+        // rent on the 1st fails before the first paycheck lands on the 15th
+        let failures = account.advance(&clock);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(account.get_balance(), Money::dollars(100.0));
+
+        clock.advance_months(2).unwrap();
+        assert!(account.advance(&clock).is_empty());
+        // two paychecks and two rents: +1000 -900 +1000 -900
+        assert_eq!(account.get_balance(), Money::dollars(300.0));
+        assert_eq!(account.transactions[0].timestamp, start + chrono::Days::new(14));
+
+        // one month of interest at 1% a month, credited on the 1st of February and March
+        account.accrue_until(&clock);
+        let interest: Vec<&Transaction> = account.transactions.iter()
+            .filter(|t| t.transaction_type == TransactionType::Interest)
+            .collect();
+        assert_eq!(interest.len(), 2);
+        assert!(account.advance(&clock).is_empty());
+        assert_eq!(account.transactions.len(), 6);
+    }
+
+    #[test]
+    fn real_statement_deflates_amounts() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut clock = Clock::starting_at(start);
+        let mut account = BankAccount::new_checking("Checking", Money::dollars(100.0), 0.0, Money::dollars(0.0), Money::dollars(0.0));
+        clock.jump_to(start + chrono::Duration::days(3653)).unwrap();
+        account.deposit_on(&clock, Money::dollars(1000.0), Some("bonus")).unwrap();
+
+        let statement = account.generate_real_statement(None, None, &Inflation::new(0.03, start));
+        assert!(statement.contains("in 2025-01-01 dollars at 3.0% inflation"));
+        // $1,000 and the $1,100 balance ten years on, in 2025 dollars
+        assert!(statement.contains("$744.06"));
+        assert!(statement.contains("$818.47"));
+        assert!(!account.generate_statement(None, None).contains("Real"));
+    }
+}
@@ -0,0 +1,177 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use money::{Money, Rounding};
+
+use crate::{BankAccount, Transaction};
+
+// shares of one security in a brokerage account, valued at the last known price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holding {
+    pub symbol: String,
+    pub shares: f64,
+    pub price: Money,
+}
+
+impl Holding {
+    pub fn value(&self) -> Money {
+        self.price.mul_rate(self.shares, Rounding::HalfEven)
+    }
+}
+
+// one slice of an allocation pie
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Slice {
+    pub label: String,
+    pub value: Money,
+    pub percent: f64,
+}
+
+// everything one person owns: bank accounts plus a brokerage account of holdings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Person {
+    pub name: String,
+    pub accounts: Vec<BankAccount>,
+    #[serde(default)]
+    pub holdings: Vec<Holding>,
+}
+
+impl Person {
+    pub fn new(name: &str) -> Self {
+        Person {
+            name: name.to_string(),
+            accounts: Vec::new(),
+            holdings: Vec::new(),
+        }
+    }
+
+    pub fn load_from_json(filepath: &str) -> serde_json::Result<Person> {
+        let json_data = fs::read_to_string(filepath)
+            .map_err(serde_json::Error::io)?;
+        serde_json::from_str(&json_data)
+    }
+
+    pub fn write_json_to_file(&self, filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(&self)?;
+        let mut file = File::create(filepath)
+            .map_err(serde_json::Error::io)?;
+
+        file.write_all(json_data.as_bytes())
+            .map_err(serde_json::Error::io)?;
+
+        Ok(())
+    }
+
+    pub fn cash(&self) -> Money {
+        self.accounts.iter().map(|a| a.get_balance()).sum()
+    }
+
+    pub fn brokerage_value(&self) -> Money {
+        self.holdings.iter().map(|h| h.value()).sum()
+    }
+
+    pub fn net_worth(&self) -> Money {
+        self.cash() + self.brokerage_value()
+    }
+
+    pub fn allocation(&self) -> Vec<Slice> {
+        // the brokerage split by holding, largest first
+        let total = self.brokerage_value();
+        let mut slices: Vec<Slice> = self.holdings.iter()
+            .map(|h| Slice {
+                label: h.symbol.clone(),
+                value: h.value(),
+                percent: if total.is_zero() { 0.0 } else { h.value().ratio(total) * 100.0 },
+            })
+            .collect();
+        slices.sort_by_key(|s| std::cmp::Reverse(s.value.cents()));
+        slices
+    }
+
+    pub fn recent_transactions(&self, count: usize) -> Vec<(&str, &Transaction)> {
+        // newest first, across every account
+        let mut all: Vec<(&str, &Transaction)> = self.accounts.iter()
+            .flat_map(|a| a.transactions.iter().map(move |t| (a.get_name(), t)))
+            .collect();
+        all.sort_by_key(|(_, t)| std::cmp::Reverse(t.timestamp));
+        all.truncate(count);
+        all
+    }
+
+    pub fn net_worth_history(&self) -> Vec<(NaiveDate, Money)> {
+        // net worth at the end of each day with a transaction, replaying every account's
+        // transactions from the starting balances. holdings count at today's value throughout
+        let mut events: Vec<(DateTime<Utc>, Money)> = self.accounts.iter()
+            .flat_map(|a| a.transactions.iter().map(|t| (t.timestamp, t.signed_amount())))
+            .collect();
+        events.sort_by_key(|(timestamp, _)| *timestamp);
+
+        let mut worth: Money = self.accounts.iter().map(|a| a.starting_balance).sum::<Money>() + self.brokerage_value();
+        let mut history: Vec<(NaiveDate, Money)> = Vec::new();
+        for (timestamp, change) in events {
+            worth += change;
+            let day = timestamp.date_naive();
+            match history.last_mut() {
+                Some((last, value)) if *last == day => *value = worth,
+                _ => history.push((day, worth)),
+            }
+        }
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn person() -> Person {
+        let mut person = Person::new("Stephen");
+        let mut checking = BankAccount::new_checking("Checking", Money::dollars(1000.0), 0.5, Money::dollars(500.0), Money::dollars(25.0));
+        let mut savings = BankAccount::new_checking("Savings", Money::dollars(5000.0), 4.0, Money::dollars(0.0), Money::dollars(0.0));
+        checking.deposit(Money::dollars(200.0), Some("paycheck")).unwrap();
+        checking.transfer(&mut savings, Money::dollars(300.0)).unwrap();
+        savings.withdraw(Money::dollars(50.0), None).unwrap();
+        person.accounts = vec![checking, savings];
+        person.holdings = vec![
+            Holding { symbol: "VTI".to_string(), shares: 10.0, price: Money::dollars(250.0) },
+            Holding { symbol: "BND".to_string(), shares: 12.5, price: Money::dollars(80.0) },
+        ];
+        person
+    }
+
+    #[test]
+    fn totals_and_allocation() {
+        let person = person();
+        assert_eq!(person.cash(), Money::dollars(6150.0));
+        assert_eq!(person.brokerage_value(), Money::dollars(3500.0));
+        assert_eq!(person.net_worth(), Money::dollars(9650.0));
+
+        let allocation = person.allocation();
+        assert_eq!(allocation[0].label, "VTI");
+        assert!((allocation[0].percent - 71.43).abs() < 0.01);
+        assert!((allocation.iter().map(|s| s.percent).sum::<f64>() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn history_replays_transactions() {
+        let mut person = person();
+        // pin the timestamps to two days so the daily grouping is predictable
+        let day = |d| Utc.with_ymd_and_hms(2025, 1, d, 12, 0, 0).unwrap();
+        for (i, t) in person.accounts[0].transactions.iter_mut().enumerate() {
+            t.timestamp = day(1) + chrono::Duration::minutes(i as i64);
+        }
+        for t in person.accounts[1].transactions.iter_mut() {
+            t.timestamp = day(2);
+        }
+
+        let history = person.net_worth_history();
+        let values: Vec<Money> = history.iter().map(|(_, v)| *v).collect();
+        // +200 deposit and -300 transfer out on day 1, +300 transfer in and -50 on day 2
+        assert_eq!(values, vec![Money::dollars(9400.0), Money::dollars(9650.0)]);
+        assert_eq!(history.last().unwrap().1, person.net_worth());
+        assert_eq!(person.recent_transactions(1)[0].0, "Savings");
+    }
+}
@@ -1,43 +1,430 @@
-fn binsearch(arr: &[i32], val: i32) -> Option<usize> {
-    //accepts an array of ints and a value to search for. returns index of said value.
-    if arr.is_empty() {
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+use std::time::Instant;
+
+mod sorted_vec;
+
+use sorted_vec::SortedVec;
+
+fn binsearch<T: Ord>(arr: &[T], val: &T) -> Result<usize, usize> {
+    // accepts a sorted slice and a value to search for. returns Ok(index) of said value,
+    // or Err(index) where it could be inserted to keep the slice sorted
+    binsearch_by(arr, |probe| probe.cmp(val))
+}
+
+fn binsearch_by<T, F: FnMut(&T) -> Ordering>(arr: &[T], mut f: F) -> Result<usize, usize> {
+    // f says how a probed element compares to the target: Less means the target is further right
+    let mut low = 0;
+    let mut high = arr.len(); // search the half-open range low..high, so high never underflows
+    while low < high {
+        let mid = low + (high-low)/2; // avoids risk of integer overflow
+        match f(&arr[mid]) {
+            Ordering::Equal => return Ok(mid),
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+        }
+    }
+    Err(low)
+}
+
+fn binsearch_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &[T], key: &K, mut f: F) -> Result<usize, usize> {
+    // arr must be sorted by the key f extracts, e.g. payments sorted by date searched by date
+    binsearch_by(arr, |probe| f(probe).cmp(key))
+}
+
+fn binsearch_in_range<T: Ord, R: RangeBounds<usize>>(arr: &[T], range: R, val: &T) -> Result<usize, usize> {
+    // searches only arr[range], but Ok/Err indices are positions in the whole of arr.
+    // panics like slicing does if the range is out of bounds
+    let low = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i + 1,
+        Bound::Unbounded => 0,
+    };
+    let high = match range.end_bound() {
+        Bound::Included(&i) => i + 1,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => arr.len(),
+    };
+    match binsearch(&arr[low..high], val) {
+        Ok(i) => Ok(low + i),
+        Err(i) => Err(low + i),
+    }
+}
+
+fn nearest_index(arr: &[f64], val: f64) -> Option<usize> {
+    // index of the element closest to val in a sorted slice; ties go to the lower index.
+    // None for an empty slice or a NaN val
+    if arr.is_empty() || val.is_nan() {
         return None;
     }
+    let i = binsearch_by(arr, |probe| probe.total_cmp(&val)).unwrap_or_else(|i| i);
+    if i == 0 {
+        return Some(0);
+    }
+    if i == arr.len() {
+        return Some(arr.len() - 1);
+    }
+    // val sits between arr[i - 1] and arr[i]
+    if val - arr[i - 1] <= arr[i] - val { Some(i - 1) } else { Some(i) }
+}
+
+fn binsearch_approx(arr: &[f64], val: f64, epsilon: f64) -> Option<usize> {
+    // exact float equality rarely holds, so accept the closest element within epsilon
+    nearest_index(arr, val).filter(|&i| (arr[i] - val).abs() <= epsilon)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+fn binsearch_ordered<T: Ord>(arr: &[T], val: &T, order: SortOrder) -> Result<usize, usize> {
+    // for slices sorted either way; Err is still the index that keeps the slice in its order
+    match order {
+        SortOrder::Ascending => binsearch_by(arr, |probe| probe.cmp(val)),
+        SortOrder::Descending => binsearch_by(arr, |probe| val.cmp(probe)),
+    }
+}
+
+fn lower_bound<T: Ord>(arr: &[T], val: &T) -> usize {
+    // index of the first element >= val (arr.len() if there is none)
     let mut low = 0;
-    let mut high = arr.len() - 1;
-    while low <= high {
-        let mid = low + (high-low)/2; // avoids risk of integer overflow
-        if arr[mid] == val {
-            return Some(mid);
-        } else if arr[mid] < val {
+    let mut high = arr.len();
+    while low < high {
+        let mid = low + (high-low)/2;
+        if arr[mid] < *val {
             low = mid + 1;
         } else {
-            high = mid - 1;
+            high = mid;
         }
     }
-    None
+    low
+}
+
+fn upper_bound<T: Ord>(arr: &[T], val: &T) -> usize {
+    // index of the first element > val (arr.len() if there is none)
+    let mut low = 0;
+    let mut high = arr.len();
+    while low < high {
+        let mid = low + (high-low)/2;
+        if arr[mid] <= *val {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+fn binsearch_first<T: Ord>(arr: &[T], val: &T) -> Result<usize, usize> {
+    // like binsearch, but with duplicates always returns the first matching index
+    let i = lower_bound(arr, val);
+    if i < arr.len() && arr[i] == *val { Ok(i) } else { Err(i) }
+}
+
+fn binsearch_last<T: Ord>(arr: &[T], val: &T) -> Result<usize, usize> {
+    // like binsearch, but with duplicates always returns the last matching index
+    let i = upper_bound(arr, val);
+    if i > 0 && arr[i - 1] == *val { Ok(i - 1) } else { Err(i) }
+}
+
+fn count_occurrences<T: Ord>(arr: &[T], val: &T) -> usize {
+    upper_bound(arr, val) - lower_bound(arr, val)
+}
+
+fn exponential_search<T: Ord>(arr: &[T], val: &T) -> Result<usize, usize> {
+    // doubles a bound until it passes val, then binary searches the last doubling step.
+    // cost depends on where val is, not on arr.len(), so it suits data of unknown or unbounded size
+    if arr.is_empty() {
+        return Err(0);
+    }
+    let mut bound = 1;
+    while bound < arr.len() && arr[bound] < *val {
+        bound *= 2;
+    }
+    let low = bound / 2;
+    let high = (bound + 1).min(arr.len());
+    match binsearch(&arr[low..high], val) {
+        Ok(i) => Ok(low + i),
+        Err(i) => Err(low + i),
+    }
+}
+
+fn interpolation_search(arr: &[i64], val: i64) -> Result<usize, usize> {
+    // guesses the position from the value, assuming keys are roughly evenly spread.
+    // about O(log log n) probes on uniform data, but O(n) on badly skewed data
+    let mut low = 0;
+    let mut high = arr.len();
+    while low < high {
+        let (low_val, high_val) = (arr[low], arr[high - 1]);
+        if val < low_val {
+            return Err(low);
+        }
+        if val > high_val {
+            return Err(high);
+        }
+        if low_val == high_val {
+            return Ok(low); // everything left equals val
+        }
+        // i128 so the span and the product can't overflow
+        let offset = (val as i128 - low_val as i128) * (high - 1 - low) as i128
+            / (high_val as i128 - low_val as i128);
+        let pos = low + offset as usize;
+        if arr[pos] == val {
+            return Ok(pos);
+        } else if arr[pos] < val {
+            low = pos + 1;
+        } else {
+            high = pos;
+        }
+    }
+    Err(low)
+}
+
+fn scattered(len: usize, seed: u64, below: u64) -> Vec<i64> {
+    // len values spread over 0..below by a splitmix64 stream; the same seed gives the same values
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            ((z ^ (z >> 31)) % below) as i64
+        })
+        .collect()
+}
+
+fn benchmark_search(name: &str, search: impl Fn(i64) -> Result<usize, usize>, queries: &[i64]) {
+    let start = Instant::now();
+    let found = queries.iter().filter(|&&q| search(q).is_ok()).count();
+    let elapsed = start.elapsed();
+    println!("{:>13} | {} lookups | {:>7} found | {:?}", name, queries.len(), found, elapsed);
 }
 
 fn main() {
     let a = [1, 2, 3, 4, 5, 6, 10, 100, 101, 120, 155, 10001];
 
-    let cases = [6, 120, 155, 2, 7]; // 7 isn't in list for testing purposes
+    let cases = [6, 120, 155, 2, 7, 0, 20000]; // 7, 0 and 20000 aren't in list for testing purposes
 
-    for &val in &cases {
+    for val in &cases {
         match binsearch(&a, val) {
-            Some(i) => {
+            Ok(i) => {
                 println!("Found {} at index {}", val, i);
-                assert_eq!(a[i],val);
+                assert_eq!(a[i], *val);
             },
-            None => println!("{} not found in array", val),
+            Err(i) => println!("{} not found in array, would be inserted at index {}", val, i),
         }
     }
 
     //test on empty list
     let empty: [i32; 0] = [];
-    match binsearch(&empty, 5) {
-        Some(i) => println!("Found 5 at index {}", i),
-        None => println!("5 not found in empty array, as expected"),
+    match binsearch(&empty, &5) {
+        Ok(i) => println!("Found 5 at index {}", i),
+        Err(_) => println!("5 not found in empty array, as expected"),
+    }
+
+    // with duplicates, binsearch may land on any copy; first/last are deterministic
+    let dups = [1, 2, 2, 2, 2, 3, 5, 5, 8];
+    for val in [2, 5, 4] {
+        println!("{}: first {:?}, last {:?}, count {}",
+            val, binsearch_first(&dups, &val), binsearch_last(&dups, &val), count_occurrences(&dups, &val));
+    }
+
+    // exponential search only looks as far as it needs to
+    println!("exponential search for 101: {:?}", exponential_search(&a, &101));
+    println!("interpolation search for 101: {:?}", interpolation_search(&a.map(|v| v as i64), 101));
+
+    // roughly uniform keys: the case interpolation search is built for
+    let n = 1_000_000;
+    let noise = scattered(n, 42, 3);
+    let large: Vec<i64> = (0..n).map(|i| i as i64 * 3 + noise[i]).collect();
+    let queries = scattered(n, 7, 3 * n as u64);
+    benchmark_search("binary", |q| binsearch(&large, &q), &queries);
+    benchmark_search("exponential", |q| exponential_search(&large, &q), &queries);
+    benchmark_search("interpolation", |q| interpolation_search(&large, q), &queries);
+    benchmark_search("std", |q| large.binary_search(&q), &queries);
+
+    // queries near the front are where exponential search shines
+    let near_front: Vec<i64> = queries.iter().map(|q| q % 1000).collect();
+    benchmark_search("binary", |q| binsearch(&large, &q), &near_front);
+    benchmark_search("exponential", |q| exponential_search(&large, &q), &near_front);
+
+    // searching a window of the slice still reports positions in the full slice
+    println!("100 within a[6..10]: {:?}", binsearch_in_range(&a, 6..10, &100));
+    println!("100 within a[..5]: {:?}", binsearch_in_range(&a, ..5, &100));
+
+    // 0.1 + 0.2 isn't exactly 0.3, so search with a tolerance
+    let floats = [0.1, 0.2, 0.3, 0.5, 0.8];
+    println!("0.1 + 0.2 within 1e-9: {:?}", binsearch_approx(&floats, 0.1 + 0.2, 1e-9));
+    println!("nearest to 0.65: {:?}", nearest_index(&floats, 0.65));
+
+    // descending slices and struct fields
+    let desc = [90, 70, 70, 40, 10];
+    for order in [SortOrder::Ascending, SortOrder::Descending] {
+        println!("40 in descending list searched as {:?}: {:?}", order, binsearch_ordered(&desc, &40, order));
+    }
+    let people = [("ann", 31), ("bob", 45), ("cy", 52)];
+    println!("age 45: {:?}", binsearch_by(&people, |p| p.1.cmp(&45)));
+    println!("age 52: {:?}", binsearch_by_key(&people, &52, |p| p.1));
+
+    // a sorted set built on the insertion point
+    let mut primes: SortedVec<u32> = [7, 2, 11, 3].into_iter().collect();
+    primes.insert(5);
+    let odds: SortedVec<u32> = (1..12).step_by(2).collect();
+    println!("primes {:?}, odd primes {:?}, primes in 3..8 {:?}",
+        primes.as_slice(), primes.intersection(&odds).as_slice(), primes.range(3..8));
+    primes.remove(&2);
+    let merged = primes.union(&odds);
+    println!("odd primes and odds together: {:?} (empty: {})",
+        merged.iter().collect::<Vec<_>>(), merged.is_empty());
+
+    // works for anything Ord, not just i32
+    let words = ["apple", "banana", "cherry", "plum"];
+    match binsearch(&words, &"fig") {
+        Ok(i) => println!("Found fig at index {}", i),
+        Err(i) => println!("fig not found, would be inserted at index {}", i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_present_values() {
+        let a = [1, 2, 3, 4, 5, 6, 10, 100, 101, 120, 155, 10001];
+        for (i, val) in a.iter().enumerate() {
+            assert_eq!(binsearch(&a, val), Ok(i));
+        }
+    }
+
+    #[test]
+    fn insertion_point_matches_std() {
+        let a = [1, 2, 3, 4, 5, 6, 10, 100, 101, 120, 155, 10001];
+        for val in [-5, 0, 7, 50, 150, 20000] {
+            assert_eq!(binsearch(&a, &val), a.binary_search(&val));
+        }
+        assert_eq!(binsearch(&[] as &[i32], &5), Err(0));
+    }
+
+    #[test]
+    fn first_and_last_occurrence() {
+        let dups = [1, 2, 2, 2, 2, 3, 5, 5, 8];
+        assert_eq!(binsearch_first(&dups, &2), Ok(1));
+        assert_eq!(binsearch_last(&dups, &2), Ok(4));
+        assert_eq!(binsearch_first(&dups, &8), Ok(8));
+        assert_eq!(binsearch_last(&dups, &1), Ok(0));
+        assert_eq!(binsearch_first(&dups, &4), Err(6));
+        assert_eq!(binsearch_last(&dups, &4), Err(6));
+        assert_eq!(binsearch_last(&dups, &0), Err(0));
+        assert_eq!(binsearch_first(&dups, &9), Err(9));
+        assert_eq!(count_occurrences(&dups, &2), 4);
+        assert_eq!(count_occurrences(&dups, &4), 0);
     }
 
+    #[test]
+    fn exponential_and_interpolation_match_binsearch() {
+        let a: Vec<i64> = vec![-40, -3, 0, 2, 2, 9, 15, 16, 100, 1000, i64::MAX];
+        for val in [-50, -40, -3, 1, 9, 10, 16, 99, 1000, 5000, i64::MAX, i64::MIN] {
+            let expected = binsearch(&a, &val);
+            assert_eq!(exponential_search(&a, &val).is_ok(), expected.is_ok(), "{val}");
+            assert_eq!(interpolation_search(&a, val).is_ok(), expected.is_ok(), "{val}");
+            if let Ok(i) = exponential_search(&a, &val) {
+                assert_eq!(a[i], val);
+            }
+            if let Ok(i) = interpolation_search(&a, val) {
+                assert_eq!(a[i], val);
+            }
+            if expected.is_err() {
+                assert_eq!(exponential_search(&a, &val), expected);
+                assert_eq!(interpolation_search(&a, val), expected);
+            }
+        }
+        assert_eq!(exponential_search(&[] as &[i64], &1), Err(0));
+        assert_eq!(interpolation_search(&[], 1), Err(0));
+    }
+
+    #[test]
+    fn range_restricted() {
+        let a = [1, 2, 3, 4, 5, 6, 10, 100, 101, 120, 155, 10001];
+        assert_eq!(binsearch_in_range(&a, 6..10, &100), Ok(7));
+        assert_eq!(binsearch_in_range(&a, 6..=7, &100), Ok(7));
+        assert_eq!(binsearch_in_range(&a, ..5, &100), Err(5));
+        assert_eq!(binsearch_in_range(&a, 8.., &2), Err(8));
+        assert_eq!(binsearch_in_range(&a, .., &155), Ok(10));
+        assert_eq!(binsearch_in_range(&a, 4..4, &5), Err(4));
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_out_of_bounds_panics() {
+        let a = [1, 2, 3];
+        let _ = binsearch_in_range(&a, 1..10, &2);
+    }
+
+    #[test]
+    fn approximate_float_search() {
+        let floats = [0.1, 0.2, 0.3, 0.5, 0.8];
+        assert_eq!(binsearch_approx(&floats, 0.1 + 0.2, 1e-9), Some(2));
+        assert_eq!(binsearch_approx(&floats, 0.4, 0.05), None);
+        assert_eq!(binsearch_approx(&[1.0, 2.0, 3.0], 2.5, 0.5), Some(1)); // tie goes low
+        assert_eq!(nearest_index(&floats, 0.66), Some(4));
+        assert_eq!(nearest_index(&floats, -3.0), Some(0));
+        assert_eq!(nearest_index(&floats, 9.0), Some(4));
+        assert_eq!(nearest_index(&floats, f64::NAN), None);
+        assert_eq!(nearest_index(&[], 1.0), None);
+    }
+
+    #[test]
+    fn descending_order() {
+        let desc = [90, 70, 40, 10];
+        assert_eq!(binsearch_ordered(&desc, &40, SortOrder::Descending), Ok(2));
+        assert_eq!(binsearch_ordered(&desc, &100, SortOrder::Descending), Err(0));
+        assert_eq!(binsearch_ordered(&desc, &50, SortOrder::Descending), Err(2));
+        assert_eq!(binsearch_ordered(&desc, &5, SortOrder::Descending), Err(4));
+        assert_eq!(binsearch_ordered(&[10, 40], &40, SortOrder::Ascending), Ok(1));
+    }
+
+    #[test]
+    fn custom_comparator() {
+        let people = [("ann", 31), ("bob", 45), ("cy", 52)];
+        assert_eq!(binsearch_by(&people, |p| p.1.cmp(&52)), Ok(2));
+        assert_eq!(binsearch_by(&people, |p| p.1.cmp(&20)), Err(0));
+        assert_eq!(binsearch_by(&people, |p| p.0.cmp("bob")), Ok(1));
+    }
+
+    #[test]
+    fn generic_over_ord() {
+        let words = ["apple", "banana", "cherry", "plum"];
+        assert_eq!(binsearch(&words, &"cherry"), Ok(2));
+        assert_eq!(binsearch(&words, &"aardvark"), Err(0));
+        assert_eq!(binsearch(&words, &"fig"), Err(3));
+    }
+
+    #[test]
+    fn by_key_searches_struct_field() {
+        #[derive(Debug)]
+        struct Payment {
+            day: u32,
+            amount: f64,
+        }
+        let payments: Vec<Payment> = [3, 10, 10, 17, 24]
+            .iter()
+            .map(|&day| Payment { day, amount: 100.0 })
+            .collect();
+        assert_eq!(binsearch_by_key(&payments, &17, |p| p.day), Ok(3));
+        assert_eq!(binsearch_by_key(&payments, &11, |p| p.day), Err(3));
+        assert_eq!(binsearch_by_key(&payments, &1, |p| p.day), Err(0));
+        let i = binsearch_by_key(&payments, &10, |p| p.day).unwrap();
+        assert!(i == 1 || i == 2);
+        assert_eq!(payments[i].amount, 100.0);
+        for day in 0..30 {
+            assert_eq!(
+                binsearch_by_key(&payments, &day, |p| p.day).is_ok(),
+                payments.binary_search_by_key(&day, |p| p.day).is_ok()
+            );
+        }
+    }
 }
@@ -0,0 +1,169 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::{binsearch, lower_bound, upper_bound};
+
+// a Vec kept sorted and free of duplicates, so it works as a simple ordered set.
+// lookups are O(log n); insert and remove are O(n) for the shift but find their spot in O(log n)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortedVec<T> {
+    items: Vec<T>,
+}
+
+impl<T: Ord> SortedVec<T> {
+    pub fn new() -> Self {
+        SortedVec { items: Vec::new() }
+    }
+
+    pub fn from_vec(mut items: Vec<T>) -> Self {
+        items.sort();
+        items.dedup();
+        SortedVec { items }
+    }
+
+    pub fn insert(&mut self, val: T) -> bool {
+        // returns false (and leaves the set unchanged) if val was already present
+        match binsearch(&self.items, &val) {
+            Ok(_) => false,
+            Err(i) => {
+                self.items.insert(i, val);
+                true
+            }
+        }
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        binsearch(&self.items, val).is_ok()
+    }
+
+    pub fn remove(&mut self, val: &T) -> bool {
+        match binsearch(&self.items, val) {
+            Ok(i) => {
+                self.items.remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> &[T] {
+        // the elements falling inside range, e.g. set.range(3..7) or set.range(..=10)
+        let low = match range.start_bound() {
+            Bound::Included(v) => lower_bound(&self.items, v),
+            Bound::Excluded(v) => upper_bound(&self.items, v),
+            Bound::Unbounded => 0,
+        };
+        let high = match range.end_bound() {
+            Bound::Included(v) => upper_bound(&self.items, v),
+            Bound::Excluded(v) => lower_bound(&self.items, v),
+            Bound::Unbounded => self.items.len(),
+        };
+        if low >= high { &[] } else { &self.items[low..high] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+}
+
+impl<T: Ord + Clone> SortedVec<T> {
+    pub fn union(&self, other: &Self) -> Self {
+        // linear merge of the two sorted vecs, taking equal values once
+        let (a, b) = (&self.items, &other.items);
+        let mut items = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] < b[j] {
+                items.push(a[i].clone());
+                i += 1;
+            } else if b[j] < a[i] {
+                items.push(b[j].clone());
+                j += 1;
+            } else {
+                items.push(a[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+        items.extend_from_slice(&a[i..]);
+        items.extend_from_slice(&b[j..]);
+        SortedVec { items }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        // walks the smaller set and binary searches the larger one
+        let (small, large) = if self.len() <= other.len() { (self, other) } else { (other, self) };
+        let items = small.items.iter().filter(|v| large.contains(v)).cloned().collect();
+        SortedVec { items }
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SortedVec::from_vec(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SortedVec;
+
+    #[test]
+    fn insert_keeps_order_and_rejects_duplicates() {
+        let mut set = SortedVec::new();
+        assert!(set.insert(5));
+        assert!(set.insert(1));
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert_eq!(set.as_slice(), &[1, 3, 5]);
+        assert!(set.contains(&3));
+        assert!(!set.contains(&4));
+    }
+
+    #[test]
+    fn remove() {
+        let mut set: SortedVec<i32> = [4, 2, 8].into_iter().collect();
+        assert!(set.remove(&4));
+        assert!(!set.remove(&4));
+        assert_eq!(set.as_slice(), &[2, 8]);
+    }
+
+    #[test]
+    fn range() {
+        let set = SortedVec::from_vec(vec![1, 3, 5, 7, 9, 11]);
+        assert_eq!(set.range(3..9), &[3, 5, 7]);
+        assert_eq!(set.range(3..=9), &[3, 5, 7, 9]);
+        assert_eq!(set.range(4..), &[5, 7, 9, 11]);
+        assert_eq!(set.range(..=1), &[1]);
+        assert_eq!(set.range(12..), &[] as &[i32]);
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = set.range(9..3);
+        assert_eq!(backwards, &[] as &[i32]);
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        let a = SortedVec::from_vec(vec![1, 2, 3, 5, 8]);
+        let b = SortedVec::from_vec(vec![2, 3, 4, 8, 13]);
+        assert_eq!(a.union(&b).as_slice(), &[1, 2, 3, 4, 5, 8, 13]);
+        assert_eq!(a.intersection(&b).as_slice(), &[2, 3, 8]);
+        assert!(a.intersection(&SortedVec::new()).is_empty());
+    }
+}
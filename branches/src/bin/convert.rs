@@ -0,0 +1,44 @@
+// usage: convert <quantity> <unit>, e.g. convert 100F C, convert "5 mi" km, convert 10lb kg
+use std::{env, process};
+
+use branches::units::{Length, Mass, ParseError, Temperature};
+
+fn describe(error: ParseError, kind: &str) -> String {
+    match error {
+        ParseError::UnknownUnit(unit) => format!("'{}' is not a unit of {}", unit, kind),
+        error => error.to_string(),
+    }
+}
+
+fn convert(quantity: &str, unit: &str) -> Result<String, String> {
+    // whichever kind of measurement the target unit belongs to decides how the quantity is read
+    if let Ok(unit) = unit.parse() {
+        let temperature: Temperature = quantity.parse().map_err(|e| describe(e, "temperature"))?;
+        return Ok(format!("{} = {:.2}", temperature, temperature.to(unit)));
+    }
+    if let Ok(unit) = unit.parse() {
+        let length: Length = quantity.parse().map_err(|e| describe(e, "length"))?;
+        return Ok(format!("{} = {:.4}", length, length.to(unit)));
+    }
+    if let Ok(unit) = unit.parse() {
+        let mass: Mass = quantity.parse().map_err(|e| describe(e, "mass"))?;
+        return Ok(format!("{} = {:.4}", mass, mass.to(unit)));
+    }
+    Err(format!("unknown unit '{}'", unit))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <quantity> <unit>", args[0]);
+        process::exit(1);
+    }
+
+    match convert(&args[1], &args[2]) {
+        Ok(result) => println!("{}", result),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+}
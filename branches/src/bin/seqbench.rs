@@ -0,0 +1,42 @@
+// times the lazy sequence iterators against the naive recursive versions.
+// run with --release for meaningful numbers
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use branches::{collatz, fibonacci, naive, primes::Primes};
+
+fn time<T>(runs: u32, mut f: impl FnMut() -> T) -> Duration {
+    // the best of several runs, which is the least disturbed by everything else on the machine
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn report(name: &str, naive: Duration, fast: Duration) {
+    let speedup = naive.as_secs_f64() / fast.as_secs_f64().max(f64::EPSILON);
+    println!("{:<28} {:>12.3?} {:>12.3?} {:>10.1}x", name, naive, fast, speedup);
+}
+
+fn main() {
+    println!("{:<28} {:>12} {:>12} {:>11}", "benchmark", "naive", "iterator", "speedup");
+
+    let n = 32;
+    report(&format!("fibonacci({n})"),
+        time(3, || naive::fib_recursive(black_box(n))),
+        time(3, || fibonacci::fib(black_box(n))));
+
+    let count = 2_000;
+    report(&format!("first {count} primes"),
+        time(3, || naive::primes_trial_division(black_box(count))),
+        time(3, || Primes::new().take(black_box(count)).collect::<Vec<_>>()));
+
+    let limit = 300_000;
+    report(&format!("longest collatz < {limit}"),
+        time(3, || (1..black_box(limit)).map(|n| (naive::collatz_steps_recursive(n), n)).max()),
+        time(3, || collatz::longest_below(black_box(limit))));
+}
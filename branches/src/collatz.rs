@@ -0,0 +1,70 @@
+// collatz trajectories: halve even numbers, 3n + 1 odd ones, stop at 1
+// the numbers visited from start down to 1, inclusive. ends early rather than overflow
+#[derive(Debug, Clone)]
+pub struct Collatz {
+    next: Option<u64>,
+}
+
+impl Collatz {
+    pub fn new(start: u64) -> Self {
+        // 0 never reaches 1, so it has no trajectory
+        Collatz { next: if start == 0 { None } else { Some(start) } }
+    }
+}
+
+impl Iterator for Collatz {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        self.next = match current {
+            1 => None,
+            n if n.is_multiple_of(2) => Some(n / 2),
+            n => n.checked_mul(3).and_then(|n| n.checked_add(1)),
+        };
+        Some(current)
+    }
+}
+
+pub fn steps(start: u64) -> usize {
+    // how many moves it takes to reach 1
+    Collatz::new(start).count().saturating_sub(1)
+}
+
+pub fn longest_below(limit: u64) -> Option<(u64, usize)> {
+    // the start under limit with the most steps. remembers the length for every start below
+    // limit, since each trajectory soon drops under its start into one already worked out
+    let mut known = vec![0usize; limit as usize];
+    let mut best: Option<(u64, usize)> = None;
+    for start in 1..limit {
+        let mut n = start;
+        let mut length = 0;
+        while n != 1 && (n >= start || n >= limit) {
+            n = if n.is_multiple_of(2) { n / 2 } else { 3 * n + 1 };
+            length += 1;
+        }
+        length += known[n as usize];
+        known[start as usize] = length;
+        if best.is_none_or(|(_, most)| length > most) {
+            best = Some((start, length));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trajectories() {
+        let from_six: Vec<u64> = Collatz::new(6).collect();
+        assert_eq!(from_six, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        assert_eq!(steps(27), 111);
+        assert_eq!(Collatz::new(27).max(), Some(9232));
+        assert_eq!(steps(1), 0);
+        assert_eq!(Collatz::new(0).count(), 0);
+        assert_eq!(longest_below(10), Some((9, 19)));
+        assert_eq!(longest_below(1_000_000), Some((837_799, 524)));
+    }
+}
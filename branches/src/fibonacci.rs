@@ -0,0 +1,62 @@
+// fibonacci numbers without the exponential recursion. everything is u128, and anything
+// past F(186) doesn't fit, so those come back as None instead of wrapping or panicking
+
+// the largest n whose fibonacci number fits in a u128
+pub const MAX_N: u32 = 186;
+
+pub fn fib(n: u32) -> Option<u128> {
+    // F(0) = 0, F(1) = F(2) = 1
+    Fibonacci::new().nth(usize::try_from(n).ok()?)
+}
+
+// the sequence 0, 1, 1, 2, 3, 5, ... ending at the last value that fits in a u128
+#[derive(Debug, Clone)]
+pub struct Fibonacci {
+    current: Option<u128>,
+    next: Option<u128>,
+}
+
+impl Fibonacci {
+    pub fn new() -> Self {
+        Fibonacci { current: Some(0), next: Some(1) }
+    }
+}
+
+impl Default for Fibonacci {
+    fn default() -> Self {
+        Fibonacci::new()
+    }
+}
+
+impl Iterator for Fibonacci {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        let current = self.current?;
+        let following = self.next.and_then(|next| next.checked_add(current));
+        self.current = self.next;
+        self.next = following;
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_values() {
+        let first: Vec<u128> = Fibonacci::new().take(10).collect();
+        assert_eq!(first, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(fib(1), Some(1));
+        assert_eq!(fib(2), Some(1));
+        assert_eq!(fib(50), Some(12_586_269_025));
+    }
+
+    #[test]
+    fn stops_at_overflow() {
+        assert_eq!(fib(MAX_N), Some(332_825_110_087_067_562_321_196_029_789_634_457_848));
+        assert_eq!(fib(MAX_N + 1), None);
+        assert_eq!(Fibonacci::new().count(), MAX_N as usize + 1);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod fibonacci;
+pub mod units;
+pub mod primes;
+pub mod collatz;
+pub mod naive;
@@ -1,34 +1,23 @@
-fn main() {
+use branches::fibonacci::{fib, Fibonacci, MAX_N};
+use branches::units::{Temperature, TemperatureUnit};
 
-    for i in 1..10 {
-        let fib = fib(i);
+fn main() {
 
-        println!("The {i} finonacci number is {fib}");
+    for (i, fib) in Fibonacci::new().enumerate().skip(1).take(9) {
+        println!("The {i} fibonacci number is {fib}");
     }
-    let f = 100.0;
-    let c = f_to_c(f);
-    println!("{f} F is {c} C");
-
-    let c2 = 40.0;
-    let f2 = c_to_f(c2);
-    println!("{c2} C is {f2} F");
-}
 
-fn fib(n: i32) -> i32 {
-    if n == 1 {
-        1
-    } else if n == 2 {
-        1
-    } else {
-        fib(n-1) + fib(n-2)
+    for n in [100, MAX_N, MAX_N + 1] {
+        match fib(n) {
+            Some(fib) => println!("The {n} fibonacci number is {fib}"),
+            None => println!("The {n} fibonacci number doesn't fit in a u128"),
+        }
     }
-}
+    let f = Temperature::fahrenheit(100.0);
+    let c = f.to(TemperatureUnit::Celsius);
+    println!("{f} is {c:.2}");
 
-fn f_to_c(f: f32) -> f32 {
-    //C = (F - 32) × 5/9
-    (f - 32.0) * (5.0/9.0)
+    let c2 = Temperature::celsius(40.0);
+    let f2 = c2.to(TemperatureUnit::Fahrenheit);
+    println!("{c2} is {f2}");
 }
-
-fn c_to_f(c: f32) -> f32 {
-    (9.0/5.0)*c + 32.0
-}
\ No newline at end of file
@@ -0,0 +1,47 @@
+// the textbook versions, kept to check and benchmark the fast ones against
+
+pub fn fib_recursive(n: u32) -> u128 {
+    match n {
+        0 => 0,
+        1 | 2 => 1,
+        n => fib_recursive(n - 1) + fib_recursive(n - 2),
+    }
+}
+
+pub fn primes_trial_division(count: usize) -> Vec<u64> {
+    // the first count primes, checking each candidate against every smaller number
+    let mut primes = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if (2..candidate).all(|d| candidate % d != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+pub fn collatz_steps_recursive(n: u64) -> usize {
+    match n {
+        0 | 1 => 0,
+        n if n % 2 == 0 => 1 + collatz_steps_recursive(n / 2),
+        n => 1 + collatz_steps_recursive(3 * n + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collatz, fibonacci, primes::Primes};
+
+    #[test]
+    fn match_the_fast_versions() {
+        for n in 0..25 {
+            assert_eq!(Some(fib_recursive(n)), fibonacci::fib(n));
+        }
+        assert_eq!(primes_trial_division(200), Primes::new().take(200).collect::<Vec<_>>());
+        for n in 1..500 {
+            assert_eq!(collatz_steps_recursive(n), collatz::steps(n));
+        }
+    }
+}
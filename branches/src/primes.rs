@@ -0,0 +1,91 @@
+// primes from a sieve of Eratosthenes. Primes is lazy: it sieves a block, hands those
+// primes out, and sieves a block twice the size when it runs out
+
+pub fn sieve(limit: u64) -> Vec<u64> {
+    // every prime <= limit
+    if limit < 2 {
+        return Vec::new();
+    }
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut i = 2;
+    while i * i <= limit {
+        if !composite[i] {
+            for multiple in (i * i..=limit).step_by(i) {
+                composite[multiple] = true;
+            }
+        }
+        i += 1;
+    }
+    (2..=limit).filter(|&n| !composite[n]).map(|n| n as u64).collect()
+}
+
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    // 6k ± 1 trial division; fine for one-off checks
+    if n < 4 {
+        return true;
+    }
+    if n.is_multiple_of(2) || n.is_multiple_of(3) {
+        return false;
+    }
+    let mut i = 5;
+    while i * i <= n {
+        if n.is_multiple_of(i) || n.is_multiple_of(i + 2) {
+            return false;
+        }
+        i += 6;
+    }
+    true
+}
+
+#[derive(Debug, Clone)]
+pub struct Primes {
+    found: Vec<u64>,
+    index: usize,
+    limit: u64,
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Primes { found: Vec::new(), index: 0, limit: 0 }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Primes::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.index >= self.found.len() {
+            self.limit = (self.limit * 2).max(1024);
+            self.found = sieve(self.limit);
+        }
+        self.index += 1;
+        Some(self.found[self.index - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sieve_and_iterator_agree() {
+        assert_eq!(sieve(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(sieve(1).is_empty());
+        // the 10,000th prime is past the first block, so the iterator has to grow
+        assert_eq!(Primes::new().nth(9_999), Some(104_729));
+        let below: Vec<u64> = Primes::new().take_while(|&p| p < 5_000).collect();
+        assert_eq!(below, sieve(5_000));
+        assert!(below.iter().all(|&p| is_prime(p)));
+        assert_eq!((0..5_000).filter(|&n| is_prime(n)).count(), below.len());
+    }
+}
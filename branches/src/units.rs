@@ -0,0 +1,325 @@
+// typed measurements that know their unit, so a Celsius value can't be mixed up with a
+// Fahrenheit one. parse from strings like "100F" or "5.5 km" and convert with .to(unit)
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidNumber(String),
+    UnknownUnit(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "nothing to parse"),
+            ParseError::InvalidNumber(text) => write!(f, "'{}' is not a number", text),
+            ParseError::UnknownUnit(text) => write!(f, "unknown unit '{}'", text),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn split_quantity(s: &str) -> Result<(f64, &str), ParseError> {
+    // "100F" -> (100.0, "F"), "-3.5 km" -> (-3.5, "km")
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let split = s.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+')).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let value = number.parse().map_err(|_| ParseError::InvalidNumber(number.to_string()))?;
+    Ok((value, unit.trim()))
+}
+
+fn write_value(f: &mut fmt::Formatter<'_>, value: f64, symbol: &str) -> fmt::Result {
+    // a precision like {:.1} applies to the number
+    match f.precision() {
+        Some(precision) => write!(f, "{:.*}{}", precision, value, symbol),
+        None => write!(f, "{}{}", value, symbol),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+            TemperatureUnit::Kelvin => "K",
+        }
+    }
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().trim_start_matches('°').to_ascii_lowercase().as_str() {
+            "c" | "celsius" => Ok(TemperatureUnit::Celsius),
+            "f" | "fahrenheit" => Ok(TemperatureUnit::Fahrenheit),
+            "k" | "kelvin" => Ok(TemperatureUnit::Kelvin),
+            _ => Err(ParseError::UnknownUnit(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    value: f64,
+    unit: TemperatureUnit,
+}
+
+impl Temperature {
+    pub fn new(value: f64, unit: TemperatureUnit) -> Self {
+        Temperature { value, unit }
+    }
+
+    pub fn celsius(value: f64) -> Self {
+        Temperature::new(value, TemperatureUnit::Celsius)
+    }
+
+    pub fn fahrenheit(value: f64) -> Self {
+        Temperature::new(value, TemperatureUnit::Fahrenheit)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn unit(&self) -> TemperatureUnit {
+        self.unit
+    }
+
+    pub fn to(&self, unit: TemperatureUnit) -> Temperature {
+        // temperatures don't share a zero, so go through Celsius
+        let celsius = match self.unit {
+            TemperatureUnit::Celsius => self.value,
+            TemperatureUnit::Fahrenheit => (self.value - 32.0) * 5.0 / 9.0,
+            TemperatureUnit::Kelvin => self.value - 273.15,
+        };
+        let value = match unit {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => celsius + 273.15,
+        };
+        Temperature::new(value, unit)
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self.value, self.unit.symbol())
+    }
+}
+
+impl FromStr for Temperature {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s)?;
+        Ok(Temperature::new(value, unit.parse()?))
+    }
+}
+
+// a unit that's some fixed multiple of a base unit, like feet of meters
+pub trait LinearUnit: Copy + FromStr<Err = ParseError> {
+    fn symbol(&self) -> &'static str;
+    // how many base units one of this unit is
+    fn factor(&self) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    Kilometer,
+    Inch,
+    Foot,
+    Yard,
+    Mile,
+}
+
+impl LinearUnit for LengthUnit {
+    fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::Kilometer => "km",
+            LengthUnit::Inch => "in",
+            LengthUnit::Foot => "ft",
+            LengthUnit::Yard => "yd",
+            LengthUnit::Mile => "mi",
+        }
+    }
+
+    fn factor(&self) -> f64 {
+        // in meters
+        match self {
+            LengthUnit::Millimeter => 0.001,
+            LengthUnit::Centimeter => 0.01,
+            LengthUnit::Meter => 1.0,
+            LengthUnit::Kilometer => 1000.0,
+            LengthUnit::Inch => 0.0254,
+            LengthUnit::Foot => 0.3048,
+            LengthUnit::Yard => 0.9144,
+            LengthUnit::Mile => 1609.344,
+        }
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mm" | "millimeter" | "millimeters" => Ok(LengthUnit::Millimeter),
+            "cm" | "centimeter" | "centimeters" => Ok(LengthUnit::Centimeter),
+            "m" | "meter" | "meters" => Ok(LengthUnit::Meter),
+            "km" | "kilometer" | "kilometers" => Ok(LengthUnit::Kilometer),
+            "in" | "inch" | "inches" => Ok(LengthUnit::Inch),
+            "ft" | "foot" | "feet" => Ok(LengthUnit::Foot),
+            "yd" | "yard" | "yards" => Ok(LengthUnit::Yard),
+            "mi" | "mile" | "miles" => Ok(LengthUnit::Mile),
+            _ => Err(ParseError::UnknownUnit(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MassUnit {
+    Milligram,
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+}
+
+impl LinearUnit for MassUnit {
+    fn symbol(&self) -> &'static str {
+        match self {
+            MassUnit::Milligram => "mg",
+            MassUnit::Gram => "g",
+            MassUnit::Kilogram => "kg",
+            MassUnit::Ounce => "oz",
+            MassUnit::Pound => "lb",
+        }
+    }
+
+    fn factor(&self) -> f64 {
+        // in grams
+        match self {
+            MassUnit::Milligram => 0.001,
+            MassUnit::Gram => 1.0,
+            MassUnit::Kilogram => 1000.0,
+            MassUnit::Ounce => 28.349523125,
+            MassUnit::Pound => 453.59237,
+        }
+    }
+}
+
+impl FromStr for MassUnit {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mg" | "milligram" | "milligrams" => Ok(MassUnit::Milligram),
+            "g" | "gram" | "grams" => Ok(MassUnit::Gram),
+            "kg" | "kilogram" | "kilograms" => Ok(MassUnit::Kilogram),
+            "oz" | "ounce" | "ounces" => Ok(MassUnit::Ounce),
+            "lb" | "lbs" | "pound" | "pounds" => Ok(MassUnit::Pound),
+            _ => Err(ParseError::UnknownUnit(s.to_string())),
+        }
+    }
+}
+
+// a value in some LinearUnit. Length and Mass are the two kinds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measure<U: LinearUnit> {
+    value: f64,
+    unit: U,
+}
+
+pub type Length = Measure<LengthUnit>;
+pub type Mass = Measure<MassUnit>;
+
+impl<U: LinearUnit> Measure<U> {
+    pub fn new(value: f64, unit: U) -> Self {
+        Measure { value, unit }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn unit(&self) -> U {
+        self.unit
+    }
+
+    pub fn to(&self, unit: U) -> Self {
+        Measure::new(self.value * self.unit.factor() / unit.factor(), unit)
+    }
+}
+
+impl<U: LinearUnit> fmt::Display for Measure<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self.value, &format!(" {}", self.unit.symbol()))
+    }
+}
+
+impl<U: LinearUnit> FromStr for Measure<U> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s)?;
+        Ok(Measure::new(value, unit.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn temperatures() {
+        let boiling: Temperature = "212F".parse().unwrap();
+        assert!(close(boiling.to(TemperatureUnit::Celsius).value(), 100.0));
+        assert!(close(Temperature::celsius(-40.0).to(TemperatureUnit::Fahrenheit).value(), -40.0));
+        assert!(close("0 K".parse::<Temperature>().unwrap().to(TemperatureUnit::Celsius).value(), -273.15));
+        assert_eq!(format!("{:.1}", Temperature::fahrenheit(100.0).to(TemperatureUnit::Celsius)), "37.8°C");
+        assert_eq!(" -3.5 °c".parse::<Temperature>().unwrap(), Temperature::celsius(-3.5));
+    }
+
+    #[test]
+    fn lengths_and_masses() {
+        let marathon: Length = "26.2188 mi".parse().unwrap();
+        assert!((marathon.to(LengthUnit::Kilometer).value() - 42.195).abs() < 1e-3);
+        assert!(close(Length::new(1.0, LengthUnit::Foot).to(LengthUnit::Inch).value(), 12.0));
+        assert_eq!(format!("{:.2}", "10lb".parse::<Mass>().unwrap().to(MassUnit::Kilogram)), "4.54 kg");
+        assert!(close(Mass::new(1.0, MassUnit::Pound).to(MassUnit::Ounce).value(), 16.0));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Temperature>(), Err(ParseError::Empty));
+        assert_eq!("abc".parse::<Length>(), Err(ParseError::InvalidNumber(String::new())));
+        assert_eq!("1.2.3m".parse::<Length>(), Err(ParseError::InvalidNumber("1.2.3".to_string())));
+        assert_eq!("5 parsecs".parse::<Length>(), Err(ParseError::UnknownUnit("parsecs".to_string())));
+        // a mass unit isn't a length
+        assert!("5kg".parse::<Length>().is_err());
+    }
+}
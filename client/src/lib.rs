@@ -0,0 +1,182 @@
+// a blocking client for the webserver's json api. one method per endpoint, taking and
+// returning the same types the server uses
+use std::fmt;
+
+use api::{AccountDetail, AccountSummary, AccountUpdate, Amortization, AmortizationRequest, ApiError,
+    FutureValue, FutureValueRequest, NewAccount};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+pub enum ClientError {
+    // the request never got an answer, or the answer wasn't the expected json
+    Http(reqwest::Error),
+    // the server answered with an error status
+    Api { status: u16, message: String },
+    InvalidUrl(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Http(e) => write!(f, "request failed: {}", e),
+            ClientError::Api { status, message } => write!(f, "server returned {}: {}", status, message),
+            ClientError::InvalidUrl(url) => write!(f, "invalid server url: {}", url),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+pub struct Client {
+    base: Url,
+    http: reqwest::blocking::Client,
+}
+
+impl Client {
+    pub fn new(base_url: &str) -> Result<Self> {
+        let base = Url::parse(base_url).map_err(|_| ClientError::InvalidUrl(base_url.to_string()))?;
+        if base.cannot_be_a_base() {
+            return Err(ClientError::InvalidUrl(base_url.to_string()));
+        }
+        Ok(Client { base, http: reqwest::blocking::Client::new() })
+    }
+
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        // pushed as segments so account names with spaces or slashes are escaped
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .expect("checked in new")
+            .pop_if_empty()
+            .push("api")
+            .extend(segments);
+        self.http.request(method, url)
+    }
+
+    fn check(response: Response) -> Result<Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().unwrap_or_default();
+        let message = serde_json::from_str::<ApiError>(&text).map_or(text, |e| e.error);
+        Err(ClientError::Api { status: status.as_u16(), message })
+    }
+
+    fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        Ok(Client::check(request.send()?)?.json()?)
+    }
+
+    pub fn solve_fv(&self, request: &FutureValueRequest) -> Result<FutureValue> {
+        self.send(self.request(Method::POST, &["solve_fv"]).json(request))
+    }
+
+    pub fn amortization(&self, request: &AmortizationRequest) -> Result<Amortization> {
+        self.send(self.request(Method::POST, &["amortization"]).json(request))
+    }
+
+    pub fn accounts(&self) -> Result<Vec<AccountSummary>> {
+        self.send(self.request(Method::GET, &["accounts"]))
+    }
+
+    pub fn account(&self, name: &str) -> Result<AccountDetail> {
+        self.send(self.request(Method::GET, &["accounts", name]))
+    }
+
+    pub fn create_account(&self, account: &NewAccount) -> Result<AccountSummary> {
+        self.send(self.request(Method::POST, &["accounts"]).json(account))
+    }
+
+    pub fn update_account(&self, name: &str, update: &AccountUpdate) -> Result<AccountSummary> {
+        self.send(self.request(Method::PUT, &["accounts", name]).json(update))
+    }
+
+    pub fn delete_account(&self, name: &str) -> Result<()> {
+        Client::check(self.request(Method::DELETE, &["accounts", name]).send()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use chrono::NaiveDate;
+    use money::Money;
+
+    // answers one request with a canned response and hands back the request line and body
+    fn serve_once(status: &str, body: &str) -> (String, thread::JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, body.len(), body);
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            (request_line.trim_end().to_string(), String::from_utf8(body).unwrap())
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn sends_typed_requests() {
+        let (url, server) = serve_once("200 OK",
+            r#"{"monthly_payment":"599.55 USD","total_interest":"0.00 USD","total_paid":"100000.00 USD","payoff_date":"2055-02-01","payments":[]}"#);
+        let client = Client::new(&url).unwrap();
+        let request = AmortizationRequest {
+            principal: Money::dollars(100000.0),
+            annual_rate: 6.0,
+            years: 30,
+            extra_payment: Money::dollars(0.0),
+            start: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        };
+        let amortization = client.amortization(&request).unwrap();
+        assert_eq!(amortization.monthly_payment, Money::dollars(599.55));
+
+        let (request_line, body) = server.join().unwrap();
+        assert_eq!(request_line, "POST /api/amortization HTTP/1.1");
+        assert_eq!(serde_json::from_str::<AmortizationRequest>(&body).unwrap(), request);
+    }
+
+    #[test]
+    fn surfaces_server_errors() {
+        let (url, server) = serve_once("404 Not Found", r#"{"error":"no account named Rainy Day"}"#);
+        let client = Client::new(&format!("{}/", url)).unwrap();
+        match client.delete_account("Rainy Day") {
+            Err(ClientError::Api { status, message }) => {
+                assert_eq!(status, 404);
+                assert_eq!(message, "no account named Rainy Day");
+            }
+            other => panic!("expected an api error, got {:?}", other),
+        }
+        assert_eq!(server.join().unwrap().0, "DELETE /api/accounts/Rainy%20Day HTTP/1.1");
+        assert!(matches!(Client::new("not a url"), Err(ClientError::InvalidUrl(_))));
+    }
+}
@@ -0,0 +1,249 @@
+// the Shoe filter from the book grown into a small inventory: stock counts, predicates that
+// combine with and/or/not, sorting on several keys, and grouped counts
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Shoe {
+    pub size: u32,
+    pub style: String,
+    pub price_cents: u32,
+}
+
+impl Shoe {
+    pub fn new(size: u32, style: &str, price_cents: u32) -> Self {
+        Shoe { size, style: style.to_string(), price_cents }
+    }
+}
+
+// a test on an item, built from closures and combined into bigger tests
+pub struct Predicate<T> {
+    test: Box<dyn Fn(&T) -> bool>,
+}
+
+impl<T: 'static> Predicate<T> {
+    pub fn new(test: impl Fn(&T) -> bool + 'static) -> Self {
+        Predicate { test: Box::new(test) }
+    }
+
+    pub fn matches(&self, item: &T) -> bool {
+        (self.test)(item)
+    }
+
+    pub fn and(self, other: Predicate<T>) -> Self {
+        Predicate::new(move |item| self.matches(item) && other.matches(item))
+    }
+
+    pub fn or(self, other: Predicate<T>) -> Self {
+        Predicate::new(move |item| self.matches(item) || other.matches(item))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Predicate::new(move |item| !self.matches(item))
+    }
+}
+
+pub fn size(size: u32) -> Predicate<Shoe> {
+    Predicate::new(move |shoe: &Shoe| shoe.size == size)
+}
+
+pub fn style(style: &str) -> Predicate<Shoe> {
+    let style = style.to_string();
+    Predicate::new(move |shoe: &Shoe| shoe.style == style)
+}
+
+pub fn price_between(low_cents: u32, high_cents: u32) -> Predicate<Shoe> {
+    // inclusive at both ends
+    Predicate::new(move |shoe: &Shoe| (low_cents..=high_cents).contains(&shoe.price_cents))
+}
+
+type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+// an ordering built one key at a time: the first key decides, later keys break ties
+pub struct SortBy<T> {
+    keys: Vec<Comparator<T>>,
+}
+
+impl<T: 'static> SortBy<T> {
+    pub fn key<K: Ord>(key: impl Fn(&T) -> K + 'static) -> Self {
+        SortBy { keys: Vec::new() }.then(key)
+    }
+
+    pub fn then<K: Ord>(mut self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.keys.push(Box::new(move |a, b| key(a).cmp(&key(b))));
+        self
+    }
+
+    pub fn then_descending<K: Ord>(mut self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.keys.push(Box::new(move |a, b| key(b).cmp(&key(a))));
+        self
+    }
+
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.keys.iter().fold(Ordering::Equal, |ordering, key| ordering.then_with(|| key(a, b)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stock<T> {
+    pub item: T,
+    pub quantity: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InventoryError {
+    NotStocked,
+    Insufficient { requested: u32, available: u32 },
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::NotStocked => write!(f, "item is not stocked"),
+            InventoryError::Insufficient { requested, available } =>
+                write!(f, "requested {} but only {} in stock", requested, available),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory<T> {
+    stock: Vec<Stock<T>>,
+}
+
+impl<T> Default for Inventory<T> {
+    fn default() -> Self {
+        Inventory { stock: Vec::new() }
+    }
+}
+
+impl<T: PartialEq> Inventory<T> {
+    pub fn new() -> Self {
+        Inventory::default()
+    }
+
+    pub fn restock(&mut self, item: T, quantity: u32) -> u32 {
+        // adds to an existing line if there is one, returning the new count
+        match self.stock.iter_mut().find(|s| s.item == item) {
+            Some(stock) => {
+                stock.quantity += quantity;
+                stock.quantity
+            }
+            None => {
+                self.stock.push(Stock { item, quantity });
+                quantity
+            }
+        }
+    }
+
+    pub fn remove(&mut self, item: &T, quantity: u32) -> Result<u32, InventoryError> {
+        // takes quantity off the shelf, dropping the line once none are left
+        let index = self.stock.iter().position(|s| s.item == *item).ok_or(InventoryError::NotStocked)?;
+        let stock = &mut self.stock[index];
+        if quantity > stock.quantity {
+            return Err(InventoryError::Insufficient { requested: quantity, available: stock.quantity });
+        }
+        stock.quantity -= quantity;
+        let left = stock.quantity;
+        if left == 0 {
+            self.stock.remove(index);
+        }
+        Ok(left)
+    }
+
+    pub fn quantity(&self, item: &T) -> u32 {
+        self.stock.iter().find(|s| s.item == *item).map_or(0, |s| s.quantity)
+    }
+}
+
+impl<T: 'static> Inventory<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &Stock<T>> {
+        self.stock.iter()
+    }
+
+    pub fn total_units(&self) -> u32 {
+        self.stock.iter().map(|s| s.quantity).sum()
+    }
+
+    pub fn filter<'a>(&'a self, predicate: &'a Predicate<T>) -> impl Iterator<Item = &'a Stock<T>> {
+        self.stock.iter().filter(move |s| predicate.matches(&s.item))
+    }
+
+    pub fn sorted(&self, order: &SortBy<T>) -> Vec<&Stock<T>> {
+        let mut sorted: Vec<&Stock<T>> = self.stock.iter().collect();
+        sorted.sort_by(|a, b| order.compare(&a.item, &b.item));
+        sorted
+    }
+
+    pub fn count_by<K: Ord>(&self, key: impl Fn(&T) -> K) -> BTreeMap<K, u32> {
+        // units on hand per key
+        self.stock.iter().fold(BTreeMap::new(), |mut counts, s| {
+            *counts.entry(key(&s.item)).or_insert(0) += s.quantity;
+            counts
+        })
+    }
+}
+
+impl<T: PartialEq> FromIterator<(T, u32)> for Inventory<T> {
+    fn from_iter<I: IntoIterator<Item = (T, u32)>>(iter: I) -> Self {
+        let mut inventory = Inventory::new();
+        for (item, quantity) in iter {
+            inventory.restock(item, quantity);
+        }
+        inventory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> Inventory<Shoe> {
+        [
+            (Shoe::new(10, "sneaker", 6_000), 4),
+            (Shoe::new(13, "sneaker", 6_500), 1),
+            (Shoe::new(12, "slipper", 2_000), 7),
+            (Shoe::new(10, "boot", 12_000), 2),
+            (Shoe::new(10, "slipper", 2_500), 3),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn predicates_compose() {
+        let inventory = inventory();
+        let cheap_tens = size(10).and(price_between(0, 6_000));
+        let styles: Vec<&str> = inventory.filter(&cheap_tens).map(|s| s.item.style.as_str()).collect();
+        assert_eq!(styles, vec!["sneaker", "slipper"]);
+
+        let not_sneakers = style("sneaker").not().or(size(13));
+        assert_eq!(inventory.filter(&not_sneakers).count(), 4);
+    }
+
+    #[test]
+    fn sorts_on_several_keys() {
+        let inventory = inventory();
+        let order = SortBy::key(|s: &Shoe| s.style.clone()).then_descending(|s: &Shoe| s.size);
+        let sorted: Vec<(u32, &str)> = inventory.sorted(&order).iter().map(|s| (s.item.size, s.item.style.as_str())).collect();
+        assert_eq!(sorted, vec![(10, "boot"), (12, "slipper"), (10, "slipper"), (13, "sneaker"), (10, "sneaker")]);
+    }
+
+    #[test]
+    fn groups_restocks_and_removes() {
+        let mut inventory = inventory();
+        let by_style = inventory.count_by(|s| s.style.clone());
+        assert_eq!(by_style.get("slipper"), Some(&10));
+        assert_eq!(inventory.count_by(|s| s.size).get(&10), Some(&9));
+
+        let boot = Shoe::new(10, "boot", 12_000);
+        assert_eq!(inventory.restock(boot.clone(), 3), 5);
+        assert_eq!(inventory.remove(&boot, 6), Err(InventoryError::Insufficient { requested: 6, available: 5 }));
+        assert_eq!(inventory.remove(&boot, 5), Ok(0));
+        assert_eq!(inventory.quantity(&boot), 0);
+        assert_eq!(inventory.remove(&boot, 1), Err(InventoryError::NotStocked));
+        assert_eq!(inventory.total_units(), 15);
+    }
+}
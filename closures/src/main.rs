@@ -1,3 +1,4 @@
+use closures::inventory::{self, Inventory, Shoe, SortBy};
 
 #[derive(Debug)]
 struct Rectangle {
@@ -20,18 +21,40 @@ fn main() {
     });
     println!("{list:#?}");
     println!("{num_sort_operations}");
-}
 
-#[derive(PartialEq, Debug)]
-struct Shoe {
-    size: u32,
-    style: String,
+    let mut shoes: Inventory<Shoe> = [
+        (Shoe::new(10, "sneaker", 6_000), 4),
+        (Shoe::new(13, "sneaker", 6_500), 1),
+        (Shoe::new(12, "slipper", 2_000), 7),
+        (Shoe::new(10, "boot", 12_000), 2),
+    ]
+    .into_iter()
+    .collect();
+    shoes.restock(Shoe::new(10, "slipper", 2_500), 3);
+
+    let under_65 = inventory::size(10).and(inventory::price_between(0, 6_500));
+    for stock in shoes.filter(&under_65) {
+        println!("size 10 under $65: {} x{}", stock.item.style, stock.quantity);
+    }
+    let cheapest_first = SortBy::key(|s: &Shoe| s.price_cents).then(|s: &Shoe| s.size);
+    for stock in shoes.sorted(&cheapest_first) {
+        println!("${:.2} {} size {}", f64::from(stock.item.price_cents) / 100.0, stock.item.style, stock.item.size);
+    }
+    for (style, count) in shoes.count_by(|s| s.style.clone()) {
+        println!("{style}: {count} pairs");
+    }
+    if let Err(e) = shoes.remove(&Shoe::new(10, "boot", 12_000), 3) {
+        println!("could not sell 3 boots: {e}");
+    }
+
+    println!("{:?}", shoes_in_size(vec![Shoe::new(10, "boot", 12_000), Shoe::new(9, "boot", 12_000)], 10));
 }
 
 fn shoes_in_size(shoes: Vec<Shoe>, shoe_size: u32) -> Vec<Shoe> {
     shoes.into_iter().filter(|s| s.size == shoe_size).collect()
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -70,22 +93,10 @@ mod tests {
     #[test]
     fn filters_by_size() {
         let shoes = vec![
-            Shoe {
-                size: 10,
-                style: String::from("sneaker"),
-            },
-            Shoe {
-                size: 13,
-                style: String::from("sneaker"),
-            },
-            Shoe {
-                size: 12,
-                style: String::from("slipper"),
-            },
-            Shoe {
-                size: 10,
-                style: String::from("boot"),
-            },
+            Shoe::new(10, "sneaker", 5_000),
+            Shoe::new(13, "sneaker", 5_000),
+            Shoe::new(12, "slipper", 5_000),
+            Shoe::new(10, "boot", 5_000),
         ];
 
         let in_my_size = shoes_in_size(shoes, 10);
@@ -93,14 +104,8 @@ mod tests {
         assert_eq!(
             in_my_size,
             vec![
-                Shoe {
-                    size: 10,
-                    style: String::from("sneaker"),
-                },
-                Shoe {
-                    size: 10,
-                    style: String::from("boot"),
-                },
+                Shoe::new(10, "sneaker", 5_000),
+                Shoe::new(10, "boot", 5_000),
             ]
         );
     }
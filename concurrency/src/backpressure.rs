@@ -0,0 +1,129 @@
+// producers and a consumer joined by a bounded channel: once `capacity` messages are
+// waiting, send() blocks, so a slow consumer throttles the producers down to its own pace
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub capacity: usize,
+    pub producers: usize,
+    pub messages_per_producer: usize,
+    pub produce_delay: Duration,
+    pub consume_delay: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            capacity: 4,
+            producers: 2,
+            messages_per_producer: 20,
+            produce_delay: Duration::from_millis(1),
+            consume_delay: Duration::from_millis(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub sent: usize,
+    pub received: usize,
+    // total time producers spent waiting in send() for room in the channel, summed over producers
+    pub send_blocked: Duration,
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    pub fn throughput(&self) -> f64 {
+        // messages received per second
+        self.received as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sent {}, received {} in {:.2?} ({:.0} msg/s), producers blocked for {:.2?}",
+            self.sent,
+            self.received,
+            self.elapsed,
+            self.throughput(),
+            self.send_blocked
+        )
+    }
+}
+
+pub fn run(config: Config) -> Stats {
+    let start = Instant::now();
+    let (tx, rx) = mpsc::sync_channel::<(usize, usize)>(config.capacity);
+
+    let producers: Vec<_> = (0..config.producers)
+        .map(|id| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut blocked = Duration::ZERO;
+                for n in 0..config.messages_per_producer {
+                    thread::sleep(config.produce_delay);
+                    let before = Instant::now();
+                    tx.send((id, n)).unwrap();
+                    blocked += before.elapsed();
+                }
+                blocked
+            })
+        })
+        .collect();
+    // only the producers' clones remain, so the consumer loop ends when they're all done
+    drop(tx);
+
+    let mut received = 0;
+    for _ in rx {
+        thread::sleep(config.consume_delay);
+        received += 1;
+    }
+
+    let send_blocked = producers.into_iter().map(|p| p.join().unwrap()).sum();
+    Stats {
+        sent: config.producers * config.messages_per_producer,
+        received,
+        send_blocked,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_consumer_blocks_producers() {
+        let config = Config {
+            capacity: 1,
+            producers: 2,
+            messages_per_producer: 10,
+            produce_delay: Duration::ZERO,
+            consume_delay: Duration::from_millis(5),
+        };
+        let stats = run(config);
+        assert_eq!(stats.sent, 20);
+        assert_eq!(stats.received, 20);
+        // the consumer needs ~100ms for everything, and the producers spend most of it waiting
+        assert!(stats.send_blocked >= Duration::from_millis(50), "{}", stats);
+    }
+
+    #[test]
+    fn roomy_channel_never_blocks() {
+        let config = Config {
+            capacity: 100,
+            producers: 2,
+            messages_per_producer: 10,
+            produce_delay: Duration::ZERO,
+            consume_delay: Duration::from_millis(1),
+        };
+        let stats = run(config);
+        assert_eq!(stats.received, 20);
+        assert!(stats.send_blocked < Duration::from_millis(20), "{}", stats);
+    }
+}
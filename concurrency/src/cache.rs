@@ -0,0 +1,217 @@
+// a map many threads can read at once, whose entries expire after a time to live.
+// expired entries are never returned, and an optional background thread removes them
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct Entry<V> {
+    value: V,
+    expires: Instant,
+}
+
+struct Inner<K, V> {
+    entries: RwLock<HashMap<K, Entry<V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    // set when the cache is dropped; the condvar wakes the eviction thread early
+    stop: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl<K: Eq + Hash, V> Inner<K, V> {
+    fn evict_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| entry.expires > now);
+        let evicted = before - entries.len();
+        self.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+        evicted
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 { 0.0 } else { self.hits as f64 / lookups as f64 }
+    }
+}
+
+pub struct Cache<K, V> {
+    inner: Arc<Inner<K, V>>,
+    ttl: Duration,
+    evictor: Option<thread::JoinHandle<()>>,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Eq + Hash + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    pub fn new(ttl: Duration) -> Self {
+        // entries live for `ttl` unless inserted with their own. without an eviction
+        // thread, expired entries stay in memory until evict_expired() or an overwrite
+        Cache {
+            inner: Arc::new(Inner {
+                entries: RwLock::new(HashMap::new()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+                stop: Mutex::new(false),
+                wake: Condvar::new(),
+            }),
+            ttl,
+            evictor: None,
+        }
+    }
+
+    pub fn with_eviction(ttl: Duration, interval: Duration) -> Self {
+        // also starts a thread that sweeps out expired entries every `interval`
+        let mut cache = Cache::new(ttl);
+        let inner = Arc::clone(&cache.inner);
+        cache.evictor = Some(thread::spawn(move || {
+            let mut stop = inner.stop.lock().unwrap();
+            while !*stop {
+                stop = inner.wake.wait_timeout(stop, interval).unwrap().0;
+                if !*stop {
+                    inner.evict_expired();
+                }
+            }
+        }));
+        cache
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_with_ttl(key, value, self.ttl);
+    }
+
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        let entry = Entry { value, expires: Instant::now() + ttl };
+        self.inner.entries.write().unwrap().insert(key, entry);
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        // only takes the read lock, so readers never wait on each other
+        let entries = self.inner.entries.read().unwrap();
+        let found = entries.get(key).filter(|e| e.expires > Instant::now()).map(|e| e.value.clone());
+        let counter = if found.is_some() { &self.inner.hits } else { &self.inner.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.inner.entries.write().unwrap().remove(key).map(|e| e.value)
+    }
+
+    pub fn len(&self) -> usize {
+        // includes expired entries that haven't been evicted yet
+        self.inner.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn evict_expired(&self) -> usize {
+        self.inner.evict_expired()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            evictions: self.inner.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<K, V> Drop for Cache<K, V> {
+    fn drop(&mut self) {
+        if let Some(evictor) = self.evictor.take() {
+            *self.inner.stop.lock().unwrap() = true;
+            self.inner.wake.notify_one();
+            let _ = evictor.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = Cache::new(Duration::from_secs(60));
+        cache.insert("a", 1);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.remove(&"a"), Some(1));
+        assert_eq!(cache.get(&"a"), None);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn entries_expire_on_their_own_ttl() {
+        let cache = Cache::new(Duration::from_secs(60));
+        cache.insert("long", 1);
+        cache.insert_with_ttl("short", 2, Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&"short"), None);
+        assert_eq!(cache.get(&"long"), Some(1));
+        // the expired entry is still held until something evicts it
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.evict_expired(), 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn background_thread_evicts() {
+        let cache = Cache::with_eviction(Duration::from_millis(10), Duration::from_millis(5));
+        for i in 0..10 {
+            cache.insert(i, i * 2);
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().evictions, 10);
+    }
+
+    #[test]
+    fn readers_and_writers_share_it() {
+        let cache = Arc::new(Cache::new(Duration::from_secs(60)));
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = Arc::clone(&cache);
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        if t == 0 {
+                            cache.insert(i, i);
+                        } else {
+                            cache.get(&i);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(cache.len(), 100);
+        let stats = cache.stats();
+        assert_eq!(stats.hits + stats.misses, 300);
+    }
+}
@@ -0,0 +1,272 @@
+// tools for finding and avoiding lock-ordering deadlocks. NamedLock is a Mutex that records
+// who holds it in a shared LockTable, so a Watchdog watching worker heartbeats can say
+// which locks were held when the workers stopped making progress
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// lock name -> name of the thread holding it
+#[derive(Clone, Default)]
+pub struct LockTable(Arc<Mutex<BTreeMap<String, String>>>);
+
+impl LockTable {
+    pub fn new() -> Self {
+        LockTable::default()
+    }
+
+    pub fn held(&self) -> Vec<(String, String)> {
+        self.0.lock().unwrap().iter().map(|(l, t)| (l.clone(), t.clone())).collect()
+    }
+}
+
+// every lock gets a rank from this counter, which is the order try_lock_both takes them in
+static NEXT_RANK: AtomicUsize = AtomicUsize::new(0);
+
+pub struct NamedLock<T> {
+    name: String,
+    rank: usize,
+    inner: Mutex<T>,
+    table: LockTable,
+}
+
+pub struct NamedGuard<'a, T> {
+    guard: MutexGuard<'a, T>,
+    lock: &'a NamedLock<T>,
+}
+
+fn current_thread() -> String {
+    let current = thread::current();
+    current.name().map_or_else(|| format!("{:?}", current.id()), String::from)
+}
+
+impl<T> NamedLock<T> {
+    pub fn new(name: &str, value: T, table: &LockTable) -> Self {
+        NamedLock {
+            name: name.to_string(),
+            rank: NEXT_RANK.fetch_add(1, Ordering::Relaxed),
+            inner: Mutex::new(value),
+            table: table.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lock(&self) -> NamedGuard<'_, T> {
+        let guard = self.inner.lock().unwrap();
+        self.held(guard)
+    }
+
+    pub fn try_lock(&self) -> Option<NamedGuard<'_, T>> {
+        match self.inner.try_lock() {
+            Ok(guard) => Some(self.held(guard)),
+            Err(TryLockError::WouldBlock) => None,
+            Err(TryLockError::Poisoned(e)) => panic!("lock {} is poisoned: {}", self.name, e),
+        }
+    }
+
+    fn held<'a>(&'a self, guard: MutexGuard<'a, T>) -> NamedGuard<'a, T> {
+        self.table.0.lock().unwrap().insert(self.name.clone(), current_thread());
+        NamedGuard { guard, lock: self }
+    }
+}
+
+impl<T> Deref for NamedGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for NamedGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> Drop for NamedGuard<'_, T> {
+    fn drop(&mut self) {
+        // runs before the MutexGuard field is dropped, so the entry goes just before the unlock
+        self.lock.table.0.lock().unwrap().remove(&self.lock.name);
+    }
+}
+
+pub fn try_lock_both<'a, A, B>(a: &'a NamedLock<A>, b: &'a NamedLock<B>) -> (NamedGuard<'a, A>, NamedGuard<'a, B>) {
+    // every caller blocks on the lower ranked lock first, so two threads can't each hold
+    // one and wait for the other. the second is only ever try_locked: if it's taken (say by
+    // code that locks in some other order) the first is released and we back off and retry
+    let mut backoff = Duration::from_micros(50);
+    loop {
+        if a.rank < b.rank {
+            let first = a.lock();
+            if let Some(second) = b.try_lock() {
+                return (first, second);
+            }
+        } else {
+            let first = b.lock();
+            if let Some(second) = a.try_lock() {
+                return (second, first);
+            }
+        }
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(Duration::from_millis(10));
+    }
+}
+
+#[derive(Default)]
+struct Beats {
+    count: AtomicU64,
+    done: AtomicBool,
+}
+
+// a worker calls beat() whenever it makes progress and finish() when it's done
+#[derive(Clone, Default)]
+pub struct Heartbeat(Arc<Beats>);
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Heartbeat::default()
+    }
+
+    pub fn beat(&self) {
+        self.0.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        self.0.done.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StallReport {
+    pub stalled: Vec<String>,
+    pub held: Vec<(String, String)>,
+}
+
+impl fmt::Display for StallReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no progress from {}", self.stalled.join(", "))?;
+        for (lock, thread) in &self.held {
+            write!(f, "; {} is held by {}", lock, thread)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Watchdog {
+    stop: Arc<AtomicBool>,
+    handle: thread::JoinHandle<Option<StallReport>>,
+}
+
+impl Watchdog {
+    pub fn spawn(workers: Vec<(String, Heartbeat)>, table: LockTable, stall_after: Duration) -> Self {
+        // a worker counts as stalled once its heartbeat hasn't moved for `stall_after`.
+        // the watchdog stops at the first stall, or when every worker has finished
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let mut last: Vec<(u64, Instant)> = vec![(0, Instant::now()); workers.len()];
+            while !stopped.load(Ordering::Relaxed) {
+                thread::sleep(stall_after / 4);
+                let now = Instant::now();
+                let mut stalled = Vec::new();
+                let mut running = 0;
+                for ((name, heartbeat), (count, since)) in workers.iter().zip(&mut last) {
+                    if heartbeat.0.done.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    running += 1;
+                    let current = heartbeat.0.count.load(Ordering::Relaxed);
+                    if current != *count {
+                        (*count, *since) = (current, now);
+                    } else if now - *since >= stall_after {
+                        stalled.push(name.clone());
+                    }
+                }
+                if !stalled.is_empty() {
+                    return Some(StallReport { stalled, held: table.held() });
+                }
+                if running == 0 {
+                    break;
+                }
+            }
+            None
+        });
+        Watchdog { stop, handle }
+    }
+
+    pub fn join(self) -> Option<StallReport> {
+        // waits for a stall or for every worker to finish
+        self.handle.join().unwrap()
+    }
+
+    pub fn stop(self) -> Option<StallReport> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // two workers that each lock `first` then `second` 100 times, in opposite orders
+    fn crossed_workers(safe: bool) -> (Vec<(String, Heartbeat)>, LockTable, Vec<thread::JoinHandle<()>>) {
+        let table = LockTable::new();
+        let a = Arc::new(NamedLock::new("a", 0, &table));
+        let b = Arc::new(NamedLock::new("b", 0, &table));
+        let mut beats = Vec::new();
+        let mut handles = Vec::new();
+        for (name, first, second) in [("ab", &a, &b), ("ba", &b, &a)] {
+            let heartbeat = Heartbeat::new();
+            beats.push((name.to_string(), heartbeat.clone()));
+            let (first, second) = (Arc::clone(first), Arc::clone(second));
+            let handle = thread::Builder::new().name(name.to_string()).spawn(move || {
+                for _ in 0..100 {
+                    if safe {
+                        let (mut x, mut y) = try_lock_both(&first, &second);
+                        *x += 1;
+                        *y += 1;
+                    } else {
+                        let mut x = first.lock();
+                        thread::sleep(Duration::from_millis(1));
+                        let mut y = second.lock();
+                        *x += 1;
+                        *y += 1;
+                    }
+                    heartbeat.beat();
+                }
+                heartbeat.finish();
+            });
+            handles.push(handle.unwrap());
+        }
+        (beats, table, handles)
+    }
+
+    #[test]
+    fn watchdog_reports_the_deadlock() {
+        let (beats, table, _deadlocked) = crossed_workers(false);
+        let report = Watchdog::spawn(beats, table, Duration::from_millis(100)).join().unwrap();
+        assert!(!report.stalled.is_empty());
+        let mut held = report.held;
+        held.sort();
+        assert_eq!(held, vec![("a".to_string(), "ab".to_string()), ("b".to_string(), "ba".to_string())]);
+    }
+
+    #[test]
+    fn try_lock_both_avoids_it() {
+        let (beats, table, handles) = crossed_workers(true);
+        let watchdog = Watchdog::spawn(beats, table.clone(), Duration::from_millis(500));
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(watchdog.join(), None);
+        assert!(table.held().is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod backpressure;
+pub mod cache;
+pub mod deadlock;
+pub mod metrics;
+pub mod phases;
+pub mod pool;
+pub mod scoped;
+pub mod shutdown;
+pub mod stealing;
+pub mod tasks;
@@ -0,0 +1,324 @@
+use std::env;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use concurrency::backpressure::{self, Config};
+use concurrency::cache::Cache;
+use concurrency::deadlock::{self, Heartbeat, LockTable, NamedLock, Watchdog};
+use concurrency::metrics;
+use concurrency::phases;
+use concurrency::pool::ThreadPool;
+use concurrency::scoped;
+use concurrency::shutdown::{Shutdown, WorkerGroup};
+use concurrency::stealing;
+use concurrency::tasks;
+
+fn threads() {
+    let handle = thread::spawn(|| {
+        for i in 1..10 {
+            println!("hi number {} from the spawned thread!", i);
+            thread::sleep(Duration::from_millis(1));
+        }
+    });
+
+    for i in 1..5 {
+        println!("hi number {} from the main thread!", i);
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    handle.join().unwrap();
+}
+
+fn communication() {
+    let (tx, rx) = mpsc::channel();
+
+    let tx1 = tx.clone();
+    thread::spawn(move || {
+        let vals = vec!["hi", "from", "the", "thread"];
+        for val in vals {
+            tx1.send(val.to_string()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+
+    thread::spawn(move || {
+        let vals = vec!["more", "messages", "for", "you"];
+        for val in vals {
+            tx.send(val.to_string()).unwrap();
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+
+    for received in rx {
+        println!("Got: {}", received);
+    }
+
+    // the same shape with a bounded channel: first a consumer that keeps up, then one
+    // that's five times slower than the producers and holds them back
+    let fast = Config { consume_delay: Duration::ZERO, ..Config::default() };
+    println!("Fast consumer: {}", backpressure::run(fast));
+    println!("Slow consumer: {}", backpressure::run(Config::default()));
+}
+
+fn mutex() {
+    let counter = Arc::new(Mutex::new(0));
+    let mut handles = vec![];
+
+    for _ in 0..10 {
+        let counter = Arc::clone(&counter);
+        let handle = thread::spawn(move || {
+            let mut num = counter.lock().unwrap();
+            *num += 1;
+        });
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    println!("Result: {}", *counter.lock().unwrap());
+}
+
+fn pool() {
+    // the same counter as mutex(), but the pool owns the threads and joins them on shutdown
+    let counter = Arc::new(Mutex::new(0));
+    let pool = ThreadPool::new(4);
+
+    for i in 0..10 {
+        let counter = Arc::clone(&counter);
+        pool.execute(move || {
+            if i == 3 {
+                panic!("job {} failed", i);
+            }
+            *counter.lock().unwrap() += 1;
+        });
+    }
+
+    let panicked = pool.shutdown();
+    println!("Pool result: {} ({} job panicked)", *counter.lock().unwrap(), panicked);
+}
+
+fn scheduling() {
+    // the same uneven batch through a shared queue and through work stealing
+    let tasks = stealing::uneven_workload(2_000);
+    let workers = 4;
+
+    let shared = stealing::run_shared_queue(&tasks, workers);
+    println!("Shared queue:  {:>8.2?}  per worker {:?}", shared.elapsed, shared.per_worker);
+    let stealing = stealing::run_work_stealing(&tasks, workers);
+    println!(
+        "Work stealing: {:>8.2?}  per worker {:?}, {} stolen",
+        stealing.elapsed, stealing.per_worker, stealing.stolen
+    );
+    assert_eq!(shared.checksum, stealing.checksum);
+}
+
+fn cache() {
+    // eight readers hammer a small key space while two writers refresh it. entries only
+    // live 8ms, so readers miss on keys the writers haven't refreshed recently
+    let cache = Arc::new(Cache::with_eviction(Duration::from_millis(8), Duration::from_millis(10)));
+    let mut handles = vec![];
+
+    for writer in 0..2 {
+        let cache = Arc::clone(&cache);
+        handles.push(thread::spawn(move || {
+            for round in 0..20 {
+                for key in (writer..10).step_by(2) {
+                    cache.insert(key, format!("value {} from round {}", key, round));
+                }
+                thread::sleep(Duration::from_millis(5 * (writer as u64 + 1)));
+            }
+        }));
+    }
+
+    for reader in 0..8 {
+        let cache = Arc::clone(&cache);
+        handles.push(thread::spawn(move || {
+            for i in 0..500 {
+                cache.get(&((reader + i) % 10));
+                if i % 10 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let stats = cache.stats();
+    println!(
+        "Cache: {} hits, {} misses ({:.0}% hit rate), {} evicted, {} entries left",
+        stats.hits,
+        stats.misses,
+        stats.hit_rate() * 100.0,
+        stats.evictions,
+        cache.len()
+    );
+}
+
+fn graceful_shutdown() {
+    // three workers take jobs off a shared channel until shutdown, then finish whatever is
+    // already queued. the fourth ignores the signal and is reported as a straggler.
+    // Ctrl-C during the demo triggers the same shutdown early
+    let signal = Shutdown::new();
+    if let Err(e) = signal.install_ctrlc_handler() {
+        println!("Could not install the Ctrl-C handler: {}", e);
+    }
+    let mut group = WorkerGroup::new(signal);
+    let (tx, rx) = mpsc::channel::<u32>();
+    let rx = Arc::new(Mutex::new(rx));
+
+    for id in 0..3 {
+        let rx = Arc::clone(&rx);
+        group.spawn(&format!("worker-{}", id), move |signal| {
+            let mut done = 0;
+            while !signal.is_triggered() {
+                let job = rx.lock().unwrap().recv_timeout(Duration::from_millis(5));
+                if let Ok(job) = job {
+                    thread::sleep(Duration::from_millis(u64::from(job)));
+                    done += 1;
+                }
+            }
+            let queued: Vec<u32> = rx.lock().unwrap().try_iter().collect();
+            for &job in &queued {
+                thread::sleep(Duration::from_millis(u64::from(job)));
+            }
+            let drained = queued.len();
+            println!("worker-{} did {} jobs and drained {} more", id, done, drained);
+        });
+    }
+    group.spawn("stubborn", |_| thread::sleep(Duration::from_millis(500)));
+
+    let producer_signal = group.signal().clone();
+    let producer = thread::spawn(move || {
+        for job in 0.. {
+            if producer_signal.wait_timeout(Duration::from_millis(1)) {
+                break;
+            }
+            tx.send(job % 5).unwrap();
+        }
+    });
+
+    group.signal().wait_timeout(Duration::from_millis(100));
+    let report = group.shutdown(Duration::from_millis(200));
+    producer.join().unwrap();
+    println!(
+        "Shutdown: finished {:?}, panicked {:?}, stragglers {:?}",
+        report.finished, report.panicked, report.stragglers
+    );
+}
+
+fn borrowing() {
+    // numbers is a plain local Vec; the scoped threads borrow it without an Arc
+    let numbers: Vec<i64> = (1..=1_000_000).collect();
+    println!("Sum of 1..=1000000: {}", scoped::parallel_sum(&numbers, 4));
+
+    let target = 765_432;
+    match scoped::parallel_find(&numbers, &target, 4) {
+        Some(i) => println!("Found {} at index {}", target, i),
+        None => println!("{} is not in the list", target),
+    }
+    // still ours to use afterwards
+    println!("The list still has {} numbers", numbers.len());
+}
+
+fn deadlock() {
+    // two transfers between the same pair of accounts, each locking its own source first.
+    // unsafe_run is the classic deadlock; the watchdog notices and the stuck threads are
+    // left behind. the safe run takes both locks through try_lock_both and finishes
+    for safe in [false, true] {
+        let table = LockTable::new();
+        let checking = Arc::new(NamedLock::new("checking", 100, &table));
+        let savings = Arc::new(NamedLock::new("savings", 100, &table));
+        let mut beats = Vec::new();
+        let mut handles = Vec::new();
+
+        for (name, from, to) in [("to-savings", &checking, &savings), ("to-checking", &savings, &checking)] {
+            let heartbeat = Heartbeat::new();
+            beats.push((name.to_string(), heartbeat.clone()));
+            let (from, to) = (Arc::clone(from), Arc::clone(to));
+            let transfer = thread::Builder::new().name(name.to_string()).spawn(move || {
+                for _ in 0..50 {
+                    let (mut from, mut to) = if safe {
+                        deadlock::try_lock_both(&from, &to)
+                    } else {
+                        let from = from.lock();
+                        thread::sleep(Duration::from_millis(1));
+                        (from, to.lock())
+                    };
+                    *from -= 1;
+                    *to += 1;
+                    heartbeat.beat();
+                }
+                heartbeat.finish();
+            });
+            handles.push(transfer.unwrap());
+        }
+
+        let label = if safe { "try_lock_both" } else { "naive locking" };
+        match Watchdog::spawn(beats, table, Duration::from_millis(200)).join() {
+            Some(report) => println!("{}: deadlock, {}", label, report),
+            None => {
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+                println!("{}: finished with {} in checking and {} in savings", label, *checking.lock(), *savings.lock());
+            }
+        }
+    }
+}
+
+fn progress() {
+    // pool workers report each finished item; the aggregator draws the live status line
+    let (reporter, aggregator) = metrics::start(Some(200), Duration::from_millis(50), std::io::stdout());
+    let pool = ThreadPool::new(4);
+    for i in 0..200 {
+        let reporter = reporter.named(&format!("job-{}", i % 4));
+        pool.execute(move || {
+            thread::sleep(Duration::from_millis(2));
+            reporter.add(1);
+        });
+    }
+    drop(reporter);
+    pool.shutdown();
+    let summary = aggregator.finish();
+    println!("Progress: {} items at {:.0}/s, split {:?}", summary.items, summary.throughput(), summary.per_worker);
+}
+
+fn lockstep() {
+    // running totals of a million numbers, one barrier-separated phase per doubling step
+    let data: Vec<i64> = (1..=1_000_000).collect();
+    let (sums, timings) = phases::prefix_sums(&data, 4);
+    for timing in &timings {
+        println!("Phase {:>2} (step {:>6}): {:>8.2?}", timing.phase, timing.step, timing.elapsed);
+    }
+    println!("Prefix sums done, last total {}", sums[sums.len() - 1]);
+}
+
+fn main() {
+    // --async runs the tokio versions of the demos instead of the threaded ones
+    if env::args().skip(1).any(|arg| arg == "--async") {
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start the tokio runtime");
+        runtime.block_on(async {
+            tasks::communication().await;
+            tasks::mutex().await;
+        });
+        return;
+    }
+
+    threads();
+    communication();
+    mutex();
+    pool();
+    scheduling();
+    cache();
+    graceful_shutdown();
+    borrowing();
+    deadlock();
+    progress();
+    lockstep();
+}
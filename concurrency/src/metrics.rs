@@ -0,0 +1,161 @@
+// progress reporting for long-running parallel jobs. workers send events down a channel
+// through cheap Reporter handles and one aggregator thread owns all the counting and
+// drawing, so workers never contend on a shared counter or on the terminal
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub enum Event {
+    Progress { worker: String, items: u64 },
+    Done { worker: String },
+}
+
+#[derive(Clone)]
+pub struct Reporter {
+    worker: String,
+    tx: mpsc::Sender<Event>,
+}
+
+impl Reporter {
+    pub fn named(&self, worker: &str) -> Reporter {
+        // a handle for another worker, sharing the same aggregator
+        Reporter { worker: worker.to_string(), tx: self.tx.clone() }
+    }
+
+    pub fn add(&self, items: u64) {
+        // a closed aggregator only means nobody is watching, so errors are ignored
+        let _ = self.tx.send(Event::Progress { worker: self.worker.clone(), items });
+    }
+
+    pub fn done(&self) {
+        let _ = self.tx.send(Event::Done { worker: self.worker.clone() });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub items: u64,
+    pub elapsed: Duration,
+    pub per_worker: BTreeMap<String, u64>,
+}
+
+impl Summary {
+    pub fn throughput(&self) -> f64 {
+        // items per second
+        self.items as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+pub fn render(summary: &Summary, total: Option<u64>, active: usize) -> String {
+    // one status line: a bar when the total is known, then counts, rate and active workers
+    let mut line = String::new();
+    if let Some(total) = total.filter(|&t| t > 0) {
+        let fraction = (summary.items as f64 / total as f64).min(1.0);
+        let filled = (fraction * 20.0).round() as usize;
+        line.push_str(&format!(
+            "[{}{}] {}/{} ({:.0}%)",
+            "#".repeat(filled),
+            " ".repeat(20 - filled),
+            summary.items,
+            total,
+            fraction * 100.0
+        ));
+    } else {
+        line.push_str(&format!("{} items", summary.items));
+    }
+    line.push_str(&format!(
+        "  {:.1}/s  {:.1?}  {} active",
+        summary.throughput(),
+        summary.elapsed,
+        active
+    ));
+    line
+}
+
+pub struct Aggregator {
+    handle: thread::JoinHandle<Summary>,
+}
+
+impl Aggregator {
+    pub fn finish(self) -> Summary {
+        // returns once every Reporter has been dropped, so drop yours before calling this
+        self.handle.join().unwrap()
+    }
+}
+
+pub fn start<W: Write + Send + 'static>(total: Option<u64>, refresh: Duration, mut out: W) -> (Reporter, Aggregator) {
+    // the status line is redrawn in place with \r at most every `refresh`, and a final
+    // line is written when the last Reporter goes away
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let start = Instant::now();
+        let mut summary = Summary { items: 0, elapsed: Duration::ZERO, per_worker: BTreeMap::new() };
+        let mut finished = 0;
+        let mut last_draw = start;
+        loop {
+            let event = rx.recv_timeout(refresh);
+            match event {
+                Ok(Event::Progress { worker, items }) => {
+                    summary.items += items;
+                    *summary.per_worker.entry(worker).or_insert(0) += items;
+                }
+                Ok(Event::Done { worker }) => {
+                    summary.per_worker.entry(worker).or_insert(0);
+                    finished += 1;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if last_draw.elapsed() >= refresh {
+                summary.elapsed = start.elapsed();
+                let active = summary.per_worker.len().saturating_sub(finished);
+                let _ = write!(out, "\r{}", render(&summary, total, active));
+                let _ = out.flush();
+                last_draw = Instant::now();
+            }
+        }
+        summary.elapsed = start.elapsed();
+        let _ = writeln!(out, "\r{}", render(&summary, total, 0));
+        summary
+    });
+    (Reporter { worker: "main".to_string(), tx }, Aggregator { handle })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn aggregates_every_worker() {
+        let (reporter, aggregator) = start(Some(400), Duration::from_millis(5), io::sink());
+        let handles: Vec<_> = (0..4)
+            .map(|id| {
+                let reporter = reporter.named(&format!("worker-{}", id));
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        reporter.add(1);
+                    }
+                    reporter.done();
+                })
+            })
+            .collect();
+        drop(reporter);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let summary = aggregator.finish();
+        assert_eq!(summary.items, 400);
+        assert_eq!(summary.per_worker.len(), 4);
+        assert!(summary.per_worker.values().all(|&n| n == 100));
+    }
+
+    #[test]
+    fn renders_a_status_line() {
+        let summary = Summary { items: 50, elapsed: Duration::from_secs(2), per_worker: BTreeMap::new() };
+        assert_eq!(render(&summary, Some(200), 3), "[#####               ] 50/200 (25%)  25.0/s  2.0s  3 active");
+        assert_eq!(render(&summary, None, 0), "50 items  25.0/s  2.0s  0 active");
+    }
+}
@@ -0,0 +1,92 @@
+// a computation that runs in lockstep phases: every thread finishes phase n before any
+// thread starts phase n + 1, enforced by a Barrier between phases
+use std::iter;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseTiming {
+    pub phase: usize,
+    // how far back each element reached in this phase
+    pub step: usize,
+    pub elapsed: Duration,
+}
+
+pub fn prefix_sums(data: &[i64], threads: usize) -> (Vec<i64>, Vec<PhaseTiming>) {
+    // Hillis-Steele scan: in the phase with step s, every element adds the value s places
+    // before it. after log2(n) phases each element holds the sum of everything up to it.
+    // each phase reads one buffer and writes the other, so the barrier is the only
+    // synchronisation needed; the atomics are just a way to share the buffers
+    let n = data.len();
+    let threads = threads.max(1);
+    let size = n.div_ceil(threads).max(1);
+    let buffers: [Vec<AtomicI64>; 2] = [
+        data.iter().map(|&x| AtomicI64::new(x)).collect(),
+        iter::repeat_with(|| AtomicI64::new(0)).take(n).collect(),
+    ];
+    let steps: Vec<usize> = iter::successors(Some(1usize), |&s| Some(s * 2)).take_while(|&s| s < n).collect();
+    let barrier = Barrier::new(threads);
+    let timings = Mutex::new(Vec::with_capacity(steps.len()));
+
+    thread::scope(|s| {
+        for t in 0..threads {
+            let (buffers, steps, barrier, timings) = (&buffers, &steps, &barrier, &timings);
+            s.spawn(move || {
+                let range = (t * size).min(n)..((t + 1) * size).min(n);
+                for (phase, &step) in steps.iter().enumerate() {
+                    let start = Instant::now();
+                    let (src, dst) = (&buffers[phase % 2], &buffers[(phase + 1) % 2]);
+                    for i in range.clone() {
+                        let behind = if i >= step { src[i - step].load(Ordering::Relaxed) } else { 0 };
+                        dst[i].store(src[i].load(Ordering::Relaxed) + behind, Ordering::Relaxed);
+                    }
+                    // the last thread to arrive is the leader, so its clock covers the whole phase
+                    if barrier.wait().is_leader() {
+                        timings.lock().unwrap().push(PhaseTiming { phase, step, elapsed: start.elapsed() });
+                    }
+                }
+            });
+        }
+    });
+
+    let [even, odd] = buffers;
+    let result = if steps.len().is_multiple_of(2) { even } else { odd };
+    (result.into_iter().map(AtomicI64::into_inner).collect(), timings.into_inner().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequential(data: &[i64]) -> Vec<i64> {
+        data.iter()
+            .scan(0, |sum, &x| {
+                *sum += x;
+                Some(*sum)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_a_sequential_scan() {
+        for n in [0, 1, 2, 7, 64, 1_000] {
+            let data: Vec<i64> = (0..n).map(|i| (i * 37 % 11) as i64 - 5).collect();
+            for threads in [1, 3, 4, 16] {
+                assert_eq!(prefix_sums(&data, threads).0, sequential(&data), "n={} threads={}", n, threads);
+            }
+        }
+    }
+
+    #[test]
+    fn one_timing_per_phase() {
+        let data = vec![1; 1_000];
+        let (sums, timings) = prefix_sums(&data, 4);
+        assert_eq!(sums[999], 1_000);
+        // 1, 2, 4, ... 512
+        assert_eq!(timings.len(), 10);
+        let steps: Vec<usize> = timings.iter().map(|t| t.step).collect();
+        assert_eq!(steps, vec![1, 2, 4, 8, 16, 32, 64, 128, 256, 512]);
+    }
+}
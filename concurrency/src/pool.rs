@@ -0,0 +1,160 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// a fixed set of worker threads pulling jobs off one shared queue
+pub struct ThreadPool {
+    workers: Vec<Worker>,
+    sender: Option<mpsc::Sender<Job>>,
+    panicked: Arc<AtomicUsize>,
+}
+
+struct Worker {
+    id: usize,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>, panicked: Arc<AtomicUsize>) -> Worker {
+        let thread = thread::Builder::new()
+            .name(format!("pool-worker-{}", id))
+            .spawn(move || loop {
+                // the lock is only held while waiting for a job, not while running it
+                let message = receiver.lock().unwrap().recv();
+                match message {
+                    Ok(job) => {
+                        // a panicking job is counted and the worker carries on with the next one
+                        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                            panicked.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                    // the sender is gone and the queue is empty: time to stop
+                    Err(_) => break,
+                }
+            })
+            .expect("failed to spawn pool worker");
+
+        Worker { id, thread: Some(thread) }
+    }
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> ThreadPool {
+        // panics if size is zero, since a pool with no workers would never run anything
+        assert!(size > 0, "a thread pool needs at least one worker");
+
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let panicked = Arc::new(AtomicUsize::new(0));
+
+        let workers = (0..size)
+            .map(|id| Worker::new(id, Arc::clone(&receiver), Arc::clone(&panicked)))
+            .collect();
+
+        ThreadPool { workers, sender: Some(sender), panicked }
+    }
+
+    pub fn execute<F: FnOnce() + Send + 'static>(&self, f: F) {
+        self.sender
+            .as_ref()
+            .expect("pool is running until it is dropped")
+            .send(Box::new(f))
+            .expect("workers outlive the sender");
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn panicked_jobs(&self) -> usize {
+        self.panicked.load(Ordering::SeqCst)
+    }
+
+    pub fn shutdown(mut self) -> usize {
+        // stops taking jobs, lets the workers finish everything already queued,
+        // and returns how many jobs panicked over the pool's life
+        self.join_workers();
+        self.panicked_jobs()
+    }
+
+    fn join_workers(&mut self) {
+        drop(self.sender.take());
+        for worker in &mut self.workers {
+            if let Some(thread) = worker.thread.take()
+                && thread.join().is_err()
+            {
+                eprintln!("pool worker {} died outside of a job", worker.id);
+            }
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.join_workers();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn runs_every_job() {
+        let pool = ThreadPool::new(4);
+        let count = Arc::new(AtomicUsize::new(0));
+        for _ in 0..100 {
+            let count = Arc::clone(&count);
+            pool.execute(move || {
+                count.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        assert_eq!(pool.shutdown(), 0);
+        assert_eq!(count.load(Ordering::SeqCst), 100);
+    }
+
+    #[test]
+    fn shutdown_drains_the_queue() {
+        // one slow worker means most jobs are still queued when shutdown is called
+        let pool = ThreadPool::new(1);
+        let done = Arc::new(Mutex::new(Vec::new()));
+        for i in 0..5 {
+            let done = Arc::clone(&done);
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(10));
+                done.lock().unwrap().push(i);
+            });
+        }
+        pool.shutdown();
+        assert_eq!(*done.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn survives_panicking_jobs() {
+        let pool = ThreadPool::new(2);
+        let count = Arc::new(AtomicUsize::new(0));
+        for i in 0..10 {
+            let count = Arc::clone(&count);
+            pool.execute(move || {
+                if i % 3 == 0 {
+                    panic!("job {} blew up", i);
+                }
+                count.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        // both workers are still around to run the jobs queued after the panics
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.shutdown(), 4);
+        assert_eq!(count.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn zero_workers_is_rejected() {
+        ThreadPool::new(0);
+    }
+}
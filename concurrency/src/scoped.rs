@@ -0,0 +1,76 @@
+// parallel helpers built on thread::scope. the threads borrow chunks of the caller's
+// slice directly: the scope joins them all before returning, so no Arc or 'static is needed
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+fn chunk_size(len: usize, threads: usize) -> usize {
+    len.div_ceil(threads.max(1)).max(1)
+}
+
+pub fn parallel_sum(data: &[i64], threads: usize) -> i64 {
+    // each thread sums its chunk and the parent adds up the partial sums
+    thread::scope(|s| {
+        let partials: Vec<_> = data
+            .chunks(chunk_size(data.len(), threads))
+            .map(|chunk| s.spawn(move || chunk.iter().sum::<i64>()))
+            .collect();
+        partials.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+pub fn parallel_find<T: PartialEq + Sync>(data: &[T], needle: &T, threads: usize) -> Option<usize> {
+    // the index of the first match, like iter().position(). `best` holds the lowest match
+    // found so far, so threads give up once everything left in their chunk is past it
+    let size = chunk_size(data.len(), threads);
+    let best = AtomicUsize::new(usize::MAX);
+
+    thread::scope(|s| {
+        for (n, chunk) in data.chunks(size).enumerate() {
+            let best = &best;
+            s.spawn(move || {
+                let offset = n * size;
+                for (i, item) in chunk.iter().enumerate() {
+                    if offset + i >= best.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if item == needle {
+                        best.fetch_min(offset + i, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    let best = best.into_inner();
+    (best != usize::MAX).then_some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_match_sequential() {
+        let data: Vec<i64> = (1..=10_000).collect();
+        for threads in [1, 3, 8, 20_000] {
+            assert_eq!(parallel_sum(&data, threads), 50_005_000);
+        }
+        assert_eq!(parallel_sum(&[], 4), 0);
+    }
+
+    #[test]
+    fn finds_the_first_match() {
+        let mut data = vec![0u32; 1_000];
+        data[700] = 7;
+        data[300] = 7;
+        data[900] = 7;
+        assert_eq!(parallel_find(&data, &7, 4), Some(300));
+        assert_eq!(parallel_find(&data, &7, 1), Some(300));
+        assert_eq!(parallel_find(&data, &8, 4), None);
+
+        let words = ["pear", "fig", "plum"];
+        assert_eq!(parallel_find(&words, &"plum", 2), Some(2));
+        assert_eq!(parallel_find::<u8>(&[], &1, 2), None);
+    }
+}
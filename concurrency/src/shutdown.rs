@@ -0,0 +1,157 @@
+// one shutdown signal shared by every worker. it can be triggered in code or by Ctrl-C,
+// workers check it between pieces of work, and the group joins them with a deadline
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    state: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Shutdown::default()
+    }
+
+    pub fn trigger(&self) {
+        // wakes everyone blocked in wait_timeout(); triggering twice is harmless
+        let (triggered, wake) = &*self.state;
+        *triggered.lock().unwrap() = true;
+        wake.notify_all();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.state.0.lock().unwrap()
+    }
+
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        // a sleep that ends early on shutdown. returns whether shutdown was triggered
+        let (triggered, wake) = &*self.state;
+        let guard = triggered.lock().unwrap();
+        let (guard, _) = wake.wait_timeout_while(guard, timeout, |t| !*t).unwrap();
+        *guard
+    }
+
+    pub fn install_ctrlc_handler(&self) -> Result<(), ctrlc::Error> {
+        // only one handler can be installed per process
+        let signal = self.clone();
+        ctrlc::set_handler(move || {
+            println!("Ctrl-C received, shutting down");
+            signal.trigger();
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct JoinReport {
+    pub finished: Vec<String>,
+    pub panicked: Vec<String>,
+    // still running at the deadline; they're left detached
+    pub stragglers: Vec<String>,
+}
+
+pub struct WorkerGroup {
+    signal: Shutdown,
+    workers: Vec<(String, thread::JoinHandle<()>)>,
+}
+
+impl WorkerGroup {
+    pub fn new(signal: Shutdown) -> Self {
+        WorkerGroup { signal, workers: Vec::new() }
+    }
+
+    pub fn signal(&self) -> &Shutdown {
+        &self.signal
+    }
+
+    pub fn spawn<F: FnOnce(Shutdown) + Send + 'static>(&mut self, name: &str, f: F) {
+        let signal = self.signal.clone();
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || f(signal))
+            .expect("failed to spawn worker");
+        self.workers.push((name.to_string(), handle));
+    }
+
+    pub fn shutdown(self, timeout: Duration) -> JoinReport {
+        self.signal.trigger();
+        self.join_timeout(timeout)
+    }
+
+    pub fn join_timeout(self, timeout: Duration) -> JoinReport {
+        // std can't join with a timeout, so poll is_finished() until the deadline and
+        // only join the threads that are done
+        let deadline = Instant::now() + timeout;
+        let mut pending = self.workers;
+        let mut report = JoinReport::default();
+        loop {
+            let (done, running): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, h)| h.is_finished());
+            for (name, handle) in done {
+                match handle.join() {
+                    Ok(()) => report.finished.push(name),
+                    Err(_) => report.panicked.push(name),
+                }
+            }
+            pending = running;
+            if pending.is_empty() || Instant::now() >= deadline {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        report.stragglers = pending.into_iter().map(|(name, _)| name).collect();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn trigger_wakes_waiters() {
+        let signal = Shutdown::new();
+        let waiter = {
+            let signal = signal.clone();
+            thread::spawn(move || signal.wait_timeout(Duration::from_secs(10)))
+        };
+        thread::sleep(Duration::from_millis(10));
+        assert!(!signal.is_triggered());
+        signal.trigger();
+        assert!(waiter.join().unwrap());
+        assert!(!Shutdown::new().wait_timeout(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn workers_drain_before_exiting() {
+        let (tx, rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut group = WorkerGroup::new(Shutdown::new());
+        group.spawn("drainer", move |signal| {
+            // everything already sent is processed even though shutdown comes first
+            while !signal.wait_timeout(Duration::from_millis(1)) {}
+            for job in rx.try_iter() {
+                done_tx.send(job).unwrap();
+            }
+        });
+        for job in 0..5 {
+            tx.send(job).unwrap();
+        }
+        let report = group.shutdown(Duration::from_secs(1));
+        assert_eq!(report.finished, vec!["drainer"]);
+        assert_eq!(done_rx.try_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn stragglers_and_panics_are_reported() {
+        let mut group = WorkerGroup::new(Shutdown::new());
+        group.spawn("polite", |signal| while !signal.wait_timeout(Duration::from_millis(1)) {});
+        group.spawn("stubborn", |_| thread::sleep(Duration::from_millis(300)));
+        group.spawn("broken", |_| panic!("worker failed"));
+        let report = group.shutdown(Duration::from_millis(50));
+        assert_eq!(report.finished, vec!["polite"]);
+        assert_eq!(report.panicked, vec!["broken"]);
+        assert_eq!(report.stragglers, vec!["stubborn"]);
+    }
+}
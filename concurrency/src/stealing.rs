@@ -0,0 +1,162 @@
+// two ways of spreading a batch of tasks over worker threads. each task is a number
+// saying how much busy work it needs, so the workload can be made deliberately uneven
+use std::collections::VecDeque;
+use std::hint;
+use std::iter;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_deque::{Steal, Stealer, Worker};
+
+#[derive(Debug)]
+pub struct Report {
+    pub elapsed: Duration,
+    // tasks completed by each worker
+    pub per_worker: Vec<usize>,
+    pub stolen: usize,
+    // combined result of every task, so both schedulers can be checked against each other
+    pub checksum: u64,
+}
+
+pub fn work(n: u64) -> u64 {
+    (0..n).fold(0u64, |acc, x| hint::black_box(acc.wrapping_mul(31).wrapping_add(x)))
+}
+
+pub fn uneven_workload(count: usize) -> Vec<u64> {
+    // the first eighth of the tasks are 100 times heavier than the rest, so whoever
+    // is handed the front of the list has far more to do
+    (0..count).map(|i| if i < count / 8 { 100_000 } else { 1_000 }).collect()
+}
+
+fn chunks(tasks: &[u64], workers: usize) -> impl Iterator<Item = &[u64]> {
+    tasks.chunks(tasks.len().div_ceil(workers).max(1))
+}
+
+pub fn run_work_stealing(tasks: &[u64], workers: usize) -> Report {
+    // each worker starts with its own contiguous slice of the tasks in a private deque.
+    // it pops from its own deque and only when that's empty does it steal from the others
+    let start = Instant::now();
+    let locals: Vec<Worker<u64>> = (0..workers).map(|_| Worker::new_lifo()).collect();
+    for (local, chunk) in locals.iter().zip(chunks(tasks, workers)) {
+        for &task in chunk {
+            local.push(task);
+        }
+    }
+    let stealers: Vec<Stealer<u64>> = locals.iter().map(|w| w.stealer()).collect();
+
+    let results: Vec<(usize, usize, u64)> = thread::scope(|s| {
+        let handles: Vec<_> = locals
+            .into_iter()
+            .enumerate()
+            .map(|(id, local)| {
+                let stealers = &stealers;
+                s.spawn(move || {
+                    let (mut done, mut stolen, mut checksum) = (0, 0, 0u64);
+                    loop {
+                        let task = local.pop().or_else(|| {
+                            let found = steal_from_others(id, stealers);
+                            stolen += usize::from(found.is_some());
+                            found
+                        });
+                        // nothing creates new tasks, so once every deque is empty we're done
+                        let Some(task) = task else { break };
+                        checksum = checksum.wrapping_add(work(task));
+                        done += 1;
+                    }
+                    (done, stolen, checksum)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    Report {
+        elapsed: start.elapsed(),
+        per_worker: results.iter().map(|r| r.0).collect(),
+        stolen: results.iter().map(|r| r.1).sum(),
+        checksum: results.iter().fold(0, |acc, r| acc.wrapping_add(r.2)),
+    }
+}
+
+fn steal_from_others(id: usize, stealers: &[Stealer<u64>]) -> Option<u64> {
+    // starts with the next worker along so idle workers don't all pile onto the same victim.
+    // Retry means we lost a race with another thief, so the sweep is repeated
+    loop {
+        let attempt: Steal<u64> = stealers
+            .iter()
+            .cycle()
+            .skip(id + 1)
+            .take(stealers.len() - 1)
+            .map(|s| s.steal())
+            .collect();
+        match attempt {
+            Steal::Success(task) => return Some(task),
+            Steal::Empty => return None,
+            Steal::Retry => continue,
+        }
+    }
+}
+
+pub fn run_shared_queue(tasks: &[u64], workers: usize) -> Report {
+    // every worker takes the next task from one queue behind a mutex
+    let start = Instant::now();
+    let queue = Mutex::new(tasks.iter().copied().collect::<VecDeque<u64>>());
+
+    let results: Vec<(usize, u64)> = thread::scope(|s| {
+        let handles: Vec<_> = iter::repeat_n(&queue, workers)
+            .map(|queue| {
+                s.spawn(move || {
+                    let (mut done, mut checksum) = (0, 0u64);
+                    // the guard is a temporary, so the lock is released before the work starts
+                    while let Some(task) = queue.lock().unwrap().pop_front() {
+                        checksum = checksum.wrapping_add(work(task));
+                        done += 1;
+                    }
+                    (done, checksum)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    Report {
+        elapsed: start.elapsed(),
+        per_worker: results.iter().map(|r| r.0).collect(),
+        stolen: 0,
+        checksum: results.iter().fold(0, |acc, r| acc.wrapping_add(r.1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_schedulers_do_all_the_work() {
+        let tasks = uneven_workload(400);
+        let expected = tasks.iter().fold(0u64, |acc, &t| acc.wrapping_add(work(t)));
+
+        let stealing = run_work_stealing(&tasks, 4);
+        let shared = run_shared_queue(&tasks, 4);
+        assert_eq!(stealing.per_worker.iter().sum::<usize>(), 400);
+        assert_eq!(shared.per_worker.iter().sum::<usize>(), 400);
+        assert_eq!(stealing.checksum, expected);
+        assert_eq!(shared.checksum, expected);
+    }
+
+    #[test]
+    fn idle_workers_steal() {
+        // worker 0 is handed all the heavy tasks, so the others finish early and help out
+        let stealing = run_work_stealing(&uneven_workload(400), 4);
+        assert!(stealing.stolen > 0, "{:?}", stealing);
+        assert!(stealing.per_worker[0] < 100, "{:?}", stealing);
+    }
+
+    #[test]
+    fn more_workers_than_tasks() {
+        let stealing = run_work_stealing(&[10, 20], 4);
+        assert_eq!(stealing.per_worker.iter().sum::<usize>(), 2);
+        assert_eq!(stealing.checksum, work(10).wrapping_add(work(20)));
+    }
+}
@@ -0,0 +1,77 @@
+// async versions of the channel and mutex demos in main.rs. tasks take the place of
+// threads and the tokio primitives are awaited instead of blocking
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, Mutex};
+use tokio::time;
+
+pub async fn communication() -> Vec<String> {
+    let (tx, mut rx) = mpsc::channel(8);
+
+    let tx1 = tx.clone();
+    tokio::spawn(async move {
+        let vals = vec!["hi", "from", "the", "task"];
+        for val in vals {
+            tx1.send(val.to_string()).await.unwrap();
+            time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+
+    tokio::spawn(async move {
+        let vals = vec!["more", "messages", "for", "you"];
+        for val in vals {
+            tx.send(val.to_string()).await.unwrap();
+            time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+
+    // recv() returns None once both senders have been dropped
+    let mut received = Vec::new();
+    while let Some(val) = rx.recv().await {
+        println!("Got: {}", val);
+        received.push(val);
+    }
+    received
+}
+
+pub async fn mutex() -> i32 {
+    let counter = Arc::new(Mutex::new(0));
+    let mut handles = vec![];
+
+    for _ in 0..10 {
+        let counter = Arc::clone(&counter);
+        let handle = tokio::spawn(async move {
+            // a tokio mutex guard may be held across an .await, unlike std's
+            let mut num = counter.lock().await;
+            time::sleep(Duration::from_millis(1)).await;
+            *num += 1;
+        });
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let result = *counter.lock().await;
+    println!("Result: {}", result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn every_message_arrives() {
+        let mut received = communication().await;
+        received.sort();
+        assert_eq!(received, vec!["for", "from", "hi", "messages", "more", "task", "the", "you"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn counter_reaches_ten() {
+        assert_eq!(mutex().await, 10);
+    }
+}
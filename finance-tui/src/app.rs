@@ -0,0 +1,174 @@
+// everything the screens show, and how keys change it. drawing lives in ui.rs
+use bankaccounts::person::Person;
+use money::Money;
+use mortgage::{AmortizationSchedule, Mortgage};
+use ratatui::crossterm::event::KeyCode;
+use simclock::Clock;
+
+// the extra-payment slider moves in $50 steps up to $3,000 a month
+pub const EXTRA_STEP: Money = Money::usd(5_000);
+pub const EXTRA_MAX: Money = Money::usd(300_000);
+const PAGE: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Schedule,
+    Accounts,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 2] = [Tab::Schedule, Tab::Accounts];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Schedule => "Amortization",
+            Tab::Accounts => "Accounts",
+        }
+    }
+
+    fn next(&self) -> Tab {
+        match self {
+            Tab::Schedule => Tab::Accounts,
+            Tab::Accounts => Tab::Schedule,
+        }
+    }
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub mortgage: Mortgage,
+    pub extra: Money,
+    pub schedule: AmortizationSchedule,
+    // the schedule without any extra payment, to show what the slider saves
+    pub baseline: AmortizationSchedule,
+    // first schedule row on screen
+    pub scroll: usize,
+    pub person: Person,
+    // highlighted account on the accounts tab
+    pub selected: usize,
+    pub quit: bool,
+    clock: Clock,
+}
+
+impl App {
+    pub fn new(mortgage: Mortgage, person: Person, clock: Clock) -> Self {
+        let baseline = mortgage.schedule_from(&clock);
+        let schedule = mortgage.schedule_from(&clock);
+        App {
+            tab: Tab::Schedule,
+            extra: Money::zero(mortgage.monthly_payment().currency()),
+            mortgage,
+            schedule,
+            baseline,
+            scroll: 0,
+            person,
+            selected: 0,
+            quit: false,
+            clock,
+        }
+    }
+
+    pub fn interest_saved(&self) -> Money {
+        self.baseline.total_interest_paid - self.schedule.total_interest_paid
+    }
+
+    pub fn on_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => self.tab = self.tab.next(),
+            KeyCode::Char('1') => self.tab = Tab::Schedule,
+            KeyCode::Char('2') => self.tab = Tab::Accounts,
+            _ => match self.tab {
+                Tab::Schedule => self.on_schedule_key(key),
+                Tab::Accounts => self.on_accounts_key(key),
+            },
+        }
+    }
+
+    fn on_schedule_key(&mut self, key: KeyCode) {
+        let last = self.schedule.payments.len().saturating_sub(1);
+        match key {
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown => self.scroll = (self.scroll + PAGE).min(last),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = last,
+            KeyCode::Right | KeyCode::Char('+') => self.set_extra((self.extra + EXTRA_STEP).min(EXTRA_MAX)),
+            KeyCode::Left | KeyCode::Char('-') => {
+                let lower = self.extra - EXTRA_STEP;
+                self.set_extra(if lower.is_negative() { Money::zero(lower.currency()) } else { lower });
+            }
+            _ => {}
+        }
+    }
+
+    fn on_accounts_key(&mut self, key: KeyCode) {
+        let last = self.person.accounts.len().saturating_sub(1);
+        match key {
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn set_extra(&mut self, extra: Money) {
+        if extra == self.extra {
+            return;
+        }
+        self.extra = extra;
+        self.schedule = self.mortgage.clone().with_additional_payment(extra).schedule_from(&self.clock);
+        // a shorter schedule may leave the view past its end
+        self.scroll = self.scroll.min(self.schedule.payments.len().saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn app() -> App {
+        let origin = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mortgage = Mortgage::new(origin, Money::dollars(479000.0), 5.5, 30);
+        App::new(mortgage, Person::new("Stephen"), Clock::starting_at(origin))
+    }
+
+    #[test]
+    fn slider_recomputes_the_schedule() {
+        let mut app = app();
+        assert_eq!(app.schedule.payments.len(), 360);
+        app.on_key(KeyCode::Left);
+        assert!(app.extra.is_zero());
+
+        app.on_key(KeyCode::End);
+        for _ in 0..20 {
+            app.on_key(KeyCode::Right);
+        }
+        assert_eq!(app.extra, Money::dollars(1000.0));
+        assert!(app.schedule.payments.len() < 360);
+        assert!(app.interest_saved().is_positive());
+        assert_eq!(app.scroll, app.schedule.payments.len() - 1);
+
+        for _ in 0..100 {
+            app.on_key(KeyCode::Char('+'));
+        }
+        assert_eq!(app.extra, EXTRA_MAX);
+    }
+
+    #[test]
+    fn keys_switch_tabs_and_quit() {
+        let mut app = app();
+        app.on_key(KeyCode::Tab);
+        assert_eq!(app.tab, Tab::Accounts);
+        // no accounts to move between
+        app.on_key(KeyCode::Down);
+        assert_eq!(app.selected, 0);
+        app.on_key(KeyCode::Char('1'));
+        assert_eq!(app.tab, Tab::Schedule);
+        app.on_key(KeyCode::PageDown);
+        assert_eq!(app.scroll, 12);
+        app.on_key(KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+}
@@ -0,0 +1,52 @@
+// terminal front end for the mortgage and bank account libraries.
+// usage: finance-tui [person.json]
+use std::{env, io, process};
+
+use chrono::{TimeZone, Utc};
+use money::Money;
+use mortgage::Mortgage;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use bankaccounts::person::Person;
+use simclock::Clock;
+
+mod app;
+mod ui;
+
+use app::App;
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.on_key(key.code);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let path = env::args().nth(1).unwrap_or_else(|| "person.json".to_string());
+    let person = Person::load_from_json(&path).unwrap_or_else(|e| {
+        eprintln!("Could not load {}: {}", path, e);
+        process::exit(1);
+    });
+
+    let clock = Clock::system();
+    let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+    let mortgage = Mortgage::builder(origin_date, Money::dollars(479000.0), 5.5, 30)
+        .with_history(&clock)
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!("Bad mortgage terms: {}", e);
+            process::exit(1);
+        });
+
+    let mut app = App::new(mortgage, person, clock);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,178 @@
+use bankaccounts::BankAccount;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, LineGauge, List, ListItem, ListState, Paragraph, Row, Table, Tabs};
+
+use crate::app::{App, Tab, EXTRA_MAX};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [tabs, body, help] = Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)])
+        .areas(frame.area());
+
+    let titles = Tab::ALL.iter().enumerate().map(|(i, t)| format!("{} {}", i + 1, t.title()));
+    let selected = Tab::ALL.iter().position(|t| *t == app.tab).unwrap_or(0);
+    frame.render_widget(
+        Tabs::new(titles)
+            .block(Block::bordered().title(format!(" {} ", app.person.name)))
+            .select(selected)
+            .highlight_style(Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        tabs,
+    );
+
+    match app.tab {
+        Tab::Schedule => draw_schedule(frame, app, body),
+        Tab::Accounts => draw_accounts(frame, app, body),
+    }
+
+    let keys = match app.tab {
+        Tab::Schedule => "←/→ extra payment  ↑/↓ PgUp/PgDn scroll  Tab switch  q quit",
+        Tab::Accounts => "↑/↓ select account  Tab switch  q quit",
+    };
+    frame.render_widget(Paragraph::new(keys).dark_gray(), help);
+}
+
+fn draw_schedule(frame: &mut Frame, app: &App, area: Rect) {
+    let [summary, slider, table] = Layout::vertical([Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)])
+        .areas(area);
+
+    let payments = &app.schedule.payments;
+    let payoff = payments.last().map(|p| p.payment_date.format("%Y-%b").to_string()).unwrap_or_default();
+    let text = format!(
+        "Monthly payment {} + {} extra   Payoff {} ({} payments)   Interest {} (saves {})",
+        app.mortgage.monthly_payment(),
+        app.extra,
+        payoff,
+        payments.len(),
+        app.schedule.total_interest_paid,
+        app.interest_saved(),
+    );
+    frame.render_widget(Paragraph::new(text).block(Block::bordered().title(" Loan ")), summary);
+
+    frame.render_widget(
+        LineGauge::default()
+            .block(Block::bordered().title(" Extra payment "))
+            .filled_style(Style::new().fg(Color::Green))
+            .label(format!("{:>10}/mo", app.extra))
+            .ratio(app.extra.ratio(EXTRA_MAX).clamp(0.0, 1.0)),
+        slider,
+    );
+
+    let rows = payments.iter().skip(app.scroll).map(|p| {
+        Row::new(vec![
+            p.payment_number.to_string(),
+            p.payment_date.format("%Y-%b-%d").to_string(),
+            p.payment_amount.to_string(),
+            p.principal_payment.to_string(),
+            p.interest_payment.to_string(),
+            p.remaining_principal.to_string(),
+        ])
+    });
+    let widths = [Constraint::Length(5), Constraint::Length(12), Constraint::Length(13),
+        Constraint::Length(13), Constraint::Length(13), Constraint::Length(14)];
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(["Pmt#", "Date", "Payment", "Principal", "Interest", "Remaining"]).bold())
+            .block(Block::bordered().title(format!(" Schedule {}/{} ", (app.scroll + 1).min(payments.len()), payments.len()))),
+        table,
+    );
+}
+
+fn draw_accounts(frame: &mut Frame, app: &App, area: Rect) {
+    let [left, right] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(area);
+    let [accounts, allocation] = Layout::vertical([Constraint::Min(0), Constraint::Length(12)]).areas(left);
+
+    let items: Vec<ListItem> = app.person.accounts.iter()
+        .map(|a| ListItem::new(format!("{:<18} {:>14}", a.get_name(), a.get_balance())))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::bordered().title(format!(" Net worth {} ", app.person.net_worth())))
+            .highlight_style(Style::new().reversed()),
+        accounts,
+        &mut state,
+    );
+
+    let bars: Vec<Bar> = app.person.allocation().iter()
+        .map(|s| Bar::default()
+            .label(Line::from(s.label.clone()))
+            .value(s.percent.round() as u64)
+            .text_value(format!("{:.0}%", s.percent)))
+        .collect();
+    frame.render_widget(
+        BarChart::default()
+            .block(Block::bordered().title(format!(" Portfolio {} ", app.person.brokerage_value())))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(6)
+            .bar_gap(1)
+            .max(100),
+        allocation,
+    );
+
+    match app.person.accounts.get(app.selected) {
+        Some(account) => draw_statement(frame, account, right),
+        None => frame.render_widget(Paragraph::new("No accounts").block(Block::bordered()), right),
+    }
+}
+
+fn draw_statement(frame: &mut Frame, account: &BankAccount, area: Rect) {
+    // newest first, with the balance after each transaction
+    let mut balance = account.starting_balance;
+    let mut rows: Vec<Row> = account.transactions.iter()
+        .map(|t| {
+            balance += t.signed_amount();
+            Row::new(vec![
+                t.timestamp.format("%Y-%m-%d").to_string(),
+                format!("{:?}", t.transaction_type),
+                t.signed_amount().to_string(),
+                balance.to_string(),
+                t.description.clone().unwrap_or_default(),
+            ])
+        })
+        .collect();
+    rows.reverse();
+
+    let widths = [Constraint::Length(10), Constraint::Length(10), Constraint::Length(13),
+        Constraint::Length(14), Constraint::Min(10)];
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(Row::new(["Date", "Type", "Amount", "Balance", "Description"]).bold())
+            .block(Block::bordered().title(format!(" {} ({}) ", account.get_name(), account.kind()))),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bankaccounts::person::{Holding, Person};
+    use chrono::{TimeZone, Utc};
+    use money::Money;
+    use mortgage::Mortgage;
+    use ratatui::{Terminal, backend::TestBackend};
+    use simclock::Clock;
+
+    #[test]
+    fn draws_both_tabs() {
+        let mut person = Person::new("Stephen");
+        let mut checking = BankAccount::new_checking("Checking", Money::dollars(1000.0), 0.5, Money::dollars(0.0), Money::dollars(0.0));
+        checking.deposit(Money::dollars(250.0), Some("paycheck")).unwrap();
+        person.accounts.push(checking);
+        person.holdings.push(Holding { symbol: "VTI".to_string(), shares: 10.0, price: Money::dollars(250.0) });
+        let origin = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mut app = App::new(Mortgage::new(origin, Money::dollars(479000.0), 5.5, 30), person, Clock::starting_at(origin));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let screen = format!("{:?}", terminal.backend().buffer());
+        assert!(screen.contains("Schedule 1/360"));
+
+        app.tab = Tab::Accounts;
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let screen = format!("{:?}", terminal.backend().buffer());
+        assert!(screen.contains("paycheck"));
+        assert!(screen.contains("$1,250.00"));
+    }
+}
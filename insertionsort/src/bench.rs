@@ -0,0 +1,187 @@
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::alloc;
+
+pub struct BenchResult {
+    pub algorithm: String,
+    pub input: String,
+    pub size: usize,
+    pub runs: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+    pub min: Duration,
+    pub allocations: usize,
+    // baseline mean / this mean for the same input and size, see compute_speedups
+    pub speedup: Option<f64>,
+}
+
+pub struct Harness {
+    pub warmups: usize,
+    pub iterations: usize,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Harness { warmups: 2, iterations: 10 }
+    }
+}
+
+impl Harness {
+    pub fn new(warmups: usize, iterations: usize) -> Self {
+        Harness { warmups, iterations: iterations.max(1) }
+    }
+
+    pub fn run<T: Ord + Clone>(&self, algorithm: &str, input: &str, sort: impl Fn(&mut [T]), data: &[T]) -> BenchResult {
+        // every run sorts a fresh copy so earlier runs can't hand later ones sorted input
+        for _ in 0..self.warmups {
+            let mut arr = data.to_vec();
+            sort(&mut arr);
+        }
+
+        let mut timings = Vec::with_capacity(self.iterations);
+        let mut allocations = 0;
+        for _ in 0..self.iterations {
+            let mut arr = data.to_vec();
+            let before = alloc::allocations();
+            let start = Instant::now();
+            sort(&mut arr);
+            timings.push(start.elapsed());
+            allocations += alloc::allocations() - before;
+            assert!(arr.is_sorted(), "{algorithm} produced unsorted output");
+        }
+
+        summarize(algorithm, input, data.len(), timings, allocations / self.iterations)
+    }
+}
+
+pub fn random_u64s(len: usize, seed: u64) -> Vec<u64> {
+    // xorshift, good enough for benchmark input without pulling in rand
+    let mut x = seed.max(1);
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        })
+        .collect()
+}
+
+pub fn random_vec(len: usize, seed: u64) -> Vec<i32> {
+    random_u64s(len, seed)
+        .into_iter()
+        .map(|x| (x % 1_000_000) as i32 - 500_000)
+        .collect()
+}
+
+fn summarize(algorithm: &str, input: &str, size: usize, mut timings: Vec<Duration>, allocations: usize) -> BenchResult {
+    timings.sort();
+    let runs = timings.len();
+    let nanos: Vec<f64> = timings.iter().map(|t| t.as_nanos() as f64).collect();
+
+    let mean = nanos.iter().sum::<f64>() / runs as f64;
+    let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / runs as f64;
+    let median = if runs.is_multiple_of(2) {
+        (nanos[runs / 2 - 1] + nanos[runs / 2]) / 2.0
+    } else {
+        nanos[runs / 2]
+    };
+
+    BenchResult {
+        algorithm: algorithm.to_string(),
+        input: input.to_string(),
+        size,
+        runs,
+        mean: Duration::from_nanos(mean as u64),
+        median: Duration::from_nanos(median as u64),
+        stddev: Duration::from_nanos(variance.sqrt() as u64),
+        min: timings[0],
+        allocations,
+        speedup: None,
+    }
+}
+
+impl BenchResult {
+    pub fn print(&self) {
+        let speedup = self.speedup.map(|s| format!("x{s:.2}")).unwrap_or_default();
+        println!("{:>14} | {:>10} | n = {:>7} | mean {:>12?} | median {:>12?} | stddev {:>12?} | allocs {:>5} | {:>8}",
+            self.algorithm, self.input, self.size, self.mean, self.median, self.stddev, self.allocations, speedup);
+    }
+}
+
+pub fn compute_speedups(results: &mut [BenchResult], baseline: &str) {
+    // above 1.0 means faster than the baseline on the same input
+    let baseline_means: Vec<(String, usize, f64)> = results
+        .iter()
+        .filter(|r| r.algorithm == baseline)
+        .map(|r| (r.input.clone(), r.size, r.mean.as_nanos() as f64))
+        .collect();
+    for r in results.iter_mut() {
+        r.speedup = baseline_means
+            .iter()
+            .find(|(input, size, _)| *input == r.input && *size == r.size)
+            .map(|(_, _, base)| base / (r.mean.as_nanos() as f64).max(1.0));
+    }
+}
+
+pub fn write_csv<W: Write>(results: &[BenchResult], mut out: W) -> io::Result<()> {
+    writeln!(out, "algorithm,input,size,runs,mean_ns,median_ns,stddev_ns,min_ns,allocations,speedup")?;
+    for r in results {
+        let speedup = r.speedup.map(|s| format!("{s:.4}")).unwrap_or_default();
+        writeln!(out, "{},{},{},{},{},{},{},{},{},{}",
+            r.algorithm, r.input, r.size, r.runs,
+            r.mean.as_nanos(), r.median.as_nanos(), r.stddev.as_nanos(), r.min.as_nanos(), r.allocations, speedup)?;
+    }
+    Ok(())
+}
+
+pub fn write_json<W: Write>(results: &[BenchResult], mut out: W) -> io::Result<()> {
+    let rows: Vec<serde_json::Value> = results
+        .iter()
+        .map(|r| serde_json::json!({
+            "algorithm": r.algorithm,
+            "input": r.input,
+            "size": r.size,
+            "runs": r.runs,
+            "mean_ns": r.mean.as_nanos() as u64,
+            "median_ns": r.median.as_nanos() as u64,
+            "stddev_ns": r.stddev.as_nanos() as u64,
+            "min_ns": r.min.as_nanos() as u64,
+            "allocations": r.allocations,
+            "speedup": r.speedup,
+        }))
+        .collect();
+    serde_json::to_writer_pretty(&mut out, &rows)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speedups_are_relative_to_baseline() {
+        let timed = |algorithm: &str, size: usize, nanos: u64| {
+            summarize(algorithm, "random", size, vec![Duration::from_nanos(nanos)], 0)
+        };
+        let mut results = vec![timed("std", 10, 100), timed("quick", 10, 50), timed("quick", 20, 50)];
+        compute_speedups(&mut results, "std");
+        assert_eq!(results[0].speedup, Some(1.0));
+        assert_eq!(results[1].speedup, Some(2.0));
+        assert_eq!(results[2].speedup, None); // no baseline at that size
+    }
+
+    #[test]
+    fn summary_statistics() {
+        let timings = [4, 1, 3, 2].iter().map(|&n| Duration::from_nanos(n)).collect();
+        let r = summarize("test", "random", 4, timings, 3);
+        assert_eq!(r.runs, 4);
+        assert_eq!(r.min, Duration::from_nanos(1));
+        assert_eq!(r.median, Duration::from_nanos(2)); // 2.5 truncated
+        assert_eq!(r.mean, Duration::from_nanos(2));
+        assert_eq!(r.stddev, Duration::from_nanos(1)); // sqrt(1.25)
+        assert_eq!(r.allocations, 3);
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::kway::merge_k_iter;
+use crate::sort;
+
+// each run being merged holds a read buffer of this size
+const RUN_BUFFER: usize = 8 * 1024;
+
+// sorts a file of newline separated integers that may not fit in memory:
+// chunks of at most mem_limit bytes are sorted into run files, then the runs are merged
+// a few at a time, as many as mem_limit has read buffers for
+pub fn external_sort(input_path: &str, output_path: &str, mem_limit: usize) -> io::Result<()> {
+    let run_len = (mem_limit / mem::size_of::<i64>()).max(1);
+    let fan_in = (mem_limit / RUN_BUFFER).max(2);
+    let run_dir = run_dir()?;
+
+    let result = write_runs(input_path, &run_dir, run_len)
+        .and_then(|runs| merge_runs(runs, &run_dir, output_path, fan_in))
+        .map(|_| ());
+
+    let _ = fs::remove_dir_all(&run_dir);
+    result
+}
+
+fn run_dir() -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("extsort-{}-{}", process::id(), nanos));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn parse_line(line: &str) -> io::Result<i64> {
+    line.trim()
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("not an integer: {line}")))
+}
+
+fn write_runs(input_path: &str, run_dir: &Path, run_len: usize) -> io::Result<Vec<PathBuf>> {
+    let reader = BufReader::new(File::open(input_path)?);
+    let mut runs = Vec::new();
+    let mut chunk = Vec::with_capacity(run_len);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        chunk.push(parse_line(&line)?);
+        if chunk.len() == run_len {
+            runs.push(write_run(&mut chunk, run_dir, runs.len())?);
+        }
+    }
+    if !chunk.is_empty() {
+        runs.push(write_run(&mut chunk, run_dir, runs.len())?);
+    }
+    Ok(runs)
+}
+
+fn write_run(chunk: &mut Vec<i64>, run_dir: &Path, number: usize) -> io::Result<PathBuf> {
+    sort::intro(chunk);
+    let path = run_dir.join(format!("run-{number}.txt"));
+    let mut writer = BufWriter::new(File::create(&path)?);
+    for value in chunk.drain(..) {
+        writeln!(writer, "{value}")?;
+    }
+    writer.flush()?;
+    Ok(path)
+}
+
+// reads a run file back one value at a time; the first error ends the run and is kept
+// so merge_runs can report it once the merge is done
+struct RunReader {
+    lines: Lines<BufReader<File>>,
+    error: Option<io::Error>,
+}
+
+impl Iterator for RunReader {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.lines.next()?.and_then(|line| parse_line(&line)) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+// merges fan_in runs at a time into longer runs until one pass can finish the job,
+// returns the number of passes
+fn merge_runs(mut runs: Vec<PathBuf>, run_dir: &Path, output_path: &str, fan_in: usize) -> io::Result<usize> {
+    let mut passes = 1;
+    while runs.len() > fan_in {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(fan_in));
+        for group in runs.chunks(fan_in) {
+            let path = run_dir.join(format!("pass-{passes}-run-{}.txt", merged.len()));
+            merge_into(group, &path)?;
+            for run in group {
+                fs::remove_file(run)?;
+            }
+            merged.push(path);
+        }
+        runs = merged;
+        passes += 1;
+    }
+    merge_into(&runs, Path::new(output_path))?;
+    Ok(passes)
+}
+
+fn merge_into(runs: &[PathBuf], output_path: &Path) -> io::Result<()> {
+    // the merge holds one value and one read buffer per run
+    let mut readers = Vec::with_capacity(runs.len());
+    for path in runs {
+        readers.push(RunReader {
+            lines: BufReader::with_capacity(RUN_BUFFER, File::open(path)?).lines(),
+            error: None,
+        });
+    }
+
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    let mut merged = merge_k_iter(readers);
+    for value in merged.by_ref() {
+        writeln!(writer, "{value}")?;
+    }
+    if let Some(e) = merged.into_sources().into_iter().find_map(|r| r.error) {
+        return Err(e);
+    }
+    writer.flush()
+}
+
+pub fn generate_input(path: &str, target_bytes: u64, seed: u64) -> io::Result<u64> {
+    // writes random integers until the file reaches roughly target_bytes, returns the count
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut written = 0;
+    let mut count = 0;
+    let mut x = seed.max(1);
+    while written < target_bytes {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        let line = format!("{}\n", x as i64);
+        writer.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_across_many_runs() {
+        let dir = run_dir().unwrap();
+        let input = dir.join("input.txt");
+        let output = dir.join("output.txt");
+        let input = input.to_str().unwrap();
+        let output = output.to_str().unwrap();
+
+        generate_input(input, 4_000, 7).unwrap();
+        // 64 bytes is 8 values per run, so this merges a couple hundred runs
+        external_sort(input, output, 64).unwrap();
+
+        let read = |path: &str| -> Vec<i64> {
+            fs::read_to_string(path).unwrap().lines().map(|l| l.parse().unwrap()).collect()
+        };
+        let mut expected = read(input);
+        expected.sort();
+        assert_eq!(read(output), expected);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn merges_in_passes_when_runs_outnumber_the_fan_in() {
+        let dir = run_dir().unwrap();
+        let input = dir.join("input.txt");
+        let output = dir.join("output.txt");
+        let input = input.to_str().unwrap();
+        let output = output.to_str().unwrap();
+
+        let count = generate_input(input, 2_000, 11).unwrap();
+        let runs = write_runs(input, &dir, 4).unwrap();
+        assert!(runs.len() > 9);
+        // three at a time can't merge more than nine runs in two passes
+        assert!(merge_runs(runs, &dir, output, 3).unwrap() > 2);
+
+        let values: Vec<i64> = fs::read_to_string(output).unwrap().lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(values.len() as u64, count);
+        assert!(values.is_sorted());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
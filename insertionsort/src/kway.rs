@@ -0,0 +1,69 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// lazily merges any number of sorted iterators, holding one pending value per source
+pub struct MergeK<T, I> {
+    sources: Vec<I>,
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+}
+
+pub fn merge_k_iter<T: Ord, I: Iterator<Item = T>>(mut sources: Vec<I>) -> MergeK<T, I> {
+    let mut heap = BinaryHeap::with_capacity(sources.len());
+    for (i, source) in sources.iter_mut().enumerate() {
+        if let Some(value) = source.next() {
+            heap.push(Reverse((value, i)));
+        }
+    }
+    MergeK { sources, heap }
+}
+
+pub fn merge_k<T: Ord + Clone>(sorted_slices: &[&[T]]) -> Vec<T> {
+    // ties are broken by slice position, so equal values keep their slice order
+    let total = sorted_slices.iter().map(|s| s.len()).sum();
+    let mut merged = Vec::with_capacity(total);
+    merged.extend(merge_k_iter(sorted_slices.iter().map(|s| s.iter()).collect()).cloned());
+    merged
+}
+
+impl<T, I> MergeK<T, I> {
+    pub fn into_sources(self) -> Vec<I> {
+        self.sources
+    }
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for MergeK<T, I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let Reverse((value, i)) = self.heap.pop()?;
+        if let Some(next) = self.sources[i].next() {
+            self.heap.push(Reverse((next, i)));
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stability::Tagged;
+
+    #[test]
+    fn merges_many_slices() {
+        let a = [1, 4, 9];
+        let b = [2, 3, 10, 11];
+        let c: [i32; 0] = [];
+        let d = [0, 4, 4];
+        assert_eq!(merge_k(&[&a, &b, &c, &d]), vec![0, 1, 2, 3, 4, 4, 4, 9, 10, 11]);
+        assert_eq!(merge_k::<i32>(&[]), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn ties_keep_slice_order() {
+        // Tagged only compares keys, so the indices show which slice each value came from
+        let a = [Tagged { key: 1, index: 0 }, Tagged { key: 2, index: 0 }];
+        let b = [Tagged { key: 1, index: 1 }, Tagged { key: 2, index: 1 }];
+        let sources: Vec<usize> = merge_k(&[&a, &b]).iter().map(|t| t.index).collect();
+        assert_eq!(sources, vec![0, 1, 0, 1]);
+    }
+}
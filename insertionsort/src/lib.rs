@@ -0,0 +1,7 @@
+pub mod alloc;
+pub mod bench;
+pub mod external;
+pub mod kway;
+pub mod plot;
+pub mod sort;
+pub mod stability;
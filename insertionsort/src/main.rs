@@ -1,34 +1,319 @@
-fn bubble_sort(arr: &mut [i32]) {
-    for i in 0..arr.len() {
-        for j in 0..(arr.len() - i - 1) {
-            if arr[j] > arr[j+1] {
-                arr.swap(j, j+1);
+use std::fs::{self, File};
+use std::io;
+use std::process;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, ValueEnum};
+use concurrency::metrics::{self, Reporter};
+
+use insertionsort::alloc::CountingAlloc;
+use insertionsort::bench::{self, random_u64s, random_vec, BenchResult, Harness};
+use insertionsort::sort::{self, Algorithm, Primitive, RadixKey};
+use insertionsort::stability;
+use insertionsort::{external, plot};
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// above this size the quadratic sorts only run when asked for by name
+const QUADRATIC_LIMIT: usize = 100_000;
+
+#[derive(Parser)]
+#[command(about = "Benchmarks the sorting algorithms in the sort module")]
+struct Cli {
+    /// Algorithms to run (default: all)
+    #[arg(short, long, value_delimiter = ',')]
+    algorithms: Vec<String>,
+
+    /// Input sizes
+    #[arg(short, long, value_delimiter = ',', default_values_t = [100, 1_000, 10_000])]
+    sizes: Vec<usize>,
+
+    /// Input distributions
+    #[arg(short, long, value_enum, value_delimiter = ',', default_values_t = [Distribution::Random])]
+    distributions: Vec<Distribution>,
+
+    /// Timed runs per algorithm and input
+    #[arg(short, long, default_value_t = 10)]
+    iterations: usize,
+
+    /// Untimed runs before measuring
+    #[arg(short, long, default_value_t = 2)]
+    warmups: usize,
+
+    /// Size of the rayon thread pool (default: one per core)
+    #[arg(short, long)]
+    threads: Option<usize>,
+
+    /// Parallel merge threshold, a number or `auto` to calibrate it
+    #[arg(long)]
+    threshold: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Write csv/json output to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Show a live count of finished runs on stderr
+    #[arg(long)]
+    progress: bool,
+
+    /// Also draw an SVG chart of the first distribution
+    #[arg(long)]
+    plot: Option<String>,
+
+    /// Skip the std and rayon baseline sorts
+    #[arg(long)]
+    no_baselines: bool,
+
+    /// Check every algorithm's stability claim instead of benchmarking
+    #[arg(long)]
+    verify_stability: bool,
+
+    /// Benchmark the external sort on a generated file of this many MB instead
+    #[arg(long)]
+    external: Option<u64>,
+
+    /// Memory budget in MB for --external
+    #[arg(long, default_value_t = 64)]
+    mem_limit: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    FewUnique,
+    OrganPipe,
+    RandomU64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+impl Distribution {
+    fn label(self) -> &'static str {
+        match self {
+            Distribution::Random => "random",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::FewUnique => "few unique",
+            Distribution::OrganPipe => "organ pipe",
+            Distribution::RandomU64 => "random u64",
+        }
+    }
+
+    fn generate(self, len: usize) -> Vec<i32> {
+        let random = random_vec(len, len as u64);
+        match self {
+            Distribution::Random | Distribution::RandomU64 => random,
+            Distribution::Sorted => {
+                let mut sorted = random;
+                sorted.sort();
+                sorted
+            }
+            Distribution::Reversed => {
+                let mut reversed = random;
+                reversed.sort_by(|a, b| b.cmp(a));
+                reversed
             }
+            Distribution::FewUnique => random.iter().map(|x| x % 10).collect(),
+            // sorted ascending then descending, a classic median-of-three killer
+            Distribution::OrganPipe => (0..len as i32 / 2).chain((0..len as i32 / 2).rev()).collect(),
+        }
+    }
+}
+
+fn selected<T>(algorithms: Vec<Algorithm<T>>, names: &[String], size: usize) -> Vec<Algorithm<T>> {
+    if names.is_empty() {
+        algorithms
+            .into_iter()
+            .filter(|a| size <= QUADRATIC_LIMIT || !["bubble", "insertion", "insertion_fast"].contains(&a.name))
+            .collect()
+    } else {
+        algorithms.into_iter().filter(|a| names.iter().any(|n| n == a.name)).collect()
+    }
+}
+
+fn run_matrix<T: Primitive + Send + RadixKey>(cli: &Cli, harness: &Harness, threshold: usize, input: &str, data: &[T], skip: &[&str], progress: Option<&Reporter>) -> Vec<BenchResult> {
+    let mut algorithms = if cli.no_baselines { Vec::new() } else { sort::baselines::<T>() };
+    let mut ours = sort::algorithms::<T>();
+    ours.extend(sort::fast_paths::<T>());
+    algorithms.extend(selected(ours, &cli.algorithms, data.len()));
+
+    let mut results = Vec::new();
+    for a in algorithms {
+        if skip.contains(&a.name) {
+            continue;
+        }
+        let result = if a.name == "merge_par" {
+            harness.run(a.name, input, |arr: &mut [T]| sort::merge_par_with_threshold(arr, threshold), data)
+        } else {
+            harness.run(a.name, input, a.sort, data)
+        };
+        results.push(result);
+        if let Some(reporter) = progress {
+            reporter.add(1);
         }
     }
+
+    bench::compute_speedups(&mut results, sort::BASELINE);
+    if let Format::Table = cli.format {
+        for result in &results {
+            result.print();
+        }
+    }
+    results
 }
 
-fn insertion_sort<T: Ord + Copy>(arr: &mut [T]) {
-    // sorts array in place
-    for i in 1..arr.len() {
-        let key = arr[i];
-        let mut j = i;
-        while j > 0 && arr[j-1] > key {
-            arr[j] = arr[j-1];
-            j -= 1;
+fn auto_tune_threshold() -> usize {
+    // times merge_par on a fixed input for a range of thresholds and keeps the fastest
+    let data = random_vec(200_000, 0x5eed);
+    let mut best = (sort::DEFAULT_THRESHOLD, u128::MAX);
+    for threshold in (8..=16).map(|p| 1usize << p) {
+        let mut elapsed = u128::MAX;
+        for _ in 0..3 {
+            let mut arr = data.clone();
+            let start = Instant::now();
+            sort::merge_par_with_threshold(&mut arr, threshold);
+            elapsed = elapsed.min(start.elapsed().as_nanos());
         }
-        arr[j] = key;
+        if elapsed < best.1 {
+            best = (threshold, elapsed);
+        }
+    }
+    best.0
+}
+
+fn verify_stability() {
+    println!("{:>10} | {:>8} | {:>8}", "Algorithm", "Claimed", "Observed");
+    for report in stability::verify(&sort::algorithms()) {
+        let status = if report.claimed && !report.observed { "  <-- claim violated" } else { "" };
+        println!("{:>10} | {:>8} | {:>8}{}", report.name, report.claimed, report.observed, status);
     }
 }
 
+fn benchmark_external(size_mb: u64, mem_limit_mb: usize) -> io::Result<()> {
+    let dir = std::env::temp_dir();
+    let input = dir.join("extsort-input.txt");
+    let output = dir.join("extsort-output.txt");
+    let input = input.to_string_lossy();
+    let output = output.to_string_lossy();
+
+    println!("Generating {size_mb} MB of input at {input}...");
+    let count = external::generate_input(&input, size_mb * 1024 * 1024, 42)?;
+
+    println!("Sorting {count} values with a {mem_limit_mb} MB memory budget...");
+    let start = Instant::now();
+    external::external_sort(&input, &output, mem_limit_mb * 1024 * 1024)?;
+    println!("External sort took {:?}", start.elapsed());
+
+    fs::remove_file(&*input)?;
+    fs::remove_file(&*output)?;
+    Ok(())
+}
+
 fn main() {
-    let mut a = [1, 10, 3, 8, 12, 2, 1, 0, 13, 10001, -5];
-    println!("Unsorted: {:?}", a);
-    insertion_sort(&mut a);
-    println!("Sorted:   {:?}", a);
-
-    let mut b = [1, 10, 3, 8, 12, 2, 1, 0, 13, 10001, -5];
-    println!("Unsorted: {:?}", b);
-    bubble_sort(&mut b);
-    println!("Sorted:   {:?}", b);
+    let cli = Cli::parse();
+
+    let mut known = sort::algorithms::<i32>();
+    known.extend(sort::fast_paths::<i32>());
+    if let Some(name) = cli.algorithms.iter().find(|n| !known.iter().any(|a| a.name == n.as_str())) {
+        let names: Vec<&str> = known.iter().map(|a| a.name).collect();
+        eprintln!("Error: unknown algorithm '{}', expected one of: {}", name, names.join(", "));
+        process::exit(1);
+    }
+
+    if let Some(threads) = cli.threads
+        && let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global()
+    {
+        eprintln!("Error: could not size the thread pool: {e}");
+        process::exit(1);
+    }
+
+    if cli.verify_stability {
+        verify_stability();
+        return;
+    }
+
+    if let Some(size_mb) = cli.external {
+        if let Err(e) = benchmark_external(size_mb, cli.mem_limit) {
+            eprintln!("Error: external sort failed: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    let threshold = match cli.threshold.as_deref() {
+        Some("auto") => auto_tune_threshold(),
+        Some(value) => match value.parse::<usize>() {
+            Ok(threshold) => threshold.max(1),
+            Err(_) => {
+                eprintln!("Error: invalid threshold: {value}");
+                process::exit(1);
+            }
+        },
+        None => sort::DEFAULT_THRESHOLD,
+    };
+
+    let harness = Harness::new(cli.warmups, cli.iterations);
+    let mut results = Vec::new();
+
+    if let Format::Table = cli.format {
+        println!("Parallel merge threshold: {}, threads: {}", threshold, rayon::current_num_threads());
+    }
+
+    let (reporter, aggregator) = if cli.progress {
+        let (reporter, aggregator) = metrics::start(None, Duration::from_millis(200), io::stderr());
+        (Some(reporter), Some(aggregator))
+    } else {
+        (None, None)
+    };
+
+    for &distribution in &cli.distributions {
+        for &size in &cli.sizes {
+            let input = distribution.label();
+            if distribution == Distribution::RandomU64 {
+                // full-width keys: counting sort's range would be enormous, so it sits this one out
+                let data = random_u64s(size, size as u64);
+                results.extend(run_matrix(&cli, &harness, threshold, input, &data, &["counting"], reporter.as_ref()));
+            } else {
+                let data = distribution.generate(size);
+                results.extend(run_matrix(&cli, &harness, threshold, input, &data, &[], reporter.as_ref()));
+            }
+        }
+    }
+
+    drop(reporter);
+    if let Some(aggregator) = aggregator {
+        aggregator.finish();
+    }
+
+    let written = match (cli.format, &cli.output) {
+        (Format::Table, _) => Ok(()),
+        (Format::Csv, Some(path)) => File::create(path).and_then(|f| bench::write_csv(&results, f)),
+        (Format::Csv, None) => bench::write_csv(&results, io::stdout().lock()),
+        (Format::Json, Some(path)) => File::create(path).and_then(|f| bench::write_json(&results, f)),
+        (Format::Json, None) => bench::write_json(&results, io::stdout().lock()),
+    };
+    if let Err(e) = written {
+        eprintln!("Error: failed to write results: {e}");
+        process::exit(1);
+    }
+
+    if let Some(path) = &cli.plot {
+        let input = cli.distributions.first().map(|d| d.label()).unwrap_or("random");
+        match plot::plot_results(&results, input, path) {
+            Ok(_) => eprintln!("Wrote chart to {}", path),
+            Err(e) => eprintln!("Failed to plot {}: {}", path, e),
+        }
+    }
 }
@@ -0,0 +1,71 @@
+use std::error::Error;
+
+use plotters::prelude::*;
+
+use crate::bench::BenchResult;
+
+// draws mean time against input size for every algorithm benchmarked on `input`, on log-log axes
+pub fn plot_results(results: &[BenchResult], input: &str, filepath: &str) -> Result<(), Box<dyn Error>> {
+    let points: Vec<&BenchResult> = results.iter().filter(|r| r.input == input).collect();
+    if points.is_empty() {
+        return Err(format!("no results for input '{input}'").into());
+    }
+
+    let mut algorithms: Vec<&str> = Vec::new();
+    for r in &points {
+        if !algorithms.contains(&r.algorithm.as_str()) {
+            algorithms.push(&r.algorithm);
+        }
+    }
+
+    let nanos = |r: &BenchResult| (r.mean.as_nanos() as f64).max(1.0);
+    let min_size = points.iter().map(|r| r.size).min().unwrap_or(1) as f64;
+    let max_size = points.iter().map(|r| r.size).max().unwrap_or(1) as f64;
+    let min_time = points.iter().map(|r| nanos(r)).fold(f64::MAX, f64::min);
+    let max_time = points.iter().map(|r| nanos(r)).fold(1.0, f64::max);
+
+    let root = SVGBackend::new(filepath, (1024, 768)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Sort time vs input size ({input})"), ("sans-serif", 24))
+        .margin(20)
+        .x_label_area_size(50)
+        .y_label_area_size(80)
+        .build_cartesian_2d(
+            (min_size * 0.8..max_size * 1.25).log_scale(),
+            (min_time * 0.8..max_time * 1.25).log_scale(),
+        )?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Input size (elements)")
+        .y_desc("Mean time (ns)")
+        .draw()?;
+
+    for (i, algorithm) in algorithms.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let mut series: Vec<(f64, f64)> = points
+            .iter()
+            .filter(|r| r.algorithm == *algorithm)
+            .map(|r| (r.size as f64, nanos(r)))
+            .collect();
+        series.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        chart
+            .draw_series(LineSeries::new(series.clone(), color.stroke_width(2)))?
+            .label(*algorithm)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+        chart.draw_series(series.into_iter().map(|p| Circle::new(p, 3, color.filled())))?;
+    }
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    Ok(())
+}
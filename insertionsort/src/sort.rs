@@ -0,0 +1,655 @@
+use std::cmp::Ordering;
+
+use rayon::slice::ParallelSliceMut;
+
+// every algorithm sorts a mutable slice in place, so they can be swapped for one another
+pub type SortFn<T> = fn(&mut [T]);
+
+// a named sort plus whether it keeps equal elements in their original order
+#[derive(Clone, Copy)]
+pub struct Algorithm<T> {
+    pub name: &'static str,
+    pub sort: SortFn<T>,
+    pub stable: bool,
+}
+
+pub fn algorithms<T: Ord + Clone + Send + RadixKey>() -> Vec<Algorithm<T>> {
+    vec![
+        Algorithm { name: "bubble", sort: bubble, stable: true },
+        Algorithm { name: "insertion", sort: insertion, stable: true },
+        Algorithm { name: "merge", sort: merge, stable: true },
+        Algorithm { name: "merge_par", sort: merge_par, stable: true },
+        Algorithm { name: "quick", sort: quick, stable: false },
+        Algorithm { name: "heap", sort: heap, stable: false },
+        Algorithm { name: "intro", sort: intro, stable: false },
+        Algorithm { name: "radix", sort: radix, stable: true },
+        Algorithm { name: "counting", sort: counting, stable: true },
+    ]
+}
+
+// the library sorts everything here is measured against; "std" is the speedup baseline
+pub const BASELINE: &str = "std";
+
+pub fn baselines<T: Ord + Send>() -> Vec<Algorithm<T>> {
+    vec![
+        Algorithm { name: BASELINE, sort: std_stable, stable: true },
+        Algorithm { name: "std_unstable", sort: std_unstable, stable: false },
+        Algorithm { name: "rayon_par", sort: rayon_par, stable: true },
+    ]
+}
+
+fn std_stable<T: Ord>(arr: &mut [T]) {
+    arr.sort();
+}
+
+fn std_unstable<T: Ord>(arr: &mut [T]) {
+    arr.sort_unstable();
+}
+
+fn rayon_par<T: Ord + Send>(arr: &mut [T]) {
+    arr.par_sort();
+}
+
+// partitions at or below this size are handed to insertion sort
+const INSERTION_CUTOFF: usize = 16;
+
+// slices at or below this size are merge sorted on the current thread by merge_par;
+// merge_par_with_threshold takes a different cutoff
+pub const DEFAULT_THRESHOLD: usize = 1000;
+
+// maps a value to an unsigned key with the same ordering, so radix/counting sort can bucket it
+pub trait RadixKey {
+    fn to_key(&self) -> u64;
+}
+
+impl RadixKey for u32 {
+    fn to_key(&self) -> u64 {
+        *self as u64
+    }
+}
+
+impl RadixKey for u64 {
+    fn to_key(&self) -> u64 {
+        *self
+    }
+}
+
+impl RadixKey for i32 {
+    fn to_key(&self) -> u64 {
+        // flipping the sign bit puts negatives below positives
+        (*self as u32 ^ 0x8000_0000) as u64
+    }
+}
+
+impl RadixKey for i64 {
+    fn to_key(&self) -> u64 {
+        *self as u64 ^ 0x8000_0000_0000_0000
+    }
+}
+
+// small Copy keys where comparisons compile to a cmov and moves are plain memcpys
+pub trait Primitive: Copy + Ord {}
+
+impl Primitive for i32 {}
+impl Primitive for i64 {}
+impl Primitive for u32 {}
+impl Primitive for u64 {}
+
+pub fn fast_paths<T: Primitive>() -> Vec<Algorithm<T>> {
+    vec![
+        Algorithm { name: "insertion_fast", sort: insertion_fast, stable: true },
+        Algorithm { name: "merge_fast", sort: merge_fast, stable: true },
+    ]
+}
+
+pub fn bubble<T: Ord>(arr: &mut [T]) {
+    bubble_by(arr, T::cmp);
+}
+
+pub fn bubble_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    bubble_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn bubble_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    for i in 0..arr.len() {
+        for j in 0..(arr.len() - i - 1) {
+            if cmp(&arr[j], &arr[j+1]) == Ordering::Greater {
+                arr.swap(j, j+1);
+            }
+        }
+    }
+}
+
+pub fn insertion<T: Ord>(arr: &mut [T]) {
+    insertion_by(arr, T::cmp);
+}
+
+pub fn insertion_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    insertion_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn insertion_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    // sorts array in place: find where arr[i] belongs, then rotate it into place
+    // so nothing needs to be copied or cloned
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && cmp(&arr[j-1], &arr[i]) == Ordering::Greater {
+            j -= 1;
+        }
+        arr[j..=i].rotate_right(1);
+    }
+}
+
+pub fn insertion_fast<T: Primitive>(arr: &mut [T]) {
+    // same as insertion, but the key stays in a register and the shift is one copy_within
+    for i in 1..arr.len() {
+        let key = arr[i];
+        let mut j = i;
+        while j > 0 && arr[j-1] > key {
+            j -= 1;
+        }
+        arr.copy_within(j..i, j + 1);
+        arr[j] = key;
+    }
+}
+
+pub fn merge<T: Ord + Clone>(arr: &mut [T]) {
+    merge_by(arr, T::cmp);
+}
+
+pub fn merge_by_key<T: Clone, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    merge_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn merge_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    // one scratch buffer for the whole sort; each merge only ever needs room for its left half
+    let mut scratch = arr[..arr.len() / 2].to_vec();
+    merge_recursive(arr, &mut scratch, &mut cmp);
+}
+
+fn merge_recursive<T: Clone, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], scratch: &mut [T], cmp: &mut F) {
+    // top-down merge sort
+    if arr.len() <= 1 {
+        return;
+    }
+    let mid = arr.len() / 2;
+    merge_recursive(&mut arr[..mid], scratch, cmp);
+    merge_recursive(&mut arr[mid..], scratch, cmp);
+    merge_halves(arr, mid, scratch, cmp);
+}
+
+pub fn merge_par<T: Ord + Clone + Send>(arr: &mut [T]) {
+    merge_par_with_threshold(arr, DEFAULT_THRESHOLD);
+}
+
+pub fn merge_par_with_threshold<T: Ord + Clone + Send>(arr: &mut [T], threshold: usize) {
+    merge_par_by_threshold(arr, threshold, &T::cmp);
+}
+
+pub fn merge_par_by_key<T: Clone + Send, K: Ord, F: Fn(&T) -> K + Sync>(arr: &mut [T], f: F) {
+    merge_par_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn merge_par_by<T: Clone + Send, F: Fn(&T, &T) -> Ordering + Sync>(arr: &mut [T], cmp: F) {
+    merge_par_by_threshold(arr, DEFAULT_THRESHOLD, &cmp);
+}
+
+fn merge_par_by_threshold<T, F>(arr: &mut [T], threshold: usize, cmp: &F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut scratch = arr[..arr.len() / 2].to_vec();
+    merge_par_recursive(arr, &mut scratch, threshold, cmp);
+}
+
+fn merge_par_recursive<T, F>(arr: &mut [T], scratch: &mut [T], threshold: usize, cmp: &F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // same as merge, but sorts the halves in parallel on the rayon pool while the input is above threshold
+    if arr.len() <= threshold.max(1) {
+        merge_recursive(arr, scratch, &mut |a, b| cmp(a, b));
+        return;
+    }
+    let mid = arr.len() / 2;
+    let (left, right) = arr.split_at_mut(mid);
+    // the halves need at most mid / 2 and (len - mid) / 2 of scratch, so they can share it without overlap
+    let (left_scratch, right_scratch) = scratch.split_at_mut(mid / 2);
+    rayon::join(
+        || merge_par_recursive(left, left_scratch, threshold, cmp),
+        || merge_par_recursive(right, right_scratch, threshold, cmp),
+    );
+    merge_halves(arr, mid, scratch, &mut |a, b| cmp(a, b));
+}
+
+fn merge_halves<T: Clone, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mid: usize, scratch: &mut [T], cmp: &mut F) {
+    // merges the sorted runs arr[..mid] and arr[mid..]; ties go to the left run, which keeps it stable.
+    // only the left run is copied out: the write position k never passes the right run's read position j
+    scratch[..mid].clone_from_slice(&arr[..mid]);
+
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < mid && j < arr.len() {
+        if cmp(&scratch[i], &arr[j]) != Ordering::Greater {
+            arr[k] = scratch[i].clone();
+            i += 1;
+        } else {
+            // arr[k] was already copied into scratch, so the stale value can go to j
+            arr.swap(k, j);
+            j += 1;
+        }
+        k += 1;
+    }
+    // any rest of the right run is already in place
+    arr[k..k + mid - i].clone_from_slice(&scratch[i..mid]);
+}
+
+pub fn merge_fast<T: Primitive>(arr: &mut [T]) {
+    // merge sort for Copy keys: insertion_fast on small runs and a branchless merge step
+    let mut scratch = arr[..arr.len() / 2].to_vec();
+    merge_fast_recursive(arr, &mut scratch);
+}
+
+fn merge_fast_recursive<T: Primitive>(arr: &mut [T], scratch: &mut [T]) {
+    if arr.len() <= INSERTION_CUTOFF {
+        insertion_fast(arr);
+        return;
+    }
+    let mid = arr.len() / 2;
+    merge_fast_recursive(&mut arr[..mid], scratch);
+    merge_fast_recursive(&mut arr[mid..], scratch);
+    if arr[mid - 1] <= arr[mid] {
+        return; // already in order, common for sorted input
+    }
+
+    let left = &mut scratch[..mid];
+    left.copy_from_slice(&arr[..mid]);
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < mid && j < arr.len() {
+        // both candidates are read up front so the choice compiles to a select, not a branch
+        let (l, r) = (left[i], arr[j]);
+        let take_left = l <= r;
+        arr[k] = if take_left { l } else { r };
+        i += take_left as usize;
+        j += !take_left as usize;
+        k += 1;
+    }
+    arr[k..k + mid - i].copy_from_slice(&left[i..]);
+}
+
+pub fn quick<T: Ord>(arr: &mut [T]) {
+    quick_by(arr, T::cmp);
+}
+
+pub fn quick_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    quick_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn quick_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    quick_recursive(arr, &mut cmp);
+}
+
+fn quick_recursive<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], cmp: &mut F) {
+    // sorts array in place, falling back to insertion sort for small partitions
+    if arr.len() <= INSERTION_CUTOFF {
+        insertion_by(arr, &mut *cmp);
+        return;
+    }
+    let p = partition(arr, cmp);
+    let (left, right) = arr.split_at_mut(p);
+    quick_recursive(left, cmp);
+    quick_recursive(&mut right[1..], cmp);
+}
+
+fn median_of_three<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], cmp: &mut F) -> usize {
+    // orders first, middle and last so arr[0] <= arr[mid] <= arr[last]; returns mid
+    let mid = arr.len() / 2;
+    let last = arr.len() - 1;
+    if cmp(&arr[mid], &arr[0]) == Ordering::Less {
+        arr.swap(0, mid);
+    }
+    if cmp(&arr[last], &arr[0]) == Ordering::Less {
+        arr.swap(0, last);
+    }
+    if cmp(&arr[last], &arr[mid]) == Ordering::Less {
+        arr.swap(mid, last);
+    }
+    mid
+}
+
+fn partition<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], cmp: &mut F) -> usize {
+    // hoare-style partition around the median of three, returns the pivot's final index
+    let last = arr.len() - 1;
+    let mid = median_of_three(arr, cmp);
+    arr.swap(mid, last); // park the pivot at the end
+
+    let mut i = 0;
+    let mut j = last - 1;
+    loop {
+        while cmp(&arr[i], &arr[last]) == Ordering::Less {
+            i += 1;
+        }
+        while j > 0 && cmp(&arr[j], &arr[last]) == Ordering::Greater {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        // stopping on equal keys keeps runs of duplicates from degrading to O(n^2)
+        arr.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
+    arr.swap(i, last);
+    i
+}
+
+pub fn heap<T: Ord>(arr: &mut [T]) {
+    heap_by(arr, T::cmp);
+}
+
+pub fn heap_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    heap_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn heap_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    // sorts array in place: build a max-heap bottom up, then repeatedly move the max to the end
+    let len = arr.len();
+    for start in (0..len / 2).rev() {
+        sift_down(arr, start, len, &mut cmp);
+    }
+    for end in (1..len).rev() {
+        arr.swap(0, end);
+        sift_down(arr, 0, end, &mut cmp);
+    }
+}
+
+fn sift_down<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut root: usize, end: usize, cmp: &mut F) {
+    // restores the heap property for the subtree at root, only looking at arr[..end]
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= end {
+            break;
+        }
+        if child + 1 < end && cmp(&arr[child], &arr[child + 1]) == Ordering::Less {
+            child += 1;
+        }
+        if cmp(&arr[root], &arr[child]) != Ordering::Less {
+            break;
+        }
+        arr.swap(root, child);
+        root = child;
+    }
+}
+
+pub fn intro<T: Ord>(arr: &mut [T]) {
+    intro_by(arr, T::cmp);
+}
+
+pub fn intro_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut f: F) {
+    intro_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn intro_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], mut cmp: F) {
+    // quicksort until the recursion gets suspiciously deep, then heapsort; insertion sort for small slices
+    let depth_limit = 2 * (usize::BITS - arr.len().leading_zeros());
+    intro_recursive(arr, depth_limit, &mut cmp);
+}
+
+fn intro_recursive<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], depth_limit: u32, cmp: &mut F) {
+    if arr.len() <= INSERTION_CUTOFF {
+        insertion_by(arr, &mut *cmp);
+        return;
+    }
+    if depth_limit == 0 {
+        heap_by(arr, &mut *cmp);
+        return;
+    }
+    let p = partition(arr, cmp);
+    let (left, right) = arr.split_at_mut(p);
+    intro_recursive(left, depth_limit - 1, cmp);
+    intro_recursive(&mut right[1..], depth_limit - 1, cmp);
+}
+
+pub fn radix<T: RadixKey + Clone>(arr: &mut [T]) {
+    // LSD radix sort, one byte per pass; passes stop once every remaining digit is zero
+    let max_key = match arr.iter().map(|v| v.to_key()).max() {
+        Some(k) => k,
+        None => return,
+    };
+    let mut buffer = arr.to_vec();
+    let mut shift = 0;
+    while shift < 64 && (max_key >> shift) > 0 {
+        let mut counts = [0usize; 256];
+        for v in arr.iter() {
+            counts[((v.to_key() >> shift) & 0xff) as usize] += 1;
+        }
+        // turn counts into starting offsets
+        let mut total = 0;
+        for count in counts.iter_mut() {
+            let c = *count;
+            *count = total;
+            total += c;
+        }
+        for v in arr.iter() {
+            let digit = ((v.to_key() >> shift) & 0xff) as usize;
+            buffer[counts[digit]] = v.clone();
+            counts[digit] += 1;
+        }
+        arr.clone_from_slice(&buffer);
+        shift += 8;
+    }
+}
+
+// how many count slots per element counting sort will allocate before it gives way to radix
+const COUNTING_RANGE_FACTOR: u64 = 16;
+
+pub fn counting<T: RadixKey + Clone>(arr: &mut [T]) {
+    // stable counting sort over the key range; only sensible when max - min is small, so a
+    // range much wider than the input is handed to radix sort instead
+    let (min, max) = match (arr.iter().map(|v| v.to_key()).min(), arr.iter().map(|v| v.to_key()).max()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return,
+    };
+    let limit = (arr.len() as u64).saturating_mul(COUNTING_RANGE_FACTOR).max(256);
+    let range = match (max - min).checked_add(1) {
+        Some(range) if range <= limit => range,
+        _ => return radix(arr),
+    };
+    let mut counts = vec![0usize; range as usize];
+    for v in arr.iter() {
+        counts[(v.to_key() - min) as usize] += 1;
+    }
+    let mut total = 0;
+    for count in counts.iter_mut() {
+        let c = *count;
+        *count = total;
+        total += c;
+    }
+    let mut output = arr.to_vec();
+    for v in arr.iter() {
+        let slot = &mut counts[(v.to_key() - min) as usize];
+        output[*slot] = v.clone();
+        *slot += 1;
+    }
+    arr.clone_from_slice(&output);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cases() -> Vec<Vec<i32>> {
+        vec![
+            vec![],
+            vec![1],
+            vec![2, 1],
+            vec![1, 10, 3, 8, 12, 2, 1, 0, 13, 10001, -5],
+            (0..100).collect(),
+            (0..100).rev().collect(),
+            (0..500).map(|i| (i * 7919) % 13 - 6).collect(),
+            (0..3000).map(|i| (i * 104729) % 1009).collect(),
+        ]
+    }
+
+    fn check(sort: SortFn<i32>) {
+        for case in cases() {
+            let mut expected = case.clone();
+            expected.sort();
+            let mut actual = case;
+            sort(&mut actual);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn bubble_sorts() {
+        check(bubble);
+    }
+
+    #[test]
+    fn insertion_sorts() {
+        check(insertion);
+    }
+
+    #[test]
+    fn merge_sorts() {
+        check(merge);
+    }
+
+    #[test]
+    fn merge_par_sorts() {
+        check(merge_par);
+    }
+
+    #[test]
+    fn merge_par_respects_threshold() {
+        for threshold in [1, 2, 16, 10_000] {
+            for case in cases() {
+                let mut expected = case.clone();
+                expected.sort();
+                let mut actual = case;
+                merge_par_with_threshold(&mut actual, threshold);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn fast_paths_sort() {
+        check(insertion_fast);
+        check(merge_fast);
+
+        let mut wide = vec![u64::MAX, 0, 1 << 40, 7, u64::MAX - 1];
+        merge_fast(&mut wide);
+        assert_eq!(wide, vec![0, 7, 1 << 40, u64::MAX - 1, u64::MAX]);
+    }
+
+    #[test]
+    fn quick_sorts() {
+        check(quick);
+    }
+
+    #[test]
+    fn heap_sorts() {
+        check(heap);
+    }
+
+    #[test]
+    fn intro_sorts() {
+        check(intro);
+    }
+
+    #[test]
+    fn radix_sorts() {
+        check(radix);
+
+        let mut wide = vec![u64::MAX, 0, 1 << 40, 7, u64::MAX - 1];
+        radix(&mut wide);
+        assert_eq!(wide, vec![0, 7, 1 << 40, u64::MAX - 1, u64::MAX]);
+    }
+
+    #[test]
+    fn counting_sorts() {
+        check(counting);
+
+        // ranges too wide to count fall back to radix rather than overflowing or allocating
+        let mut wide = vec![u32::MAX, 0, 5];
+        counting(&mut wide);
+        assert_eq!(wide, vec![0, 5, u32::MAX]);
+        let mut full = vec![u64::MAX, 0, u64::MAX - 1, 1];
+        counting(&mut full);
+        assert_eq!(full, vec![0, 1, u64::MAX - 1, u64::MAX]);
+    }
+
+    #[test]
+    fn sorts_non_copy_types() {
+        let words = ["pear", "apple", "fig", "banana", "apple", "cherry"];
+        let mut expected: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        expected.sort();
+        let sorts: [SortFn<String>; 7] = [bubble, insertion, merge, merge_par, quick, heap, intro];
+        for sort in sorts {
+            let mut actual: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+            sort(&mut actual);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Rectangle {
+        width: u32,
+        height: u32,
+    }
+
+    type KeySort = fn(&mut [Rectangle], fn(&Rectangle) -> u32);
+    type CmpSort = fn(&mut [Rectangle], fn(&Rectangle, &Rectangle) -> Ordering);
+
+    fn rectangles() -> Vec<Rectangle> {
+        vec![
+            Rectangle { width: 10, height: 1 },
+            Rectangle { width: 3, height: 5 },
+            Rectangle { width: 7, height: 12 },
+            Rectangle { width: 3, height: 2 },
+        ]
+    }
+
+    #[test]
+    fn by_key_sorts_structs() {
+        let sorts: [KeySort; 7] = [
+            bubble_by_key,
+            insertion_by_key,
+            merge_by_key,
+            merge_par_by_key,
+            quick_by_key,
+            heap_by_key,
+            intro_by_key,
+        ];
+        for sort in sorts {
+            let mut list = rectangles();
+            sort(&mut list, |r| r.width * r.height);
+            let areas: Vec<u32> = list.iter().map(|r| r.width * r.height).collect();
+            assert_eq!(areas, vec![6, 10, 15, 84]);
+        }
+    }
+
+    #[test]
+    fn by_sorts_with_comparator() {
+        let sorts: [CmpSort; 7] = [
+            bubble_by,
+            insertion_by,
+            merge_by,
+            merge_par_by,
+            quick_by,
+            heap_by,
+            intro_by,
+        ];
+        for sort in sorts {
+            // descending by height
+            let mut list = rectangles();
+            sort(&mut list, |a, b| b.height.cmp(&a.height));
+            let heights: Vec<u32> = list.iter().map(|r| r.height).collect();
+            assert_eq!(heights, vec![12, 5, 2, 1]);
+        }
+    }
+}
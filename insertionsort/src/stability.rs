@@ -0,0 +1,97 @@
+use std::cmp::Ordering;
+
+use crate::bench::random_vec;
+use crate::sort::{Algorithm, RadixKey, SortFn};
+
+// a value tagged with its position in the input; only the key takes part in ordering,
+// so after a stable sort equal keys must still have ascending indices
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged {
+    pub key: i32,
+    pub index: usize,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl RadixKey for Tagged {
+    fn to_key(&self) -> u64 {
+        self.key.to_key()
+    }
+}
+
+pub struct StabilityReport {
+    pub name: &'static str,
+    pub claimed: bool,
+    pub observed: bool,
+}
+
+pub fn tag(data: &[i32]) -> Vec<Tagged> {
+    data.iter()
+        .enumerate()
+        .map(|(index, &key)| Tagged { key, index })
+        .collect()
+}
+
+pub fn is_stable_on(sort: SortFn<Tagged>, data: &[i32]) -> bool {
+    let mut tagged = tag(data);
+    sort(&mut tagged);
+    tagged
+        .windows(2)
+        .all(|w| w[0].key < w[1].key || (w[0].key == w[1].key && w[0].index < w[1].index))
+}
+
+pub fn verify(algorithms: &[Algorithm<Tagged>]) -> Vec<StabilityReport> {
+    // lots of duplicate keys across a few sizes, so unstable sorts get plenty of chances to reorder ties
+    let inputs: Vec<Vec<i32>> = [10, 100, 2_000]
+        .iter()
+        .map(|&len| random_vec(len, len as u64).iter().map(|x| x % 8).collect())
+        .collect();
+
+    algorithms
+        .iter()
+        .map(|a| StabilityReport {
+            name: a.name,
+            claimed: a.stable,
+            observed: inputs.iter().all(|data| is_stable_on(a.sort, data)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::algorithms;
+
+    #[test]
+    fn stable_claims_hold() {
+        for report in verify(&algorithms()) {
+            if report.claimed {
+                assert!(report.observed, "{} claims to be stable but reordered equal keys", report.name);
+            }
+        }
+    }
+
+    #[test]
+    fn detects_instability() {
+        // heapsort moves the first of two equal maxima to the end
+        assert!(!is_stable_on(crate::sort::heap, &[1, 1]));
+    }
+}
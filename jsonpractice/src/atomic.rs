@@ -0,0 +1,114 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+// how many old copies of a data file are kept next to it: people.json.1 is the newest
+pub const DEFAULT_BACKUPS: usize = 3;
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+pub fn backup_path(path: &Path, n: usize) -> PathBuf {
+    with_suffix(path, &format!(".{}", n))
+}
+
+fn rotate_backups(path: &Path, backups: usize) -> io::Result<()> {
+    // shifts .1 -> .2 -> ... dropping the oldest, then copies the current file to .1.
+    // copying rather than renaming means `path` never disappears
+    if backups == 0 || !path.exists() {
+        return Ok(());
+    }
+    for n in (1..backups).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+pub fn write_atomic(filepath: &str, data: &[u8], backups: usize) -> io::Result<()> {
+    // the new contents go to a temp file in the same directory which is then renamed over
+    // the target, so a crash leaves either the old file or the new one, never half of each
+    let path = Path::new(filepath);
+    let temp = with_suffix(path, &format!(".tmp-{}", process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        rotate_backups(path, backups)?;
+        fs::rename(&temp, path)?;
+        sync_parent(path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    // the rename itself is only durable once the directory entry is flushed
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_rotated_backups() {
+        let dir = std::env::temp_dir().join(format!("jsonpractice-{}-atomic", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("people.json");
+        let filepath = path.to_str().unwrap();
+
+        for version in 1..=5 {
+            write_atomic(filepath, format!("v{}", version).as_bytes(), 2).unwrap();
+        }
+        let read = |p: PathBuf| fs::read_to_string(p).unwrap();
+        assert_eq!(read(path.clone()), "v5");
+        assert_eq!(read(backup_path(&path, 1)), "v4");
+        assert_eq!(read(backup_path(&path, 2)), "v3");
+        assert!(!backup_path(&path, 3).exists());
+
+        // no temp files are left behind
+        let mut entries: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["people.json", "people.json.1", "people.json.2"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_write_leaves_the_original() {
+        let dir = std::env::temp_dir().join(format!("jsonpractice-{}-atomic-fail", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // renaming a file over a directory fails, which stands in for a crash before the swap
+        let target = dir.join("people.json");
+        fs::create_dir_all(target.join("keep")).unwrap();
+
+        assert!(write_atomic(target.to_str().unwrap(), b"new", 0).is_err());
+        assert!(target.join("keep").is_dir());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
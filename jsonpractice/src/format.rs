@@ -0,0 +1,262 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::atomic::{self, DEFAULT_BACKUPS};
+use crate::person::Person;
+use crate::schema::{self, Envelope, SchemaError};
+use crate::spreadsheet::{self, CsvError};
+
+// on-disk formats for a list of people. yaml and toml sit behind cargo features of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Csv,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Format {
+    pub fn from_path(filepath: &str) -> Option<Format> {
+        let ext = Path::new(filepath).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Format::Json),
+            "ndjson" | "jsonl" => Some(Format::Ndjson),
+            "csv" => Some(Format::Csv),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Format::Yaml),
+            #[cfg(feature = "toml")]
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Csv(CsvError),
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    #[cfg(feature = "toml")]
+    TomlRead(toml::de::Error),
+    #[cfg(feature = "toml")]
+    TomlWrite(toml::ser::Error),
+    Schema(SchemaError),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Io(e) => write!(f, "file error: {}", e),
+            FormatError::Json(e) => write!(f, "invalid json: {}", e),
+            FormatError::Csv(e) => write!(f, "{}", e),
+            #[cfg(feature = "yaml")]
+            FormatError::Yaml(e) => write!(f, "invalid yaml: {}", e),
+            #[cfg(feature = "toml")]
+            FormatError::TomlRead(e) => write!(f, "invalid toml: {}", e),
+            #[cfg(feature = "toml")]
+            FormatError::TomlWrite(e) => write!(f, "could not write toml: {}", e),
+            FormatError::Schema(e) => write!(f, "unsupported file layout: {}", e),
+            FormatError::UnknownFormat(path) => write!(f, "can't tell the format of {} from its extension", path),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<io::Error> for FormatError {
+    fn from(e: io::Error) -> Self {
+        FormatError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(e: serde_json::Error) -> Self {
+        FormatError::Json(e)
+    }
+}
+
+impl From<CsvError> for FormatError {
+    fn from(e: CsvError) -> Self {
+        FormatError::Csv(e)
+    }
+}
+
+impl From<SchemaError> for FormatError {
+    fn from(e: SchemaError) -> Self {
+        FormatError::Schema(e)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for FormatError {
+    fn from(e: serde_yaml::Error) -> Self {
+        FormatError::Yaml(e)
+    }
+}
+
+pub fn load(filepath: &str) -> Result<Vec<Person>, FormatError> {
+    let format = Format::from_path(filepath)
+        .ok_or_else(|| FormatError::UnknownFormat(filepath.to_string()))?;
+    load_as(filepath, format)
+}
+
+pub fn save(people: &[Person], filepath: &str) -> Result<(), FormatError> {
+    save_with_backups(people, filepath, DEFAULT_BACKUPS)
+}
+
+pub fn save_with_backups(people: &[Person], filepath: &str, backups: usize) -> Result<(), FormatError> {
+    let format = Format::from_path(filepath)
+        .ok_or_else(|| FormatError::UnknownFormat(filepath.to_string()))?;
+    let data = to_string(people, format)?;
+    atomic::write_atomic(filepath, data.as_bytes(), backups)?;
+    Ok(())
+}
+
+pub fn load_as(filepath: &str, format: Format) -> Result<Vec<Person>, FormatError> {
+    let data = fs::read_to_string(filepath)?;
+    from_str(&data, format)
+}
+
+pub fn save_as(people: &[Person], filepath: &str, format: Format) -> Result<(), FormatError> {
+    let data = to_string(people, format)?;
+    atomic::write_atomic(filepath, data.as_bytes(), DEFAULT_BACKUPS)?;
+    Ok(())
+}
+
+pub fn from_str(data: &str, format: Format) -> Result<Vec<Person>, FormatError> {
+    // ndjson and csv are one record per line with no envelope; the other formats are read
+    // as a generic document first so files written by older versions can be migrated
+    let doc: serde_json::Value = match format {
+        Format::Csv => return Ok(spreadsheet::read_people(data.as_bytes())?),
+        Format::Ndjson => {
+            return Ok(serde_json::Deserializer::from_str(data)
+                .into_iter()
+                .map(|record| record.and_then(|r| serde_json::from_value(schema::migrate_record(r))))
+                .collect::<Result<Vec<Person>, _>>()?);
+        }
+        Format::Json => serde_json::from_str(data)?,
+        #[cfg(feature = "yaml")]
+        Format::Yaml => serde_yaml::from_str(data)?,
+        #[cfg(feature = "toml")]
+        Format::Toml => toml::from_str(data).map_err(FormatError::TomlRead)?,
+    };
+    let envelope: Envelope = serde_json::from_value(schema::migrate(doc)?)?;
+    Ok(envelope.people)
+}
+
+pub fn to_string(people: &[Person], format: Format) -> Result<String, FormatError> {
+    let envelope = Envelope::new(people.to_vec());
+    let data = match format {
+        Format::Json => serde_json::to_string_pretty(&envelope)?,
+        Format::Csv => {
+            let mut data = Vec::new();
+            spreadsheet::write_people(people, &mut data)?;
+            String::from_utf8(data).expect("csv writer only writes the utf-8 it's given")
+        }
+        Format::Ndjson => {
+            let mut data = String::new();
+            for person in people {
+                data.push_str(&serde_json::to_string(person)?);
+                data.push('\n');
+            }
+            data
+        }
+        #[cfg(feature = "yaml")]
+        Format::Yaml => serde_yaml::to_string(&envelope)?,
+        #[cfg(feature = "toml")]
+        Format::Toml => toml::to_string_pretty(&envelope).map_err(FormatError::TomlWrite)?,
+    };
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::person::{Address, Gender};
+    use chrono::NaiveDate;
+
+    fn sample() -> Vec<Person> {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        vec![
+            Person::new("Stephen", date(1987, 3, 2), Gender::Male).with_email("stephen@example.com"),
+            Person::new("Ashley", date(1989, 11, 20), Gender::Female).with_address(Address {
+                street: "1 Main St".to_string(),
+                city: "Springfield".to_string(),
+                postal_code: "12345".to_string(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Format::from_path("people.json"), Some(Format::Json));
+        assert_eq!(Format::from_path("data/People.JSON"), Some(Format::Json));
+        assert_eq!(Format::from_path("people.jsonl"), Some(Format::Ndjson));
+        assert_eq!(Format::from_path("people.csv"), Some(Format::Csv));
+        assert_eq!(Format::from_path("people.xml"), None);
+        assert_eq!(Format::from_path("people"), None);
+        #[cfg(feature = "yaml")]
+        assert_eq!(Format::from_path("people.yml"), Some(Format::Yaml));
+        #[cfg(feature = "toml")]
+        assert_eq!(Format::from_path("people.toml"), Some(Format::Toml));
+    }
+
+    #[test]
+    fn round_trips_every_format() {
+        let formats = [
+            Format::Json,
+            Format::Ndjson,
+            Format::Csv,
+            #[cfg(feature = "yaml")]
+            Format::Yaml,
+            #[cfg(feature = "toml")]
+            Format::Toml,
+        ];
+        for format in formats {
+            let text = to_string(&sample(), format).unwrap();
+            assert_eq!(from_str(&text, format).unwrap(), sample(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn old_files_load_in_every_format() {
+        // what each format looked like before the version envelope
+        let check = |people: Vec<Person>| {
+            assert_eq!(people.len(), 1);
+            assert_eq!(people[0].name, "Stephen");
+            assert_eq!(people[0].age(), 38);
+        };
+        let json_v1 = r#"[{"name": "Stephen", "age": 38, "gender": "male"}]"#;
+        check(from_str(json_v1, Format::Json).unwrap());
+        #[cfg(feature = "yaml")]
+        {
+            let yaml_v1 = "- name: Stephen\n  age: 38\n  gender: male\n";
+            check(from_str(yaml_v1, Format::Yaml).unwrap());
+        }
+        #[cfg(feature = "toml")]
+        {
+            let toml_v1 = "[[people]]\nname = \"Stephen\"\nage = 38\ngender = \"male\"\n";
+            check(from_str(toml_v1, Format::Toml).unwrap());
+        }
+    }
+
+    #[test]
+    fn saved_files_carry_the_version() {
+        let text = to_string(&sample(), Format::Json).unwrap();
+        let doc: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(doc["version"], schema::CURRENT_VERSION);
+    }
+
+    #[test]
+    fn unknown_extension_is_an_error() {
+        assert!(matches!(load("people.txt"), Err(FormatError::UnknownFormat(_))));
+    }
+}
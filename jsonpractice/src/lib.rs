@@ -0,0 +1,10 @@
+pub mod atomic;
+pub mod format;
+pub mod merge;
+pub mod ndjson;
+pub mod person;
+pub mod schema;
+pub mod spreadsheet;
+pub mod store;
+pub mod table;
+pub mod validation;
@@ -0,0 +1,214 @@
+use std::process;
+
+use chrono::{Local, NaiveDate};
+use clap::{Args, Parser, Subcommand};
+
+use jsonpractice::format;
+use jsonpractice::merge::{self, DedupKey, MergeStrategy, OnConflict};
+use jsonpractice::person::{Address, Gender, Person};
+use jsonpractice::store::PersonStore;
+use jsonpractice::table;
+
+#[derive(Parser)]
+#[command(name = "people", about = "Manages a list of people stored in a data file")]
+struct Cli {
+    /// Data file; the extension picks the format (.json, .ndjson/.jsonl, .yaml/.yml, .toml or .csv)
+    #[arg(short, long, global = true, default_value = "people.json")]
+    file: String,
+
+    /// Old copies of the data file to keep (file.1 is the newest)
+    #[arg(long, global = true, default_value_t = 3)]
+    backups: usize,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct NewPerson {
+    #[arg(long)]
+    name: String,
+    /// Date of birth, as YYYY-MM-DD
+    #[arg(long)]
+    birthdate: NaiveDate,
+    #[arg(long)]
+    gender: Gender,
+    #[arg(long)]
+    email: Option<String>,
+    /// Street address; needs --city and --postal-code too
+    #[arg(long, requires_all = ["city", "postal_code"])]
+    street: Option<String>,
+    #[arg(long, requires_all = ["street", "postal_code"])]
+    city: Option<String>,
+    #[arg(long, requires_all = ["street", "city"])]
+    postal_code: Option<String>,
+}
+
+impl NewPerson {
+    fn build(&self) -> Person {
+        let mut person = Person::new(&self.name, self.birthdate, self.gender.clone());
+        person.email = self.email.clone();
+        if let (Some(street), Some(city), Some(postal_code)) = (&self.street, &self.city, &self.postal_code) {
+            person.address = Some(Address {
+                street: street.clone(),
+                city: city.clone(),
+                postal_code: postal_code.clone(),
+            });
+        }
+        person
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Add a person
+    Add(NewPerson),
+    /// Show one person
+    Get { name: String },
+    /// Change some of a person's details
+    Update {
+        name: String,
+        /// New name
+        #[arg(long)]
+        rename: Option<String>,
+        #[arg(long)]
+        birthdate: Option<NaiveDate>,
+        #[arg(long)]
+        gender: Option<Gender>,
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// List everyone in the file
+    List {
+        /// Also count people by gender
+        #[arg(long)]
+        by_gender: bool,
+    },
+    /// Remove a person
+    Remove { name: String },
+    /// List the people matching every given filter
+    Search {
+        #[arg(long)]
+        min_age: Option<u32>,
+        #[arg(long)]
+        max_age: Option<u32>,
+        #[arg(long)]
+        gender: Option<Gender>,
+        /// Case-insensitive substring of the name
+        #[arg(long)]
+        name: Option<String>,
+        /// Also count the matches by gender
+        #[arg(long)]
+        by_gender: bool,
+    },
+    /// Combine several data files into --file, dropping duplicates
+    Merge {
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// What makes two records the same person: name or name-and-birthdate
+        #[arg(long, default_value = "name")]
+        key: DedupKey,
+        /// How to settle differing duplicates: newest, keep-first or error
+        #[arg(long, default_value = "keep-first")]
+        on_conflict: OnConflict,
+    },
+}
+
+fn print_people<'a>(people: impl IntoIterator<Item = &'a Person>, by_gender: bool) {
+    let people: Vec<&Person> = people.into_iter().collect();
+    let today = Local::now().date_naive();
+    if !people.is_empty() {
+        print!("{}", table::render_table(&people, today));
+        println!();
+    }
+    print!("{}", table::render_summary(&table::summarize(&people, today), by_gender));
+}
+
+fn print_person(person: &Person) {
+    println!("Name:      {}", person.name);
+    println!("Age:       {}", person.age());
+    println!("Birthdate: {}", person.birthdate);
+    println!("Gender:    {}", person.gender);
+    if let Some(email) = &person.email {
+        println!("Email:     {}", email);
+    }
+    if let Some(address) = &person.address {
+        println!("Address:   {}", address);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let filepath = &cli.file;
+
+    // merging writes --file from scratch, so there's nothing to load first
+    if let Command::Merge { inputs, key, on_conflict } = &cli.command {
+        let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+        let strategy = MergeStrategy { key: *key, on_conflict: *on_conflict };
+        let people = merge::merge_files(&inputs, strategy).map_err(|e| e.to_string())?;
+        format::save_with_backups(&people, filepath, cli.backups).map_err(|e| e.to_string())?;
+        println!("Merged {} files into {} people in {}", inputs.len(), people.len(), filepath);
+        return Ok(());
+    }
+
+    let mut store = PersonStore::open(filepath).map_err(|e| format!("{}: {}", filepath, e))?;
+    store.set_backups(cli.backups);
+
+    match cli.command {
+        Command::Add(new) => {
+            store.add(new.build()).map_err(|e| e.to_string())?;
+            store.save().map_err(|e| e.to_string())?;
+            println!("Added {} to {}", new.name, filepath);
+        }
+        Command::Get { name } => match store.get(&name) {
+            Some(person) => print_person(person),
+            None => return Err(format!("no one named {} in {}", name, filepath)),
+        },
+        Command::Update { name, rename, birthdate, gender, email } => {
+            let updated = store
+                .update(&name, |p| {
+                    if let Some(rename) = rename {
+                        p.name = rename;
+                    }
+                    if let Some(birthdate) = birthdate {
+                        p.birthdate = birthdate;
+                    }
+                    if let Some(gender) = gender {
+                        p.gender = gender;
+                    }
+                    if email.is_some() {
+                        p.email = email;
+                    }
+                })
+                .map_err(|e| e.to_string())?
+                .clone();
+            store.save().map_err(|e| e.to_string())?;
+            println!("Updated {}:", name);
+            print_person(&updated);
+        }
+        Command::List { by_gender } => print_people(store.iter(), by_gender),
+        Command::Remove { name } => {
+            let person = store.remove(&name).map_err(|e| e.to_string())?;
+            store.save().map_err(|e| e.to_string())?;
+            println!("Removed {} from {}", person.name, filepath);
+        }
+        Command::Search { min_age, max_age, gender, name, by_gender } => {
+            let name = name.map(|n| n.to_lowercase());
+            print_people(store.iter().filter(|p| {
+                let age = p.age();
+                min_age.is_none_or(|min| age >= min)
+                    && max_age.is_none_or(|max| age <= max)
+                    && gender.as_ref().is_none_or(|g| p.gender == *g)
+                    && name.as_ref().is_none_or(|n| p.name.to_lowercase().contains(n))
+            }), by_gender);
+        }
+        Command::Merge { .. } => unreachable!("merge returns before the store is opened"),
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
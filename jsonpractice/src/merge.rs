@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::person::Person;
+use crate::validation::{self, PersonError};
+
+// what makes two records "the same person"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupKey {
+    Name,
+    NameAndBirthdate,
+}
+
+impl DedupKey {
+    fn of(&self, person: &Person) -> String {
+        let name = person.name.trim().to_lowercase();
+        match self {
+            DedupKey::Name => name,
+            DedupKey::NameAndBirthdate => format!("{} ({})", name, person.birthdate),
+        }
+    }
+}
+
+// what to do when two records share a key but differ. identical duplicates are always collapsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnConflict {
+    // take the record from the most recently modified file (the later file on a tie)
+    Newest,
+    KeepFirst,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeStrategy {
+    pub key: DedupKey,
+    pub on_conflict: OnConflict,
+}
+
+impl Default for MergeStrategy {
+    fn default() -> Self {
+        MergeStrategy { key: DedupKey::Name, on_conflict: OnConflict::KeepFirst }
+    }
+}
+
+impl FromStr for DedupKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(DedupKey::Name),
+            "name-and-birthdate" => Ok(DedupKey::NameAndBirthdate),
+            _ => Err(format!("unknown key '{}', expected name or name-and-birthdate", s)),
+        }
+    }
+}
+
+impl FromStr for OnConflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(OnConflict::Newest),
+            "keep-first" => Ok(OnConflict::KeepFirst),
+            "error" => Ok(OnConflict::Error),
+            _ => Err(format!("unknown conflict strategy '{}', expected newest, keep-first or error", s)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MergeError {
+    Load(PersonError),
+    Metadata { path: String, error: io::Error },
+    Conflict { key: String, first: String, second: String },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Load(e) => write!(f, "{}", e),
+            MergeError::Metadata { path, error } => write!(f, "could not read the modified time of {}: {}", path, error),
+            MergeError::Conflict { key, first, second } =>
+                write!(f, "conflicting records for '{}' in {} and {}", key, first, second),
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+pub fn merge_files(paths: &[&str], strategy: MergeStrategy) -> Result<Vec<Person>, MergeError> {
+    // people come out in the order they were first seen; a replaced record keeps its slot
+    let mut merged: Vec<(Person, usize)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut modified: Vec<SystemTime> = Vec::with_capacity(paths.len());
+
+    for (source, &path) in paths.iter().enumerate() {
+        let people = validation::load_people(path).map_err(MergeError::Load)?;
+        let mtime = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|error| MergeError::Metadata { path: path.to_string(), error })?;
+        modified.push(mtime);
+
+        for person in people {
+            let key = strategy.key.of(&person);
+            let Some(&slot) = index.get(&key) else {
+                index.insert(key, merged.len());
+                merged.push((person, source));
+                continue;
+            };
+            let (kept, kept_source) = &merged[slot];
+            if *kept == person {
+                continue;
+            }
+            match strategy.on_conflict {
+                OnConflict::KeepFirst => {}
+                OnConflict::Newest => {
+                    if modified[source] >= modified[*kept_source] {
+                        merged[slot] = (person, source);
+                    }
+                }
+                OnConflict::Error => {
+                    return Err(MergeError::Conflict {
+                        key,
+                        first: paths[*kept_source].to_string(),
+                        second: path.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(merged.into_iter().map(|(person, _)| person).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format;
+    use crate::person::Gender;
+    use chrono::NaiveDate;
+    use std::fs::File;
+    use std::process;
+    use std::time::Duration;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    // writes people to a temp file whose modified time is `age_secs` in the past
+    fn write(name: &str, people: &[Person], age_secs: u64) -> String {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-merge-{}", process::id(), name));
+        let path = path.to_str().unwrap().to_string();
+        format::save(people, &path).unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(age_secs);
+        File::options().write(true).open(&path).unwrap().set_modified(mtime).unwrap();
+        path
+    }
+
+    // named for the test, since tests run in parallel and each resets its files' modified times
+    fn files(test: &str) -> (String, String) {
+        let old = write(&format!("{}-old.json", test), &[
+            Person::new("Stephen", date(1987, 3, 2), Gender::Male),
+            Person::new("Ashley", date(1989, 11, 20), Gender::Female),
+        ], 3600);
+        let new = write(&format!("{}-new.csv", test), &[
+            Person::new("ashley", date(1989, 11, 20), Gender::Female).with_email("ash@example.com"),
+            Person::new("Stephen", date(1987, 3, 2), Gender::Male),
+            Person::new("David", date(1997, 5, 30), Gender::Male),
+        ], 0);
+        (old, new)
+    }
+
+    #[test]
+    fn keep_first_and_newest() {
+        let (old, new) = files("keep-first");
+
+        let strategy = MergeStrategy { key: DedupKey::Name, on_conflict: OnConflict::KeepFirst };
+        let merged = merge_files(&[&old, &new], strategy).unwrap();
+        let names: Vec<&str> = merged.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Stephen", "Ashley", "David"]);
+        assert_eq!(merged[1].email, None);
+
+        // the csv file is newer even though it's listed first
+        let strategy = MergeStrategy { on_conflict: OnConflict::Newest, ..strategy };
+        let merged = merge_files(&[&new, &old], strategy).unwrap();
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].email.as_deref(), Some("ash@example.com"));
+
+        fs::remove_file(old).unwrap();
+        fs::remove_file(new).unwrap();
+    }
+
+    #[test]
+    fn conflicts_can_be_errors() {
+        let (old, new) = files("conflicts");
+        let strategy = MergeStrategy { key: DedupKey::NameAndBirthdate, on_conflict: OnConflict::Error };
+        match merge_files(&[&old, &new], strategy) {
+            Err(MergeError::Conflict { key, .. }) => assert_eq!(key, "ashley (1989-11-20)"),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+        // a differing birthdate makes a different person under name+birthdate
+        let twin = write("conflicts-twin.json", &[Person::new("Ashley", date(2001, 1, 1), Gender::Female)], 0);
+        let merged = merge_files(&[&old, &twin], strategy).unwrap();
+        assert_eq!(merged.len(), 3);
+
+        fs::remove_file(old).unwrap();
+        fs::remove_file(new).unwrap();
+        fs::remove_file(twin).unwrap();
+    }
+}
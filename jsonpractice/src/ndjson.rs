@@ -0,0 +1,94 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Write};
+
+use crate::atomic::{self, DEFAULT_BACKUPS};
+use crate::person::Person;
+use crate::schema;
+
+// newline delimited json: one Person object per line, so a file can grow by appending
+// and be read back one record at a time without holding it all in memory.
+// lines have no version envelope, so each record is upgraded on its own as it's read
+
+pub fn append_person(filepath: &str, person: &Person) -> serde_json::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filepath)
+        .map_err(serde_json::Error::io)?;
+    // serialize into a buffer first so a failed write never leaves half a line behind
+    let mut line = serde_json::to_vec(person)?;
+    line.push(b'\n');
+    file.write_all(&line).map_err(serde_json::Error::io)?;
+    Ok(())
+}
+
+pub fn iter_people(filepath: &str) -> io::Result<impl Iterator<Item = serde_json::Result<Person>>> {
+    let reader = BufReader::new(File::open(filepath)?);
+    Ok(serde_json::Deserializer::from_reader(reader)
+        .into_iter::<serde_json::Value>()
+        .map(|record| record.and_then(|r| serde_json::from_value(schema::migrate_record(r)))))
+}
+
+pub fn write_people(people: &[Person], filepath: &str) -> serde_json::Result<()> {
+    // rewriting the whole file goes through a temp file like every other format;
+    // append_person can't, but a torn append only ever loses the line being added
+    let mut data = Vec::new();
+    for person in people {
+        serde_json::to_writer(&mut data, person)?;
+        data.push(b'\n');
+    }
+    atomic::write_atomic(filepath, &data, DEFAULT_BACKUPS).map_err(serde_json::Error::io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::person::Gender;
+    use chrono::NaiveDate;
+    use std::fs;
+    use std::process;
+
+    #[test]
+    fn append_then_stream() {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-stream.ndjson", process::id()));
+        let path = path.to_str().unwrap();
+        let _ = fs::remove_file(path);
+
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        write_people(&[Person::new("Stephen", date(1987, 3, 2), Gender::Male)], path).unwrap();
+        append_person(path, &Person::new("Ashley", date(1989, 11, 20), Gender::Female)).unwrap();
+        append_person(path, &Person::new("David", date(1997, 5, 30), Gender::Male)).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 3);
+
+        let names: Vec<String> = iter_people(path).unwrap().map(|p| p.unwrap().name).collect();
+        assert_eq!(names, vec!["Stephen", "Ashley", "David"]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn bad_record_is_reported_in_stream() {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-bad.ndjson", process::id()));
+        let path = path.to_str().unwrap();
+        let lines = [
+            r#"{"name":"Al","birthdate":"1990-01-01","gender":"male"}"#,
+            r#"{"name":"Bo","birthdate":"1990-02-30","gender":"male"}"#,
+        ];
+        fs::write(path, lines.join("\n")).unwrap();
+
+        let results: Vec<_> = iter_people(path).unwrap().collect();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn old_records_with_age_are_upgraded() {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-old.ndjson", process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, r#"{"name":"Al","age":30,"gender":"male"}"#).unwrap();
+
+        let person = iter_people(path).unwrap().next().unwrap().unwrap();
+        assert_eq!(person.age(), 30);
+        fs::remove_file(path).unwrap();
+    }
+}
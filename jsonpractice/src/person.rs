@@ -0,0 +1,178 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+use crate::atomic::{self, DEFAULT_BACKUPS};
+use crate::schema::{self, Envelope};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gender {
+    Male,
+    Female,
+    NonBinary,
+    // anything a file spells some other way loads as Other instead of failing the whole file
+    #[serde(other)]
+    Other,
+}
+
+impl FromStr for Gender {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "male" | "m" => Ok(Gender::Male),
+            "female" | "f" => Ok(Gender::Female),
+            "nonbinary" | "non-binary" | "nb" => Ok(Gender::NonBinary),
+            "other" => Ok(Gender::Other),
+            _ => Err(format!("unknown gender '{}', expected male, female, nonbinary or other", s)),
+        }
+    }
+}
+
+impl fmt::Display for Gender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gender::Male => write!(f, "male"),
+            Gender::Female => write!(f, "female"),
+            Gender::NonBinary => write!(f, "nonbinary"),
+            Gender::Other => write!(f, "other"),
+        }
+    }
+}
+
+// dates are stored as plain "1990-04-27" strings rather than chrono's default layout
+pub(crate) mod date_format {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub const FORMAT: &str = "%Y-%m-%d";
+
+    pub fn serialize<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date.format(FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        NaiveDate::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub postal_code: String,
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {} {}", self.street, self.city, self.postal_code)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Person {
+    pub name: String,
+    #[serde(with = "date_format")]
+    pub birthdate: NaiveDate,
+    pub gender: Gender,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+}
+
+impl Person {
+    pub fn new(name: &str, birthdate: NaiveDate, gender: Gender) -> Self {
+        Person {
+            name: name.to_string(),
+            birthdate,
+            gender,
+            email: None,
+            address: None,
+        }
+    }
+
+    pub fn with_email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn age(&self) -> u32 {
+        self.age_on(Local::now().date_naive())
+    }
+
+    pub fn age_on(&self, date: NaiveDate) -> u32 {
+        // whole years, so the age only ticks over on the birthday itself
+        date.years_since(self.birthdate).unwrap_or(0)
+    }
+
+    pub fn to_file(&self, filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(&self)?;
+        atomic::write_atomic(filepath, json_data.as_bytes(), DEFAULT_BACKUPS)
+            .map_err(serde_json::Error::io)
+    }
+
+    pub fn load_people_from_file(filepath: &str) -> serde_json::Result<Vec<Person>> {
+        let json_data = fs::read_to_string(filepath)
+            .map_err(serde_json::Error::io)?;
+        // older files are upgraded to the current layout before being read
+        let doc = schema::migrate(serde_json::from_str(&json_data)?)
+            .map_err(serde_json::Error::custom)?;
+        let envelope: Envelope = serde_json::from_value(doc)?;
+        Ok(envelope.people)
+    }
+
+    pub fn save_people_to_file(people: &[Person], filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(&Envelope::new(people.to_vec()))?;
+        atomic::write_atomic(filepath, json_data.as_bytes(), DEFAULT_BACKUPS)
+            .map_err(serde_json::Error::io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn age_ticks_over_on_birthday() {
+        let person = Person::new("Al", date(1990, 6, 15), Gender::Male);
+        assert_eq!(person.age_on(date(2020, 6, 14)), 29);
+        assert_eq!(person.age_on(date(2020, 6, 15)), 30);
+        assert_eq!(person.age_on(date(1980, 1, 1)), 0);
+    }
+
+    #[test]
+    fn dates_and_optional_fields_serialize_compactly() {
+        let person = Person::new("Al", date(1990, 6, 5), Gender::Male);
+        let json = serde_json::to_string(&person).unwrap();
+        assert_eq!(json, r#"{"name":"Al","birthdate":"1990-06-05","gender":"male"}"#);
+
+        let person = person.with_email("al@example.com").with_address(Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+            postal_code: "12345".to_string(),
+        });
+        let back: Person = serde_json::from_str(&serde_json::to_string(&person).unwrap()).unwrap();
+        assert_eq!(back, person);
+    }
+
+    #[test]
+    fn rejects_other_date_layouts() {
+        let json = r#"{"name":"Al","birthdate":"06/05/1990","gender":"male"}"#;
+        assert!(serde_json::from_str::<Person>(json).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{Datelike, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::person::Person;
+
+// version history of the people file:
+//   1: a bare list of people (or, in toml, a table holding just the list)
+//   2: the list wrapped in an envelope that records the version
+//   3: each person's `age` replaced by a `birthdate`, plus optional email and address
+pub const CURRENT_VERSION: u64 = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope {
+    pub version: u64,
+    pub people: Vec<Person>,
+}
+
+impl Envelope {
+    pub fn new(people: Vec<Person>) -> Self {
+        Envelope { version: CURRENT_VERSION, people }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SchemaError {
+    UnknownShape,
+    BadVersion(Value),
+    TooNew(u64),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::UnknownShape => write!(f, "expected a list of people or a versioned object holding one"),
+            SchemaError::BadVersion(v) => write!(f, "version should be a whole number, found {}", v),
+            SchemaError::TooNew(v) => write!(f, "file is version {} but this build only understands up to version {}", v, CURRENT_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+// each step takes a document at version n and returns it at version n + 1
+type Migration = fn(Value) -> Value;
+
+// MIGRATIONS[i] upgrades version i + 1 to version i + 2
+const MIGRATIONS: [Migration; 2] = [v1_to_v2, v2_to_v3];
+
+fn v1_to_v2(doc: Value) -> Value {
+    match doc {
+        Value::Array(people) => json!({ "version": 2, "people": people }),
+        Value::Object(mut map) => {
+            map.insert("version".to_string(), json!(2));
+            Value::Object(map)
+        }
+        other => other,
+    }
+}
+
+fn v2_to_v3(mut doc: Value) -> Value {
+    if let Some(people) = doc.get_mut("people").and_then(Value::as_array_mut) {
+        for person in people.iter_mut() {
+            *person = migrate_record(person.take());
+        }
+    }
+    doc["version"] = json!(3);
+    doc
+}
+
+pub fn migrate_record(mut person: Value) -> Value {
+    // upgrades a single person object; ndjson lines carry no version, so they go through this
+    // on every read. the old records only know an age, so the best guess is a birthday on
+    // Jan 1st, which gives back the same age for the rest of this year
+    if let Some(map) = person.as_object_mut()
+        && let Some(age) = map.remove("age").and_then(|a| a.as_i64())
+        && !map.contains_key("birthdate")
+    {
+        let this_year = i64::from(Local::now().year());
+        map.insert("birthdate".to_string(), json!(format!("{:04}-01-01", this_year - age)));
+    }
+    person
+}
+
+pub fn version_of(doc: &Value) -> Result<u64, SchemaError> {
+    match doc {
+        Value::Array(_) => Ok(1),
+        Value::Object(map) => match map.get("version") {
+            None => Ok(1),
+            Some(v) => v.as_u64().filter(|&v| v >= 1).ok_or_else(|| SchemaError::BadVersion(v.clone())),
+        },
+        _ => Err(SchemaError::UnknownShape),
+    }
+}
+
+pub fn migrate(mut doc: Value) -> Result<Value, SchemaError> {
+    // runs every migration between the document's version and CURRENT_VERSION, in order
+    let version = version_of(&doc)?;
+    if version > CURRENT_VERSION {
+        return Err(SchemaError::TooNew(version));
+    }
+    for step in &MIGRATIONS[(version - 1) as usize..] {
+        doc = step(doc);
+    }
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_list_is_upgraded() {
+        let v1 = json!([{ "name": "Al", "age": 30, "gender": "male" }]);
+        let doc = migrate(v1).unwrap();
+        assert_eq!(version_of(&doc), Ok(CURRENT_VERSION));
+        let envelope: Envelope = serde_json::from_value(doc).unwrap();
+        assert_eq!(envelope.people[0].name, "Al");
+        assert_eq!(envelope.people[0].age(), 30);
+    }
+
+    #[test]
+    fn unversioned_table_is_upgraded() {
+        let v1 = json!({ "people": [] });
+        assert_eq!(migrate(v1).unwrap(), json!({ "version": CURRENT_VERSION, "people": [] }));
+    }
+
+    #[test]
+    fn age_becomes_birthdate() {
+        let v2 = json!({ "version": 2, "people": [{ "name": "Al", "age": 30, "gender": "male" }] });
+        let doc = migrate(v2).unwrap();
+        let person = &doc["people"][0];
+        assert!(person.get("age").is_none());
+        let expected = format!("{}-01-01", Local::now().year() - 30);
+        assert_eq!(person["birthdate"], json!(expected));
+    }
+
+    #[test]
+    fn current_version_is_untouched() {
+        let doc = json!({ "version": CURRENT_VERSION, "people": [] });
+        assert_eq!(migrate(doc.clone()).unwrap(), doc);
+    }
+
+    #[test]
+    fn rejects_future_and_malformed_versions() {
+        assert_eq!(migrate(json!({ "version": 99, "people": [] })), Err(SchemaError::TooNew(99)));
+        assert!(matches!(migrate(json!({ "version": "two" })), Err(SchemaError::BadVersion(_))));
+        assert_eq!(migrate(json!("people")), Err(SchemaError::UnknownShape));
+    }
+}
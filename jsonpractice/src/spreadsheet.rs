@@ -0,0 +1,239 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io;
+
+use crate::atomic::{self, DEFAULT_BACKUPS};
+use crate::person::{date_format, Address, Gender, Person};
+use crate::validation::PersonError;
+
+// the columns a people csv must have; order doesn't matter and header case is ignored.
+// email and the address columns are optional
+const COLUMNS: [&str; 3] = ["name", "birthdate", "gender"];
+
+// csv rows are flat, so the address is spread over three columns that are all set or all empty
+#[derive(Debug, Serialize, Deserialize)]
+struct Row {
+    name: String,
+    #[serde(with = "date_format")]
+    birthdate: NaiveDate,
+    gender: Gender,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(default)]
+    street: Option<String>,
+    #[serde(default)]
+    city: Option<String>,
+    #[serde(default)]
+    postal_code: Option<String>,
+}
+
+impl From<&Person> for Row {
+    fn from(person: &Person) -> Self {
+        let address = person.address.as_ref();
+        Row {
+            name: person.name.clone(),
+            birthdate: person.birthdate,
+            gender: person.gender.clone(),
+            email: person.email.clone(),
+            street: address.map(|a| a.street.clone()),
+            city: address.map(|a| a.city.clone()),
+            postal_code: address.map(|a| a.postal_code.clone()),
+        }
+    }
+}
+
+impl Row {
+    fn into_person(self) -> Result<Person, String> {
+        let address = match (self.street, self.city, self.postal_code) {
+            (Some(street), Some(city), Some(postal_code)) => Some(Address { street, city, postal_code }),
+            (None, None, None) => None,
+            _ => return Err("street, city and postal_code must be filled in together".to_string()),
+        };
+        let person = Person {
+            name: self.name,
+            birthdate: self.birthdate,
+            gender: self.gender,
+            email: self.email,
+            address,
+        };
+        match person.validate() {
+            Err(PersonError::Invalid(errors)) => {
+                let problems: Vec<String> = errors.iter().map(|e| format!("{} {}", e.field, e.message)).collect();
+                Err(problems.join("; "))
+            }
+            _ => Ok(person),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RowError {
+    pub line: u64,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Csv(csv::Error),
+    MissingColumn(String),
+    Rows(Vec<RowError>),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(e) => write!(f, "csv error: {}", e),
+            CsvError::MissingColumn(column) => write!(f, "csv header has no '{}' column", column),
+            CsvError::Rows(errors) => {
+                write!(f, "{} bad row(s):", errors.len())?;
+                for e in errors {
+                    write!(f, "\n  line {}: {}", e.line, e.message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<csv::Error> for CsvError {
+    fn from(e: csv::Error) -> Self {
+        CsvError::Csv(e)
+    }
+}
+
+impl Person {
+    pub fn load_people_from_csv(filepath: &str) -> Result<Vec<Person>, CsvError> {
+        let file = File::open(filepath).map_err(csv::Error::from)?;
+        read_people(file)
+    }
+
+    pub fn save_people_to_csv(people: &[Person], filepath: &str) -> Result<(), CsvError> {
+        let mut data = Vec::new();
+        write_people(people, &mut data)?;
+        atomic::write_atomic(filepath, &data, DEFAULT_BACKUPS).map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+pub fn read_people<R: io::Read>(source: R) -> Result<Vec<Person>, CsvError> {
+    // reads every row before giving up, so one run reports all the bad rows at once
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(source);
+
+    let headers: csv::StringRecord = reader.headers()?.iter().map(|h| h.to_lowercase()).collect();
+    for column in COLUMNS {
+        if !headers.iter().any(|h| h == column) {
+            return Err(CsvError::MissingColumn(column.to_string()));
+        }
+    }
+    reader.set_headers(headers);
+
+    let mut people = Vec::new();
+    let mut errors = Vec::new();
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let parsed = record
+            .deserialize::<Row>(Some(reader.headers()?))
+            .map_err(|e| match e.kind() {
+                csv::ErrorKind::Deserialize { err, .. } => err.to_string(),
+                _ => e.to_string(),
+            })
+            .and_then(Row::into_person);
+        match parsed {
+            Ok(person) => people.push(person),
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(people)
+    } else {
+        Err(CsvError::Rows(errors))
+    }
+}
+
+pub fn write_people<W: io::Write>(people: &[Person], sink: W) -> Result<(), CsvError> {
+    // the header row comes from the Row field names
+    let mut writer = csv::Writer::from_writer(sink);
+    for person in people {
+        writer.serialize(Row::from(person))?;
+    }
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-{}", process::id(), name));
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round_trip.csv");
+        let people = vec![
+            Person::new("Stephen", date(1987, 3, 2), Gender::Male).with_email("stephen@example.com"),
+            Person::new("Ashley", date(1989, 11, 20), Gender::Female).with_address(Address {
+                street: "1 Main St".to_string(),
+                city: "Springfield".to_string(),
+                postal_code: "12345".to_string(),
+            }),
+        ];
+        Person::save_people_to_csv(&people, &path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("name,birthdate,gender,email,street,city,postal_code\n"));
+        assert_eq!(Person::load_people_from_csv(&path).unwrap(), people);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn headers_in_any_order_and_case() {
+        let path = temp_path("headers.csv");
+        fs::write(&path, "Gender, Birthdate ,NAME\nfemale, 1984-07-09, Jo\n").unwrap();
+        let people = Person::load_people_from_csv(&path).unwrap();
+        assert_eq!(people, vec![Person::new("Jo", date(1984, 7, 9), Gender::Female)]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_every_bad_row() {
+        let path = temp_path("bad_rows.csv");
+        let csv = "name,birthdate,gender,city\nAl,1990-13-01,male,\nBea,1990-01-01,female,\nCy,1990-01-01,male,Paris\n,1990-01-01,male,\n";
+        fs::write(&path, csv).unwrap();
+        match Person::load_people_from_csv(&path) {
+            Err(CsvError::Rows(errors)) => {
+                let lines: Vec<u64> = errors.iter().map(|e| e.line).collect();
+                assert_eq!(lines, vec![2, 4, 5]);
+                assert_eq!(errors[2].message, "name must not be empty");
+            }
+            other => panic!("expected row errors, got {:?}", other),
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_column() {
+        let path = temp_path("missing.csv");
+        fs::write(&path, "name,birthdate\nAl,1990-01-01\n").unwrap();
+        assert!(matches!(
+            Person::load_people_from_csv(&path),
+            Err(CsvError::MissingColumn(c)) if c == "gender"
+        ));
+        fs::remove_file(path).unwrap();
+    }
+}
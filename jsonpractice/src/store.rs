@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::atomic::DEFAULT_BACKUPS;
+use crate::format::{self, Format, FormatError};
+use crate::ndjson;
+use crate::person::Person;
+use crate::validation::{self, PersonError};
+
+// a data file loaded once into memory, with people indexed by name (case-insensitive,
+// so names are unique within a store). changes stay in memory until save()
+pub struct PersonStore {
+    filepath: String,
+    people: Vec<Person>,
+    index: HashMap<String, usize>,
+    // how many people at the end of `people` were added since the last save, and whether
+    // anything else changed. ndjson files only get the new lines appended if nothing else did
+    appended: usize,
+    rewrite: bool,
+    backups: usize,
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    Person(PersonError),
+    Save(FormatError),
+    Duplicate(String),
+    NotFound(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Person(e) => write!(f, "{}", e),
+            StoreError::Save(e) => write!(f, "could not save people: {}", e),
+            StoreError::Duplicate(name) => write!(f, "there is already someone named {}", name),
+            StoreError::NotFound(name) => write!(f, "no one named {}", name),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+impl PersonStore {
+    pub fn open(filepath: &str) -> Result<Self, StoreError> {
+        // a file that doesn't exist yet is just an empty store, created on the first save
+        let people = if Path::new(filepath).exists() {
+            validation::load_people(filepath).map_err(StoreError::Person)?
+        } else {
+            Vec::new()
+        };
+
+        let mut store = PersonStore {
+            filepath: filepath.to_string(),
+            people: Vec::with_capacity(people.len()),
+            index: HashMap::with_capacity(people.len()),
+            appended: 0,
+            rewrite: false,
+            backups: DEFAULT_BACKUPS,
+        };
+        for person in people {
+            store.add(person)?;
+        }
+        store.appended = 0;
+        Ok(store)
+    }
+
+    pub fn filepath(&self) -> &str {
+        &self.filepath
+    }
+
+    pub fn set_backups(&mut self, backups: usize) {
+        // how many rotated copies of the previous file each save keeps
+        self.backups = backups;
+    }
+
+    pub fn add(&mut self, person: Person) -> Result<(), StoreError> {
+        person.validate().map_err(StoreError::Person)?;
+        let key = key(&person.name);
+        if self.index.contains_key(&key) {
+            return Err(StoreError::Duplicate(person.name));
+        }
+        self.index.insert(key, self.people.len());
+        self.people.push(person);
+        self.appended += 1;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Person> {
+        self.index.get(&key(name)).map(|&i| &self.people[i])
+    }
+
+    pub fn update<F: FnOnce(&mut Person)>(&mut self, name: &str, f: F) -> Result<&Person, StoreError> {
+        // f may rename the person, in which case the index follows the new name
+        let old_key = key(name);
+        let &i = self.index.get(&old_key).ok_or_else(|| StoreError::NotFound(name.to_string()))?;
+
+        let mut updated = self.people[i].clone();
+        f(&mut updated);
+        updated.validate().map_err(StoreError::Person)?;
+        let new_key = key(&updated.name);
+        if new_key != old_key {
+            if self.index.contains_key(&new_key) {
+                return Err(StoreError::Duplicate(updated.name));
+            }
+            self.index.remove(&old_key);
+            self.index.insert(new_key, i);
+        }
+        self.people[i] = updated;
+        self.rewrite = true;
+        Ok(&self.people[i])
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Person, StoreError> {
+        let i = self.index.remove(&key(name)).ok_or_else(|| StoreError::NotFound(name.to_string()))?;
+        let person = self.people.remove(i);
+        // everyone after the removed person moved down a slot
+        for slot in self.index.values_mut() {
+            if *slot > i {
+                *slot -= 1;
+            }
+        }
+        self.rewrite = true;
+        Ok(person)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Person> {
+        self.people.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.people.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.people.is_empty()
+    }
+
+    pub fn save(&mut self) -> Result<(), StoreError> {
+        let append_only = !self.rewrite && Format::from_path(&self.filepath) == Some(Format::Ndjson);
+        if append_only {
+            for person in &self.people[self.people.len() - self.appended..] {
+                ndjson::append_person(&self.filepath, person)
+                    .map_err(|e| StoreError::Save(FormatError::Json(e)))?;
+            }
+        } else {
+            format::save_with_backups(&self.people, &self.filepath, self.backups).map_err(StoreError::Save)?;
+        }
+        self.appended = 0;
+        self.rewrite = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atomic;
+    use crate::person::Gender;
+    use chrono::NaiveDate;
+    use std::fs;
+    use std::process;
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-store-{}", process::id(), name));
+        let path = path.to_str().unwrap().to_string();
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn person(name: &str) -> Person {
+        Person::new(name, NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(), Gender::Female)
+    }
+
+    #[test]
+    fn crud_and_persist() {
+        let path = temp_path("crud.json");
+        let mut store = PersonStore::open(&path).unwrap();
+        assert!(store.is_empty());
+
+        store.add(person("Ann")).unwrap();
+        store.add(person("Bea")).unwrap();
+        store.add(person("Cat")).unwrap();
+        assert!(matches!(store.add(person("ann")), Err(StoreError::Duplicate(_))));
+        assert!(matches!(store.add(person("")), Err(StoreError::Person(PersonError::Invalid(_)))));
+        assert!(store.update("Cat", |p| p.email = Some("nope".to_string())).is_err());
+        assert_eq!(store.get("cat").unwrap().email, None);
+
+        store.update("bea", |p| p.email = Some("bea@example.com".to_string())).unwrap();
+        assert_eq!(store.get("BEA").unwrap().email.as_deref(), Some("bea@example.com"));
+
+        store.remove("Ann").unwrap();
+        assert!(store.get("ann").is_none());
+        assert_eq!(store.get("cat").unwrap().name, "Cat");
+        assert!(matches!(store.remove("Ann"), Err(StoreError::NotFound(_))));
+        store.save().unwrap();
+
+        let reopened = PersonStore::open(&path).unwrap();
+        let names: Vec<&str> = reopened.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Bea", "Cat"]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rename_moves_the_index() {
+        let path = temp_path("rename.json");
+        let mut store = PersonStore::open(&path).unwrap();
+        store.add(person("Ann")).unwrap();
+        store.add(person("Bea")).unwrap();
+
+        store.update("Ann", |p| p.name = "Anna".to_string()).unwrap();
+        assert!(store.get("ann").is_none());
+        assert!(store.get("anna").is_some());
+        assert!(matches!(store.update("Anna", |p| p.name = "bea".to_string()), Err(StoreError::Duplicate(_))));
+        assert_eq!(store.get("anna").unwrap().name, "Anna");
+    }
+
+    #[test]
+    fn ndjson_adds_are_appended() {
+        let path = temp_path("append.ndjson");
+        let mut store = PersonStore::open(&path).unwrap();
+        store.add(person("Ann")).unwrap();
+        store.save().unwrap();
+
+        let mut store = PersonStore::open(&path).unwrap();
+        store.add(person("Bea")).unwrap();
+        store.save().unwrap();
+        store.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        store.remove("Ann").unwrap();
+        store.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        let backup = atomic::backup_path(Path::new(&path), 1);
+        assert_eq!(fs::read_to_string(&backup).unwrap().lines().count(), 2);
+        fs::remove_file(path).unwrap();
+        fs::remove_file(backup).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::person::Person;
+
+const HEADERS: [&str; 6] = ["Name", "Age", "Birthdate", "Gender", "Email", "City"];
+
+fn row(person: &Person, today: NaiveDate) -> [String; 6] {
+    [
+        person.name.clone(),
+        person.age_on(today).to_string(),
+        person.birthdate.to_string(),
+        person.gender.to_string(),
+        person.email.clone().unwrap_or_default(),
+        person.address.as_ref().map(|a| a.city.clone()).unwrap_or_default(),
+    ]
+}
+
+pub fn render_table(people: &[&Person], today: NaiveDate) -> String {
+    // columns are as wide as their widest cell; age is right aligned, the rest left aligned
+    let rows: Vec<[String; 6]> = people.iter().map(|p| row(p, today)).collect();
+    let mut widths = HEADERS.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut line = |cells: &[String; 6]| {
+        let mut text = String::new();
+        for (i, (cell, width)) in cells.iter().zip(widths).enumerate() {
+            if i > 0 {
+                text.push_str("  ");
+            }
+            if i == 1 {
+                let _ = write!(text, "{:>width$}", cell, width = width);
+            } else {
+                let _ = write!(text, "{:<width$}", cell, width = width);
+            }
+        }
+        out.push_str(text.trim_end());
+        out.push('\n');
+    };
+
+    line(&HEADERS.map(String::from));
+    line(&widths.map(|w| "-".repeat(w)));
+    for row in &rows {
+        line(row);
+    }
+    out
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub average_age: Option<f64>,
+    pub by_gender: BTreeMap<String, usize>,
+}
+
+pub fn summarize(people: &[&Person], today: NaiveDate) -> Summary {
+    let mut by_gender = BTreeMap::new();
+    for person in people {
+        *by_gender.entry(person.gender.to_string()).or_insert(0) += 1;
+    }
+    let total_age: u64 = people.iter().map(|p| u64::from(p.age_on(today))).sum();
+    Summary {
+        count: people.len(),
+        average_age: (!people.is_empty()).then(|| total_age as f64 / people.len() as f64),
+        by_gender,
+    }
+}
+
+pub fn render_summary(summary: &Summary, by_gender: bool) -> String {
+    let mut out = format!("{} {}", summary.count, if summary.count == 1 { "person" } else { "people" });
+    if let Some(average) = summary.average_age {
+        let _ = write!(out, ", average age {:.1}", average);
+    }
+    out.push('\n');
+    if by_gender {
+        for (gender, count) in &summary.by_gender {
+            let _ = writeln!(out, "  {:<10} {:>4}", gender, count);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::person::{Address, Gender};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn people() -> Vec<Person> {
+        vec![
+            Person::new("Stephen", date(1987, 3, 2), Gender::Male).with_email("stephen@example.com"),
+            Person::new("Ashley", date(1989, 11, 20), Gender::Female).with_address(Address {
+                street: "1 Main St".to_string(),
+                city: "Springfield".to_string(),
+                postal_code: "12345".to_string(),
+            }),
+            Person::new("Al", date(2015, 1, 1), Gender::Male),
+        ]
+    }
+
+    #[test]
+    fn columns_line_up() {
+        let people = people();
+        let refs: Vec<&Person> = people.iter().collect();
+        let table = render_table(&refs, date(2025, 6, 1));
+        let expected = "\
+Name     Age  Birthdate   Gender  Email                City
+-------  ---  ----------  ------  -------------------  -----------
+Stephen   38  1987-03-02  male    stephen@example.com
+Ashley    35  1989-11-20  female                       Springfield
+Al        10  2015-01-01  male
+";
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn summary_counts_and_averages() {
+        let people = people();
+        let refs: Vec<&Person> = people.iter().collect();
+        let summary = summarize(&refs, date(2025, 6, 1));
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.average_age, Some(83.0 / 3.0));
+        assert_eq!(summary.by_gender.get("male"), Some(&2));
+        assert_eq!(render_summary(&summary, true), "3 people, average age 27.7\n  female        1\n  male          2\n");
+        assert_eq!(render_summary(&summarize(&[], date(2025, 6, 1)), false), "0 people\n");
+    }
+}
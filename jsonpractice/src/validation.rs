@@ -0,0 +1,174 @@
+use chrono::{Local, NaiveDate};
+use std::fmt;
+use std::io;
+
+use crate::format::{self, FormatError};
+use crate::person::Person;
+
+pub const MAX_AGE: u32 = 130;
+
+// one problem with one field of one person
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub person: String,
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let who = if self.person.trim().is_empty() { "(unnamed)" } else { &self.person };
+        write!(f, "{}: {} {}", who, self.field, self.message)
+    }
+}
+
+// what can go wrong getting people in or out of a file, phrased for the person using the tool
+// rather than as raw serde or io errors
+#[derive(Debug)]
+pub enum PersonError {
+    Invalid(Vec<FieldError>),
+    FileNotFound(String),
+    Unreadable { path: String, reason: String },
+    Malformed { path: String, reason: String },
+}
+
+impl fmt::Display for PersonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersonError::Invalid(errors) => {
+                write!(f, "invalid person data:")?;
+                for e in errors {
+                    write!(f, "\n  {}", e)?;
+                }
+                Ok(())
+            }
+            PersonError::FileNotFound(path) => write!(f, "{} does not exist", path),
+            PersonError::Unreadable { path, reason } => write!(f, "could not read {}: {}", path, reason),
+            PersonError::Malformed { path, reason } => write!(f, "{} is not a valid people file: {}", path, reason),
+        }
+    }
+}
+
+impl std::error::Error for PersonError {}
+
+impl PersonError {
+    pub fn from_format(path: &str, error: FormatError) -> Self {
+        let path = path.to_string();
+        match error {
+            FormatError::Io(e) if e.kind() == io::ErrorKind::NotFound => PersonError::FileNotFound(path),
+            FormatError::Io(e) => PersonError::Unreadable { path, reason: e.to_string() },
+            FormatError::Json(e) if e.is_io() => PersonError::Unreadable { path, reason: e.to_string() },
+            other => PersonError::Malformed { path, reason: other.to_string() },
+        }
+    }
+}
+
+impl Person {
+    pub fn validate(&self) -> Result<(), PersonError> {
+        validate_on(self, Local::now().date_naive()).map_err(PersonError::Invalid)
+    }
+}
+
+fn validate_on(person: &Person, today: NaiveDate) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let mut fail = |field, message: String| errors.push(FieldError { person: person.name.clone(), field, message });
+
+    if person.name.trim().is_empty() {
+        fail("name", "must not be empty".to_string());
+    }
+    if person.birthdate > today {
+        fail("birthdate", format!("{} is in the future", person.birthdate));
+    } else if person.age_on(today) > MAX_AGE {
+        fail("birthdate", format!("{} makes them older than {}", person.birthdate, MAX_AGE));
+    }
+    if let Some(email) = &person.email {
+        let valid = email
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.') && !domain.contains('@'));
+        if !valid {
+            fail("email", format!("'{}' is not an email address", email));
+        }
+    }
+    if let Some(address) = &person.address {
+        for (field, value) in [("street", &address.street), ("city", &address.city), ("postal_code", &address.postal_code)] {
+            if value.trim().is_empty() {
+                fail(field, "must not be empty".to_string());
+            }
+        }
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+pub fn validate_all(people: &[Person]) -> Result<(), PersonError> {
+    // checks everyone so a file with several problems reports them together
+    let today = Local::now().date_naive();
+    let errors: Vec<FieldError> = people
+        .iter()
+        .filter_map(|p| validate_on(p, today).err())
+        .flatten()
+        .collect();
+    if errors.is_empty() { Ok(()) } else { Err(PersonError::Invalid(errors)) }
+}
+
+pub fn load_people(filepath: &str) -> Result<Vec<Person>, PersonError> {
+    let people = format::load(filepath).map_err(|e| PersonError::from_format(filepath, e))?;
+    validate_all(&people)?;
+    Ok(people)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::person::{Address, Gender};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn fields(person: &Person, today: NaiveDate) -> Vec<&'static str> {
+        match validate_on(person, today) {
+            Ok(()) => vec![],
+            Err(errors) => errors.iter().map(|e| e.field).collect(),
+        }
+    }
+
+    #[test]
+    fn field_level_checks() {
+        let today = date(2025, 6, 1);
+        let ok = Person::new("Al", date(1990, 1, 1), Gender::Male).with_email("al@example.com");
+        assert!(fields(&ok, today).is_empty());
+
+        let mut bad = Person::new("  ", date(2030, 1, 1), Gender::Male).with_email("al.example.com");
+        bad.address = Some(Address { street: "1 Main St".to_string(), city: "".to_string(), postal_code: "1".to_string() });
+        assert_eq!(fields(&bad, today), vec!["name", "birthdate", "email", "city"]);
+
+        let ancient = Person::new("Methuselah", date(1890, 1, 1), Gender::Male);
+        assert_eq!(fields(&ancient, today), vec!["birthdate"]);
+        let oldest_allowed = Person::new("Jeanne", date(1895, 6, 2), Gender::Female);
+        assert!(fields(&oldest_allowed, today).is_empty());
+    }
+
+    #[test]
+    fn unknown_gender_falls_back_to_other() {
+        let json = r#"{"name":"Sam","birthdate":"1990-01-01","gender":"agender"}"#;
+        let person: Person = serde_json::from_str(json).unwrap();
+        assert_eq!(person.gender, Gender::Other);
+    }
+
+    #[test]
+    fn load_reports_friendly_errors() {
+        assert!(matches!(load_people("/definitely/not/here.json"), Err(PersonError::FileNotFound(_))));
+
+        let path = std::env::temp_dir().join(format!("jsonpractice-{}-invalid.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, r#"[{"name":"","birthdate":"1990-01-01","gender":"male"}]"#).unwrap();
+        match load_people(path) {
+            Err(PersonError::Invalid(errors)) => assert_eq!(errors[0].field, "name"),
+            other => panic!("expected validation errors, got {:?}", other),
+        }
+        std::fs::write(path, "{ not json").unwrap();
+        assert!(matches!(load_people(path), Err(PersonError::Malformed { .. })));
+        std::fs::remove_file(path).unwrap();
+    }
+}
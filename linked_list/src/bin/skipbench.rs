@@ -0,0 +1,48 @@
+// times lookups in the skip list against the plain linked list and std's BTreeSet, as the
+// average time of one lookup. build with --release, debug timings say little about either list
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use linked_list::second::List;
+use linked_list::skiplist::SkipList;
+
+const ROUNDS: u32 = 3;
+
+fn per_lookup(probes: &[u64], contains: impl Fn(&u64) -> bool) -> Duration {
+    // every probe ROUNDS times over, so a one-off stall is spread thin
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(probes.iter().filter(|k| contains(k)).count());
+    }
+    start.elapsed() / (ROUNDS * probes.len().max(1) as u32)
+}
+
+fn keys(n: u64) -> Vec<u64> {
+    // n distinct values in a scrambled order
+    (0..n).map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 16).collect()
+}
+
+fn main() {
+    println!("{:<24} {:>12} {:>12} {:>12}", "per lookup", "linked list", "skip list", "BTreeSet");
+
+    for n in [1_000, 10_000, 100_000] {
+        let keys = keys(n);
+        let mut list = List::new();
+        for &k in &keys {
+            list.push(k);
+        }
+        let skip: SkipList<u64> = keys.iter().copied().collect();
+        let set: BTreeSet<u64> = keys.iter().copied().collect();
+
+        // a lookup per key plus as many that miss. the linked list is too slow for all of them,
+        // so it only gets the first 2,000
+        let probes: Vec<u64> = keys.iter().flat_map(|&k| [k, k + 1]).collect();
+        let sample = &probes[..probes.len().min(2_000)];
+
+        let linear = per_lookup(sample, |k| list.iter().any(|x| x == k));
+        let skipped = per_lookup(&probes, |k| skip.contains(k));
+        let tree = per_lookup(&probes, |k| set.contains(k));
+        println!("{:<24} {:>12.3?} {:>12.3?} {:>12.3?}", format!("{} keys", n), linear, skipped, tree);
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Mutex;
+
+// same singly linked stack as second.rs, but the head lives behind a Mutex so
+// push/pop only need &self and the stack can be shared between threads (e.g. in an Arc)
+pub struct ConcurrentStack<T> {
+    head: Mutex<Link<T>>,
+}
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
+}
+
+impl<T> ConcurrentStack<T> {
+    pub fn new() -> Self {
+        ConcurrentStack { head: Mutex::new(None) }
+    }
+
+    pub fn push(&self, elem: T) {
+        let mut head = self.head.lock().unwrap();
+        let new_node = Box::new(Node {
+            elem,
+            next: head.take(),
+        });
+        *head = Some(new_node);
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let mut head = self.head.lock().unwrap();
+        head.take().map(|node| {
+            *head = node.next;
+            node.elem
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.lock().unwrap().is_none()
+    }
+}
+
+impl<T> Default for ConcurrentStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for ConcurrentStack<T> {
+    fn drop(&mut self) {
+        // we have &mut self so nobody else can be holding the lock
+        let head = self.head.get_mut().unwrap_or_else(|e| e.into_inner());
+        let mut cur_link = head.take();
+
+        while let Some(mut boxed_node) = cur_link {
+            cur_link = boxed_node.next.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConcurrentStack;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn basics() {
+        let stack = ConcurrentStack::new();
+        assert_eq!(stack.pop(), None);
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+
+        stack.push(4);
+
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn stress() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const PER_PRODUCER: usize = 10_000;
+
+        let stack = Arc::new(ConcurrentStack::new());
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|p| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        stack.push(p * PER_PRODUCER + i);
+                    }
+                })
+            })
+            .collect();
+
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for _ in 0..PER_PRODUCER {
+                        match stack.pop() {
+                            Some(v) => popped.push(v),
+                            None => thread::yield_now(),
+                        }
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        for p in producers {
+            p.join().unwrap();
+        }
+
+        let mut seen: Vec<usize> = consumers
+            .into_iter()
+            .flat_map(|c| c.join().unwrap())
+            .collect();
+
+        // drain whatever the consumers didn't get to
+        while let Some(v) = stack.pop() {
+            seen.push(v);
+        }
+
+        seen.sort();
+        let expected: Vec<usize> = (0..PRODUCERS * PER_PRODUCER).collect();
+        assert_eq!(seen, expected);
+    }
+}
@@ -1,2 +1,4 @@
 pub mod first;
-pub mod second;
\ No newline at end of file
+pub mod second;
+pub mod concurrent;
+pub mod skiplist;
@@ -0,0 +1,256 @@
+// a sorted set built from linked lists stacked on top of each other. every node is on the
+// bottom list, about half of them are also on the next one up, a quarter on the one above
+// that, and so on, so a search can skip most of the list on the upper levels before dropping
+// down. insert, contains and remove are expected O(log n).
+//
+// the nodes live in a Vec and link to each other by index, which keeps it all safe Rust
+const MAX_LEVEL: usize = 16;
+
+type Links = [Option<usize>; MAX_LEVEL];
+
+struct SkipNode<T> {
+    // None once the node has been removed and its slot is waiting to be reused
+    elem: Option<T>,
+    // next[l] is the following node on level l; a node is on levels 0..next.len()
+    next: Vec<Option<usize>>,
+}
+
+pub struct SkipList<T> {
+    head: Links,
+    nodes: Vec<SkipNode<T>>,
+    free: Vec<usize>,
+    // levels with at least one node on them
+    levels: usize,
+    len: usize,
+    rng: u64,
+}
+
+pub struct Iter<'a, T> {
+    list: &'a SkipList<T>,
+    next: Option<usize>,
+}
+
+impl<T: Ord> SkipList<T> {
+    pub fn new() -> Self {
+        SkipList::with_seed(0x2545_f491_4f6c_dd1d)
+    }
+
+    // the seed decides which nodes get promoted, so the same seed and inserts give the same shape
+    pub fn with_seed(seed: u64) -> Self {
+        SkipList {
+            head: [None; MAX_LEVEL],
+            nodes: Vec::new(),
+            free: Vec::new(),
+            levels: 0,
+            len: 0,
+            rng: seed.max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, elem: &T) -> bool {
+        let path = self.path(elem);
+        self.next(path[0], 0).is_some_and(|n| self.elem(n) == elem)
+    }
+
+    // returns false if the element was already there, like BTreeSet::insert
+    pub fn insert(&mut self, elem: T) -> bool {
+        let path = self.path(&elem);
+        if self.next(path[0], 0).is_some_and(|n| *self.elem(n) == elem) {
+            return false;
+        }
+
+        let height = self.random_height();
+        // levels above the old top have no nodes yet, so their predecessor is the head,
+        // which is what None in the path already means
+        self.levels = self.levels.max(height);
+        let next = (0..height).map(|level| self.next(path[level], level)).collect();
+        let node = SkipNode { elem: Some(elem), next };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        for (level, &before) in path.iter().enumerate().take(height) {
+            self.set_next(before, level, Some(index));
+        }
+        self.len += 1;
+        true
+    }
+
+    pub fn remove(&mut self, elem: &T) -> bool {
+        let path = self.path(elem);
+        let index = match self.next(path[0], 0) {
+            Some(n) if self.elem(n) == elem => n,
+            _ => return false,
+        };
+
+        // the node is on levels 0..height and path[level] is right before it on each of them
+        let next = std::mem::take(&mut self.nodes[index].next);
+        for (level, after) in next.into_iter().enumerate() {
+            self.set_next(path[level], level, after);
+        }
+        self.nodes[index].elem = None;
+        self.free.push(index);
+        while self.levels > 0 && self.head[self.levels - 1].is_none() {
+            self.levels -= 1;
+        }
+        self.len -= 1;
+        true
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.head[0].map(|n| self.elem(n))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { list: self, next: self.head[0] }
+    }
+
+    fn path(&self, elem: &T) -> Links {
+        // on each level, the last node that's less than elem (None for the head)
+        let mut path = [None; MAX_LEVEL];
+        let mut current = None;
+        for level in (0..self.levels).rev() {
+            while let Some(n) = self.next(current, level) && self.elem(n) < elem {
+                current = Some(n);
+            }
+            path[level] = current;
+        }
+        path
+    }
+
+    fn random_height(&mut self) -> usize {
+        // xorshift, then one level per trailing 1 bit: height h has probability 1/2^h
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng.trailing_ones() as usize + 1).min(MAX_LEVEL)
+    }
+}
+
+impl<T> SkipList<T> {
+    fn next(&self, node: Option<usize>, level: usize) -> Option<usize> {
+        match node {
+            None => self.head[level],
+            Some(n) => self.nodes[n].next[level],
+        }
+    }
+
+    fn set_next(&mut self, node: Option<usize>, level: usize, to: Option<usize>) {
+        match node {
+            None => self.head[level] = to,
+            Some(n) => self.nodes[n].next[level] = to,
+        }
+    }
+
+    fn elem(&self, node: usize) -> &T {
+        self.nodes[node].elem.as_ref().expect("linked nodes hold an element")
+    }
+}
+
+impl<T: Ord> Default for SkipList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SkipList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = SkipList::new();
+        for elem in iter {
+            list.insert(elem);
+        }
+        list
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|n| {
+            self.next = self.list.nodes[n].next[0];
+            self.list.elem(n)
+        })
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a SkipList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SkipList;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn basics() {
+        let mut list = SkipList::new();
+        assert!(list.is_empty());
+        assert!(!list.contains(&1));
+
+        assert!(list.insert(3));
+        assert!(list.insert(1));
+        assert!(list.insert(2));
+        assert!(!list.insert(2));
+        assert_eq!(list.len(), 3);
+        assert!(list.contains(&2));
+        assert_eq!(list.first(), Some(&1));
+
+        assert!(list.remove(&2));
+        assert!(!list.remove(&2));
+        assert!(!list.contains(&2));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn iter() {
+        let list: SkipList<i32> = [5, -1, 3, 9, 3, 0].into_iter().collect();
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&-1));
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&5));
+        assert_eq!(iter.next(), Some(&9));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn matches_btreeset() {
+        // the same pseudo-random inserts and removes on both, checking they agree throughout
+        let mut list = SkipList::with_seed(7);
+        let mut set = BTreeSet::new();
+        let mut x: u32 = 12345;
+        for _ in 0..5_000 {
+            x = x.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let value = (x >> 16) % 500;
+            if x.is_multiple_of(3) {
+                assert_eq!(list.remove(&value), set.remove(&value));
+            } else {
+                assert_eq!(list.insert(value), set.insert(value));
+            }
+            assert_eq!(list.contains(&value), set.contains(&value));
+        }
+        assert_eq!(list.len(), set.len());
+        assert!(list.iter().eq(set.iter()));
+    }
+}
@@ -0,0 +1,522 @@
+// an amount of money stored as a whole number of cents, so sums never pick up the
+// fractions of a cent that f64 arithmetic leaves behind. every amount carries its
+// currency and arithmetic between different currencies is an error
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Currency {
+    #[default]
+    Usd,
+    Eur,
+    Gbp,
+}
+
+impl Currency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "USD" | "$" => Ok(Currency::Usd),
+            "EUR" | "€" => Ok(Currency::Eur),
+            "GBP" | "£" => Ok(Currency::Gbp),
+            _ => Err(MoneyError::Parse(format!("unknown currency '{}'", s))),
+        }
+    }
+}
+
+// how a fraction of a cent is settled when converting from f64 or applying a rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    // half a cent rounds away from zero
+    HalfUp,
+    // half a cent rounds to the even cent, so repeated roundings don't drift one way
+    #[default]
+    HalfEven,
+    // toward zero
+    Down,
+    // away from zero
+    Up,
+}
+
+impl Rounding {
+    fn apply(&self, cents: f64) -> f64 {
+        match self {
+            Rounding::HalfUp => cents.round(),
+            Rounding::HalfEven => cents.round_ties_even(),
+            Rounding::Down => cents.trunc(),
+            Rounding::Up => {
+                if cents < 0.0 { cents.floor() } else { cents.ceil() }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoneyError {
+    Overflow,
+    CurrencyMismatch { left: Currency, right: Currency },
+    Parse(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::Overflow => write!(f, "amount is too large"),
+            MoneyError::CurrencyMismatch { left, right } => write!(f, "cannot combine {} with {}", left, right),
+            MoneyError::Parse(reason) => write!(f, "invalid amount: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Money {
+    cents: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub const fn from_cents(cents: i64, currency: Currency) -> Self {
+        Money { cents, currency }
+    }
+
+    pub const fn usd(cents: i64) -> Self {
+        Money::from_cents(cents, Currency::Usd)
+    }
+
+    pub const fn zero(currency: Currency) -> Self {
+        Money::from_cents(0, currency)
+    }
+
+    pub fn from_f64(amount: f64, currency: Currency, rounding: Rounding) -> Result<Self, MoneyError> {
+        let cents = rounding.apply(amount * 100.0);
+        if !cents.is_finite() || cents.abs() >= i64::MAX as f64 {
+            return Err(MoneyError::Overflow);
+        }
+        Ok(Money::from_cents(cents as i64, currency))
+    }
+
+    pub fn dollars(amount: f64) -> Self {
+        // for literal amounts in code; panics on NaN or overflow
+        Money::from_f64(amount, Currency::Usd, Rounding::HalfEven).expect("amount out of range")
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.cents as f64 / 100.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.cents == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.cents < 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.cents > 0
+    }
+
+    pub fn abs(&self) -> Self {
+        Money::from_cents(self.cents.abs(), self.currency)
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch { left: self.currency, right: other.currency })
+        }
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        let cents = self.cents.checked_add(other.cents).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(cents, self.currency))
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, MoneyError> {
+        self.same_currency(&other)?;
+        let cents = self.cents.checked_sub(other.cents).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(cents, self.currency))
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Result<Money, MoneyError> {
+        let cents = self.cents.checked_mul(factor).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(cents, self.currency))
+    }
+
+    pub fn mul_rate(self, rate: f64, rounding: Rounding) -> Money {
+        // scales by a fraction such as a monthly interest rate, settling the result to a cent
+        Money::from_f64(self.cents as f64 * rate / 100.0, self.currency, rounding).expect("amount out of range")
+    }
+
+    pub fn ratio(self, other: Money) -> f64 {
+        // self / other as a plain number, e.g. for savings per dollar
+        self.cents as f64 / other.cents as f64
+    }
+
+    pub fn min(self, other: Money) -> Money {
+        if other < self { other } else { self }
+    }
+
+    pub fn max(self, other: Money) -> Money {
+        if other > self { other } else { self }
+    }
+
+    fn expect_ok(result: Result<Money, MoneyError>) -> Money {
+        // the operators panic like integer overflow does; use the checked_ methods to handle it
+        result.unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // amounts in different currencies aren't comparable
+        (self.currency == other.currency).then(|| self.cents.cmp(&other.cents))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money::expect_ok(self.checked_add(other))
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money::expect_ok(self.checked_sub(other))
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::from_cents(-self.cents, self.currency)
+    }
+}
+
+impl Sum for Money {
+    // the sum of nothing is zero dollars
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        let mut iter = iter;
+        let first = iter.next().unwrap_or_default();
+        iter.fold(first, |acc, m| acc + m)
+    }
+}
+
+// where the currency symbol goes when an amount is written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolPosition {
+    // $1,234.56
+    Before,
+    // 1.234,56 €
+    After,
+    // 1,234.56, e.g. for a spreadsheet
+    Omitted,
+}
+
+// how amounts are written out for a locale: the separators, the symbol and how many decimal
+// places. cents are exact, so rounding only comes in when showing fewer than two places
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    // between groups of three digits, or None for no grouping
+    pub grouping: Option<char>,
+    pub decimal: char,
+    pub symbol: SymbolPosition,
+    // 0 to 2
+    pub decimals: u32,
+    pub rounding: Rounding,
+}
+
+impl Default for FormatOptions {
+    // -$1,234.56
+    fn default() -> Self {
+        FormatOptions { grouping: Some(','), decimal: '.', symbol: SymbolPosition::Before, decimals: 2, rounding: Rounding::HalfEven }
+    }
+}
+
+impl FormatOptions {
+    // -1.234,56 €
+    pub fn european() -> Self {
+        FormatOptions { grouping: Some('.'), decimal: ',', symbol: SymbolPosition::After, ..FormatOptions::default() }
+    }
+
+    pub fn with_grouping(mut self, grouping: Option<char>) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    pub fn with_symbol(mut self, symbol: SymbolPosition) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    pub fn with_decimals(mut self, decimals: u32, rounding: Rounding) -> Self {
+        self.decimals = decimals.min(2);
+        self.rounding = rounding;
+        self
+    }
+}
+
+impl Money {
+    pub fn format(&self, options: &FormatOptions) -> String {
+        // whole units of the last decimal place shown, e.g. dollars with no decimals
+        let scale = 10_i64.pow(2 - options.decimals.min(2));
+        let shown = options.rounding.apply(self.cents as f64 / scale as f64) as i64;
+        let per_unit = 10_i64.pow(options.decimals.min(2));
+
+        let units = (shown / per_unit).unsigned_abs().to_string();
+        let mut text = String::new();
+        if shown < 0 {
+            text.push('-');
+        }
+        if options.symbol == SymbolPosition::Before {
+            text.push_str(self.currency.symbol());
+        }
+        for (i, digit) in units.chars().enumerate() {
+            if let Some(separator) = options.grouping
+                && i > 0 && (units.len() - i).is_multiple_of(3) {
+                text.push(separator);
+            }
+            text.push(digit);
+        }
+        if options.decimals > 0 {
+            text.push(options.decimal);
+            text.push_str(&format!("{:0width$}", (shown % per_unit).abs(), width = options.decimals as usize));
+        }
+        if options.symbol == SymbolPosition::After {
+            text.push(' ');
+            text.push_str(self.currency.symbol());
+        }
+        text
+    }
+}
+
+impl fmt::Display for Money {
+    // -$1,234.56. padding and alignment flags apply to the whole string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.format(&FormatOptions::default()))
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    // accepts "1234.5", "$1,234.50", "-12" and "12.34 EUR"; plain amounts are dollars
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (amount, currency) = match s.rsplit_once(' ') {
+            Some((amount, code)) => (amount.trim(), code.parse()?),
+            None => (s, Currency::Usd),
+        };
+        let (negative, amount) = match amount.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, amount),
+        };
+        let amount = amount.strip_prefix(currency.symbol()).unwrap_or(amount).replace(',', "");
+
+        let bad = || MoneyError::Parse(format!("'{}' is not an amount", s));
+        let (units, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+        if units.is_empty() && fraction.is_empty()
+            || !units.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > 2
+        {
+            return Err(bad());
+        }
+        let units: i64 = if units.is_empty() { 0 } else { units.parse().map_err(|_| MoneyError::Overflow)? };
+        let fraction: i64 = format!("{:0<2}", fraction).parse().map_err(|_| bad())?;
+        let cents = units.checked_mul(100).and_then(|c| c.checked_add(fraction)).ok_or(MoneyError::Overflow)?;
+        Ok(Money::from_cents(if negative { -cents } else { cents }, currency))
+    }
+}
+
+impl Serialize for Money {
+    // as "1234.56 USD", which keeps both the exact cents and the currency
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let text = format!("{}{}.{:02} {}", sign, (self.cents / 100).unsigned_abs(), (self.cents % 100).abs(), self.currency);
+        serializer.serialize_str(&text)
+    }
+}
+
+struct MoneyVisitor;
+
+impl Visitor<'_> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an amount like \"12.34 USD\" or a number of dollars")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    // files written before amounts were Money hold plain dollar numbers
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+        Money::from_f64(v, Currency::Usd, Rounding::HalfEven).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+        Money::usd(v).checked_mul(100).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+        let v = i64::try_from(v).map_err(|_| E::custom(MoneyError::Overflow))?;
+        self.visit_i64(v)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_do_not_drift() {
+        // ten cents added a thousand times is exactly a hundred dollars
+        let total: Money = std::iter::repeat_n(Money::dollars(0.10), 1000).sum();
+        assert_eq!(total, Money::usd(10_000));
+        let float: f64 = std::iter::repeat_n(0.10, 1000).sum();
+        assert_ne!(float, 100.0);
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let euros = Money::from_cents(500, Currency::Eur);
+        assert_eq!(
+            Money::usd(500).checked_add(euros),
+            Err(MoneyError::CurrencyMismatch { left: Currency::Usd, right: Currency::Eur })
+        );
+        assert_eq!(Money::usd(i64::MAX).checked_add(Money::usd(1)), Err(MoneyError::Overflow));
+        assert_eq!(Money::usd(250).checked_mul(3), Ok(Money::usd(750)));
+        assert_eq!(Money::usd(100) - Money::usd(250), Money::usd(-150));
+        assert!(Money::usd(1).partial_cmp(&euros).is_none());
+    }
+
+    #[test]
+    fn rounding_policies() {
+        // 2.5 cents and -2.5 cents under each policy
+        let cases = [
+            (Rounding::HalfUp, 3, -3),
+            (Rounding::HalfEven, 2, -2),
+            (Rounding::Down, 2, -2),
+            (Rounding::Up, 3, -3),
+        ];
+        for (rounding, up, down) in cases {
+            assert_eq!(Money::from_f64(0.025, Currency::Usd, rounding).unwrap().cents(), up, "{:?}", rounding);
+            assert_eq!(Money::from_f64(-0.025, Currency::Usd, rounding).unwrap().cents(), down, "{:?}", rounding);
+        }
+        // 5.5% a year on $479,000 for a month
+        assert_eq!(Money::dollars(479_000.0).mul_rate(0.055 / 12.0, Rounding::HalfEven), Money::usd(219_542));
+        assert!(Money::from_f64(f64::NAN, Currency::Usd, Rounding::HalfUp).is_err());
+    }
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(Money::usd(123_456_789).to_string(), "$1,234,567.89");
+        assert_eq!(Money::usd(-5).to_string(), "-$0.05");
+        assert_eq!(Money::from_cents(1000, Currency::Eur).to_string(), "€10.00");
+        assert_eq!(format!("{:>10}|{:<8}|", Money::usd(1250), Money::usd(1)), "    $12.50|$0.01   |");
+
+        assert_eq!("$1,234.5".parse(), Ok(Money::usd(123_450)));
+        assert_eq!("-12".parse(), Ok(Money::usd(-1200)));
+        assert_eq!(".75".parse(), Ok(Money::usd(75)));
+        assert_eq!("12.34 eur".parse(), Ok(Money::from_cents(1234, Currency::Eur)));
+        for bad in ["", "abc", "1.234", "12.34 XYZ", "1-2", "."] {
+            assert!(bad.parse::<Money>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn serde_round_trip_and_legacy_numbers() {
+        let amounts = vec![Money::usd(-1050), Money::from_cents(7, Currency::Gbp)];
+        let json = serde_json::to_string(&amounts).unwrap();
+        assert_eq!(json, r#"["-10.50 USD","0.07 GBP"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Money>>(&json).unwrap(), amounts);
+
+        let legacy: Vec<Money> = serde_json::from_str("[15000.0, 25, 0.1]").unwrap();
+        assert_eq!(legacy, vec![Money::usd(1_500_000), Money::usd(2500), Money::usd(10)]);
+    }
+
+    #[test]
+    fn formats_for_a_locale() {
+        let amount = Money::usd(-123456789);
+        assert_eq!(amount.format(&FormatOptions::default()), amount.to_string());
+        assert_eq!(amount.to_string(), "-$1,234,567.89");
+        assert_eq!(Money::from_cents(123456789, Currency::Eur).format(&FormatOptions::european()), "1.234.567,89 €");
+        let plain = FormatOptions::default().with_grouping(None).with_symbol(SymbolPosition::Omitted);
+        assert_eq!(Money::usd(5).format(&plain), "0.05");
+
+        // half a dollar goes to the even dollar or up, depending on the rounding
+        let whole = |rounding| FormatOptions::default().with_decimals(0, rounding);
+        assert_eq!(Money::usd(25050).format(&whole(Rounding::HalfEven)), "$250");
+        assert_eq!(Money::usd(25050).format(&whole(Rounding::HalfUp)), "$251");
+        assert_eq!(Money::usd(-150).format(&whole(Rounding::HalfUp)), "-$2");
+        assert_eq!(Money::usd(123449).format(&FormatOptions::default().with_decimals(1, Rounding::HalfUp)), "$1,234.5");
+    }
+}
@@ -0,0 +1,218 @@
+// paying down several loans at once, e.g. the house, a rental and a car, with a fixed amount
+// extra each month. the whole extra goes to one loan at a time, picked by the strategy, and
+// when a loan is paid off its payment rolls into the extra for the rest
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use money::Money;
+use simclock::Clock;
+
+use crate::{AmortizationSchedule, Mortgage, Payment, PaymentIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // highest rate first, which pays the least interest
+    Avalanche,
+    // smallest balance first, which pays loans off soonest
+    Snowball,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Strategy::Avalanche => write!(f, "Avalanche (highest rate first)"),
+            Strategy::Snowball => write!(f, "Snowball (smallest balance first)"),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Portfolio {
+    pub loans: Vec<(String, Mortgage)>,
+}
+
+// how one loan came out under the plan
+pub struct LoanPayoff {
+    pub name: String,
+    pub schedule: AmortizationSchedule,
+    pub payoff_date: DateTime<Utc>,
+    // the share of the extra budget, rolled over payments included, this loan got
+    pub extra_paid: Money,
+    // against the loan on its own, with only its own additional payment
+    pub interest_saved: Money,
+}
+
+pub struct AllocationPlan {
+    pub strategy: Strategy,
+    pub extra_budget: Money,
+    pub loans: Vec<LoanPayoff>,
+}
+
+impl Portfolio {
+    pub fn new() -> Self {
+        Portfolio { loans: Vec::new() }
+    }
+
+    pub fn with_loan(mut self, name: &str, mortgage: Mortgage) -> Self {
+        self.loans.push((name.to_string(), mortgage));
+        self
+    }
+
+    pub fn allocate(&self, extra_budget: Money, strategy: Strategy) -> AllocationPlan {
+        self.allocate_from(&Clock::system(), extra_budget, strategy)
+    }
+
+    pub fn allocate_from(&self, clock: &Clock, extra_budget: Money, strategy: Strategy) -> AllocationPlan {
+        // the loans pay in rounds, one payment each, so monthly loans line up month by month.
+        // each round the budget goes to the open loans in the strategy's order, each taking
+        // no more than it needs to pay off
+        let zero = Money::zero(extra_budget.currency());
+        let mut projections: Vec<PaymentIterator> = self.loans.iter().map(|(_, m)| {
+            let last = m.historical_payments.last().map_or(clock.now(), |p| p.payment_date);
+            PaymentIterator::projecting(m, m.frequency.next_date(last), m.additional_payment)
+        }).collect();
+        let mut payments: Vec<Vec<Payment>> = self.loans.iter().map(|(_, m)| m.historical_payments.clone()).collect();
+        let mut extra_paid = vec![zero; self.loans.len()];
+        let mut rolled_over = zero;
+
+        loop {
+            let mut open: Vec<usize> = (0..projections.len()).filter(|&i| projections[i].balance.is_positive()).collect();
+            let rate = |i: usize| projections[i].mortgage.rate_at(projections[i].payment_number);
+            let balance = |i: usize| projections[i].balance.cents();
+            match strategy {
+                Strategy::Avalanche => open.sort_by(|&a, &b| rate(b).total_cmp(&rate(a)).then(balance(a).cmp(&balance(b)))),
+                Strategy::Snowball => open.sort_by(|&a, &b| balance(a).cmp(&balance(b)).then(rate(b).total_cmp(&rate(a)))),
+            }
+
+            let mut budget = extra_budget + rolled_over;
+            let mut paid_any = false;
+            for i in open {
+                // what's still owed after the regular payment is all the extra that can be used
+                let Some(regular) = projections[i].clone().next() else {
+                    continue;
+                };
+                let extra = budget.min(regular.remaining_principal.max(zero));
+                budget -= extra;
+                extra_paid[i] += extra;
+
+                let projection = &mut projections[i];
+                projection.extra = projection.mortgage.additional_payment + extra;
+                let Some(payment) = projection.next() else {
+                    continue;
+                };
+                paid_any = true;
+                payments[i].push(payment);
+                if !projection.balance.is_positive() {
+                    rolled_over += projection.scheduled + projection.mortgage.additional_payment;
+                }
+            }
+            if !paid_any {
+                break;
+            }
+        }
+
+        let loans = self.loans.iter().zip(payments).zip(extra_paid).map(|(((name, mortgage), payments), extra_paid)| {
+            let schedule = mortgage.schedule_of(payments, clock);
+            LoanPayoff {
+                name: name.clone(),
+                payoff_date: schedule.payments.last().map_or(mortgage.origin_date, |p| p.payment_date),
+                extra_paid,
+                interest_saved: mortgage.schedule_from(clock).total_interest_paid - schedule.total_interest_paid,
+                schedule,
+            }
+        }).collect();
+        AllocationPlan { strategy, extra_budget, loans }
+    }
+}
+
+impl AllocationPlan {
+    // when the last loan is paid off
+    pub fn debt_free_date(&self) -> Option<DateTime<Utc>> {
+        self.loans.iter().map(|loan| loan.payoff_date).max()
+    }
+
+    pub fn total_interest(&self) -> Money {
+        self.loans.iter().fold(Money::zero(self.extra_budget.currency()), |total, loan| total + loan.schedule.total_interest_paid)
+    }
+
+    pub fn interest_saved(&self) -> Money {
+        self.loans.iter().fold(Money::zero(self.extra_budget.currency()), |total, loan| total + loan.interest_saved)
+    }
+
+    pub fn payoff_order(&self) -> Vec<&LoanPayoff> {
+        let mut order: Vec<&LoanPayoff> = self.loans.iter().collect();
+        order.sort_by_key(|loan| loan.payoff_date);
+        order
+    }
+}
+
+impl fmt::Display for AllocationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} with {} extra each payment", self.strategy, self.extra_budget)?;
+        for loan in self.payoff_order() {
+            writeln!(f, "  {}: paid off {}, {} extra paid, saving {} in interest",
+                loan.name, loan.payoff_date.format("%Y-%b-%d"), loan.extra_paid, loan.interest_saved)?;
+        }
+        if let Some(date) = self.debt_free_date() {
+            write!(f, "Debt free by {}: total interest {}, saving {}", date.format("%Y-%b-%d"), self.total_interest(), self.interest_saved())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn portfolio() -> Portfolio {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        Portfolio::new()
+            .with_loan("Home", Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30))
+            .with_loan("Rental", Mortgage::new(origin_date, Money::dollars(200000.0), 6.5, 30))
+            .with_loan("Car", Mortgage::new(origin_date, Money::dollars(30000.0), 4.9, 5))
+    }
+
+    fn clock() -> Clock {
+        Clock::starting_at(Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn targets_one_loan_at_a_time() {
+        let portfolio = portfolio();
+        let budget = Money::dollars(500.0);
+        let on_their_own: Vec<AmortizationSchedule> = portfolio.loans.iter().map(|(_, m)| m.schedule_from(&clock())).collect();
+
+        // the avalanche starts on the rental, the snowball on the car
+        let avalanche = portfolio.allocate_from(&clock(), budget, Strategy::Avalanche);
+        assert_eq!(avalanche.loans[1].schedule.payments[0].principal_payment, on_their_own[1].payments[0].principal_payment + budget);
+        assert_eq!(avalanche.loans[2].schedule.payments[0].principal_payment, on_their_own[2].payments[0].principal_payment);
+        let snowball = portfolio.allocate_from(&clock(), budget, Strategy::Snowball);
+        assert_eq!(snowball.loans[2].schedule.payments[0].principal_payment, on_their_own[2].payments[0].principal_payment + budget);
+        assert_eq!(snowball.payoff_order()[0].name, "Car");
+
+        // both pay everything off sooner than the minimums, the avalanche for less interest
+        for plan in [&avalanche, &snowball] {
+            assert!(plan.debt_free_date().unwrap() < on_their_own[0].payments.last().unwrap().payment_date);
+            assert!(plan.interest_saved().is_positive());
+            assert!(plan.loans.iter().all(|loan| loan.schedule.payments.last().unwrap().remaining_principal.is_zero()));
+        }
+        assert!(avalanche.total_interest() <= snowball.total_interest());
+        assert!(snowball.loans[2].payoff_date < avalanche.loans[2].payoff_date);
+        assert!(avalanche.to_string().contains("Debt free by "));
+    }
+
+    #[test]
+    fn rolls_over_paid_off_loans() {
+        // the car is paid off on schedule, then its payment goes to the rental
+        let plan = portfolio().allocate_from(&clock(), Money::zero(money::Currency::Usd), Strategy::Avalanche);
+        let car = &plan.loans[2];
+        assert_eq!(car.schedule.payments.len(), 60);
+        assert!(car.extra_paid.is_zero() && car.interest_saved.is_zero());
+
+        let rental = &plan.loans[1].schedule.payments;
+        assert!(rental[60].principal_payment > rental[59].principal_payment + Money::dollars(500.0));
+        assert!(plan.loans[1].extra_paid.is_positive());
+        assert!(plan.loans[0].extra_paid.is_positive());
+    }
+}
@@ -0,0 +1,203 @@
+// what a loan costs once the fees paid to get it are counted. the APR spreads points and fees
+// over the payments as if they were interest, and the points break-even finds when a lower
+// rate bought with points has made up for what the points cost, either on its own or against
+// investing the same cash instead
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use money::Money;
+use simclock::Clock;
+
+use crate::{CHARGE_ROUNDING, INTEREST_ROUNDING, Mortgage, Payment};
+
+// where each choice stands after some month: what's been saved up, less what's still owed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointsMonth {
+    pub month: u32,
+    pub date: DateTime<Utc>,
+    pub with_points: Money,
+    pub investing: Money,
+}
+
+pub struct PointsComparison {
+    // paid up front for the lower rate, over what the loan without points costs to close
+    pub points_cost: Money,
+    // yearly, in percent
+    pub annual_return: f64,
+    pub months: Vec<PointsMonth>,
+    // the first month paying the points comes out ahead
+    pub break_even: Option<PointsMonth>,
+}
+
+impl Mortgage {
+    pub fn points_cost(&self) -> Money {
+        self.principal.mul_rate(self.discount_points / 100.0, CHARGE_ROUNDING)
+    }
+
+    // everything paid at closing, prepaid interest included
+    pub fn upfront_costs(&self) -> Money {
+        self.points_cost() + self.origination_fee + self.closing_costs + self.prepaid_interest()
+    }
+
+    pub(crate) fn contract_payments(&self) -> Vec<Payment> {
+        // the payments the loan was written with, from the origin on, without any extra
+        // payments, lump sums, recasts, or payments missed or paused
+        let mut contract = self.clone().with_additional_payment(Money::zero(self.principal.currency()));
+        contract.historical_payments.clear();
+        contract.extra_payments.clear();
+        contract.lump_sums.clear();
+        contract.recasts.clear();
+        contract.shortfalls.clear();
+        contract.forbearance.clear();
+        contract.payments_from(&Clock::starting_at(self.origin_date)).collect()
+    }
+
+    // the yearly rate, in percent, at which the payments are worth what the borrower actually
+    // got: the principal less the upfront costs. pmi counts as a finance charge too
+    pub fn apr(&self) -> f64 {
+        let payments: Vec<f64> = self.contract_payments().iter().map(|p| (p.payment_amount + p.pmi_payment).to_f64()).collect();
+        let received = (self.principal - self.upfront_costs()).to_f64();
+        let present_value = |rate: f64| {
+            payments.iter().enumerate().map(|(k, amount)| amount / (1.0 + rate).powi(k as i32 + 1)).sum::<f64>()
+        };
+
+        // the present value falls as the rate rises, so bisect on the periodic rate
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..100 {
+            let middle = (low + high) / 2.0;
+            if present_value(middle) > received {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        (low + high) / 2.0 * self.frequency.per_year() as f64 * 100.0
+    }
+
+    // the first payment by which this loan has cost no more than `other` in interest, pmi and
+    // upfront costs, e.g. a rate bought down with points against the same loan without them.
+    // None if it never catches up
+    pub fn points_break_even(&self, other: &Mortgage) -> Option<u32> {
+        let zero = Money::zero(self.principal.currency());
+        let cost = |p: &Payment| p.interest_payment + p.pmi_payment;
+        let ours = self.contract_payments();
+        let theirs = other.contract_payments();
+
+        let mut our_total = self.upfront_costs();
+        let mut their_total = other.upfront_costs();
+        (0..ours.len().max(theirs.len()))
+            .find(|&i| {
+                our_total += ours.get(i).map_or(zero, cost);
+                their_total += theirs.get(i).map_or(zero, cost);
+                our_total <= their_total
+            })
+            .map(|i| i as u32 + 1)
+    }
+
+    // paying for this loan's lower rate against taking `other` and investing the difference in
+    // upfront costs at `annual_return` percent a year, compounded monthly. the points side
+    // invests what its lower payments save each month at the same return
+    pub fn points_vs_investing(&self, other: &Mortgage, annual_return: f64) -> PointsComparison {
+        let zero = Money::zero(self.principal.currency());
+        let monthly_return = annual_return / 100.0 / 12.0;
+        let ours = self.contract_payments();
+        let theirs = other.contract_payments();
+        let outlay = |p: Option<&Payment>| p.map_or(zero, |p| p.payment_amount + p.pmi_payment);
+        let balance = |p: Option<&Payment>| p.map_or(zero, |p| p.remaining_principal);
+        let points_cost = self.upfront_costs() - other.upfront_costs();
+
+        let mut our_savings = zero;
+        let mut invested = points_cost;
+        let months: Vec<PointsMonth> = (0..ours.len().max(theirs.len()))
+            .map(|i| {
+                let (our, their) = (ours.get(i), theirs.get(i));
+                our_savings += our_savings.mul_rate(monthly_return, INTEREST_ROUNDING) + outlay(their) - outlay(our);
+                invested += invested.mul_rate(monthly_return, INTEREST_ROUNDING);
+                PointsMonth {
+                    month: i as u32 + 1,
+                    date: our.or(their).map(|p| p.payment_date).unwrap_or(self.origin_date),
+                    with_points: our_savings - balance(our),
+                    investing: invested - balance(their),
+                }
+            })
+            .collect();
+        let break_even = months.iter().find(|m| m.with_points >= m.investing).copied();
+        PointsComparison { points_cost, annual_return, months, break_even }
+    }
+}
+
+impl fmt::Display for PointsComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Points cost {} against investing it at {:.1}% a year", self.points_cost, self.annual_return)?;
+        match &self.break_even {
+            Some(month) => writeln!(f, "The points come out ahead after {} payments, on {}", month.month, month.date.format("%Y-%b-%d"))?,
+            None => writeln!(f, "Investing comes out ahead for the whole loan")?,
+        }
+
+        // savings less what's owed at the end of each year
+        writeln!(f, "--------------------------------------------------")?;
+        writeln!(f, "{:>4} | {:>14} | {:>14} | {:>12}", "Year", "With Points", "Investing", "Difference")?;
+        writeln!(f, "--------------------------------------------------")?;
+        for (year, chunk) in self.months.chunks(12).enumerate() {
+            let end = chunk[chunk.len() - 1];
+            writeln!(f, "{:4} | {:>14} | {:>14} | {:>12}", year + 1, end.with_points, end.investing, end.with_points - end.investing)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn mortgage(rate: f64) -> Mortgage {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        Mortgage::new(origin_date, Money::dollars(479000.0), rate, 30)
+    }
+
+    #[test]
+    fn apr_counts_the_fees() {
+        // without fees the APR is the note rate
+        assert!((mortgage(5.5).apr() - 5.5).abs() < 0.001);
+
+        let with_fees = mortgage(5.25).with_points(1.0).with_origination_fee(Money::dollars(1500.0)).with_closing_costs(Money::dollars(500.0));
+        assert_eq!(with_fees.points_cost(), Money::dollars(4790.0));
+        assert_eq!(with_fees.upfront_costs(), Money::dollars(6790.0));
+        let apr = with_fees.apr();
+        assert!(apr > 5.25 && apr < 5.5, "apr {}", apr);
+    }
+
+    #[test]
+    fn points_break_even() {
+        let points = mortgage(5.25).with_points(1.0);
+        let none = mortgage(5.5);
+        let month = points.points_break_even(&none).unwrap();
+        assert!(month > 36 && month < 72, "month {}", month);
+
+        // without the lower rate the points never pay for themselves
+        assert_eq!(mortgage(5.5).with_points(1.0).points_break_even(&none), None);
+        // and a loan is never behind itself
+        assert_eq!(none.points_break_even(&none), Some(1));
+    }
+
+    #[test]
+    fn points_against_investing() {
+        let points = mortgage(5.25).with_points(1.0);
+        let none = mortgage(5.5);
+
+        // with nothing to earn on the cash, it's close to the plain break-even
+        let idle = points.points_vs_investing(&none, 0.0);
+        assert_eq!(idle.points_cost, Money::dollars(4790.0));
+        let month = idle.break_even.unwrap().month;
+        let plain = points.points_break_even(&none).unwrap();
+        assert!(month.abs_diff(plain) < 12, "{} against {}", month, plain);
+        assert_eq!(idle.months[0].investing, Money::dollars(4790.0) - none.contract_payments()[0].remaining_principal);
+
+        // a return on the cash pushes it out, and a high enough one means the points never win
+        let invested = points.points_vs_investing(&none, 7.0);
+        assert!(invested.break_even.unwrap().month > month);
+        assert_eq!(points.points_vs_investing(&none, 25.0).break_even, None);
+        assert!(invested.to_string().contains("The points come out ahead after "));
+    }
+}
@@ -0,0 +1,174 @@
+// a checked way to set up a loan. Mortgage::new takes whatever it's given, so a negative
+// principal or a zero-year term comes out as a schedule of NaNs or nothing at all; the builder
+// turns those into an error before any payments are worked out
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use money::Money;
+use simclock::Clock;
+
+use crate::{Mortgage, PaymentFrequency};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MortgageError {
+    NonPositivePrincipal(Money),
+    // in percent; has to be a number above zero
+    InvalidRate(f64),
+    ZeroTerm,
+    // history can only be generated for a loan that started by the clock's time
+    OriginInFuture(DateTime<Utc>),
+    NegativeAmount(&'static str, Money),
+    FirstPaymentBeforeOrigin(DateTime<Utc>),
+}
+
+impl fmt::Display for MortgageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MortgageError::NonPositivePrincipal(principal) => write!(f, "principal must be positive, got {}", principal),
+            MortgageError::InvalidRate(rate) => write!(f, "rate must be above 0%, got {}%", rate),
+            MortgageError::ZeroTerm => write!(f, "term must be at least a year"),
+            MortgageError::OriginInFuture(date) =>
+                write!(f, "origin date {} is in the future, so there's no history to generate", date.format("%Y-%m-%d")),
+            MortgageError::NegativeAmount(field, amount) => write!(f, "{} can't be negative, got {}", field, amount),
+            MortgageError::FirstPaymentBeforeOrigin(date) =>
+                write!(f, "first payment on {} has to be after the origin date", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl std::error::Error for MortgageError {}
+
+pub struct MortgageBuilder {
+    mortgage: Mortgage,
+    history: Option<Clock>,
+}
+
+impl Mortgage {
+    pub fn builder(origin_date: DateTime<Utc>, principal: Money, annual_rate: f64, term_years: u32) -> MortgageBuilder {
+        MortgageBuilder { mortgage: Mortgage::new(origin_date, principal, annual_rate, term_years), history: None }
+    }
+
+    // checks the terms a schedule can't be worked out without, e.g. for a loan read from json
+    pub fn validate(&self) -> Result<(), MortgageError> {
+        if !self.principal.is_positive() {
+            return Err(MortgageError::NonPositivePrincipal(self.principal));
+        }
+        if !self.annual_rate.is_finite() || self.annual_rate <= 0.0 {
+            return Err(MortgageError::InvalidRate(self.annual_rate));
+        }
+        if self.term_years == 0 {
+            return Err(MortgageError::ZeroTerm);
+        }
+        if let Some(first) = self.first_payment_date
+            && first <= self.origin_date {
+            return Err(MortgageError::FirstPaymentBeforeOrigin(first));
+        }
+        let amounts = [
+            ("additional payment", self.additional_payment),
+            ("property tax", self.property_tax),
+            ("insurance", self.insurance),
+            ("hoa dues", self.hoa),
+            ("home value", self.home_value),
+        ];
+        match amounts.into_iter().find(|(_, amount)| amount.is_negative()) {
+            Some((field, amount)) => Err(MortgageError::NegativeAmount(field, amount)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl MortgageBuilder {
+    pub fn with_frequency(mut self, frequency: PaymentFrequency) -> Self {
+        self.mortgage = self.mortgage.with_frequency(frequency);
+        self
+    }
+
+    pub fn with_first_payment(mut self, date: DateTime<Utc>) -> Self {
+        self.mortgage = self.mortgage.with_first_payment(date);
+        self
+    }
+
+    pub fn with_additional_payment(mut self, additional_payment: Money) -> Self {
+        self.mortgage = self.mortgage.with_additional_payment(additional_payment);
+        self
+    }
+
+    pub fn with_property_tax(mut self, annual_tax: Money) -> Self {
+        self.mortgage = self.mortgage.with_property_tax(annual_tax);
+        self
+    }
+
+    pub fn with_insurance(mut self, annual_premium: Money) -> Self {
+        self.mortgage = self.mortgage.with_insurance(annual_premium);
+        self
+    }
+
+    pub fn with_hoa(mut self, monthly_dues: Money) -> Self {
+        self.mortgage = self.mortgage.with_hoa(monthly_dues);
+        self
+    }
+
+    pub fn with_home_value(mut self, home_value: Money) -> Self {
+        self.mortgage = self.mortgage.with_home_value(home_value);
+        self
+    }
+
+    // records the payments due before the clock's time as history once the loan is built
+    pub fn with_history(mut self, clock: &Clock) -> Self {
+        self.history = Some(*clock);
+        self
+    }
+
+    pub fn build(self) -> Result<Mortgage, MortgageError> {
+        let mut mortgage = self.mortgage;
+        mortgage.validate()?;
+        if let Some(clock) = self.history {
+            if mortgage.origin_date > clock.now() {
+                return Err(MortgageError::OriginInFuture(mortgage.origin_date));
+            }
+            mortgage.generate_history(&clock);
+        }
+        Ok(mortgage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn origin_date() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn builds_a_valid_loan() {
+        let clock = Clock::starting_at(Utc.with_ymd_and_hms(2024, 8, 15, 0, 0, 0).unwrap());
+        let mortgage = Mortgage::builder(origin_date(), Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .with_history(&clock)
+            .build()
+            .unwrap();
+        assert_eq!(mortgage.history().len(), 13);
+        assert_eq!(mortgage.monthly_payment(), Mortgage::new(origin_date(), Money::dollars(479000.0), 5.5, 30).monthly_payment());
+    }
+
+    #[test]
+    fn rejects_bad_terms() {
+        let build = |principal: f64, rate: f64, years: u32| Mortgage::builder(origin_date(), Money::dollars(principal), rate, years).build();
+        assert_eq!(build(-5.0, 5.5, 30).err(), Some(MortgageError::NonPositivePrincipal(Money::dollars(-5.0))));
+        assert_eq!(build(479000.0, 0.0, 30).err(), Some(MortgageError::InvalidRate(0.0)));
+        assert!(matches!(build(479000.0, f64::NAN, 30), Err(MortgageError::InvalidRate(_))));
+        assert_eq!(build(479000.0, 5.5, 0).err(), Some(MortgageError::ZeroTerm));
+
+        let negative = Mortgage::builder(origin_date(), Money::dollars(479000.0), 5.5, 30)
+            .with_additional_payment(Money::dollars(-100.0))
+            .build();
+        assert_eq!(negative.err(), Some(MortgageError::NegativeAmount("additional payment", Money::dollars(-100.0))));
+
+        let before = Clock::starting_at(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap());
+        let early = Mortgage::builder(origin_date(), Money::dollars(479000.0), 5.5, 30).with_history(&before).build();
+        assert_eq!(early.err(), Some(MortgageError::OriginInFuture(origin_date())));
+        assert_eq!(MortgageError::ZeroTerm.to_string(), "term must be at least a year");
+    }
+}
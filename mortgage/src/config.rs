@@ -0,0 +1,187 @@
+// a planning file: the loans as they stand and the what-ifs to try on them, kept in json so
+// the plan can live in version control. each scenario changes the loans it names, or all of
+// them if it names none, and is measured on the whole portfolio against leaving it as is
+use std::fmt;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use simclock::Clock;
+
+use crate::Mortgage;
+use crate::builder::MortgageError;
+use crate::scenario::{ScenarioResult, ScenarioSpec};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PortfolioConfig {
+    pub loans: Vec<LoanEntry>,
+    #[serde(default)]
+    pub scenarios: Vec<ScenarioEntry>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LoanEntry {
+    pub name: String,
+    // in the mortgage program's json format
+    pub loan: Mortgage,
+}
+
+// a ScenarioSpec's fields, e.g. "rate": 5.5 or "lump_sums": [["2026-01-01T00:00:00Z", "10000.00 USD"]],
+// plus the loans it's for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioEntry {
+    #[serde(flatten)]
+    pub spec: ScenarioSpec,
+    #[serde(default)]
+    pub loans: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Json(serde_json::Error),
+    Loan(String, MortgageError),
+    UnknownLoan { scenario: String, loan: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Json(e) => write!(f, "could not read config: {}", e),
+            ConfigError::Loan(name, e) => write!(f, "loan '{}': {}", name, e),
+            ConfigError::UnknownLoan { scenario, loan } => write!(f, "scenario '{}' names loan '{}', which isn't in the config", scenario, loan),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        ConfigError::Json(e)
+    }
+}
+
+impl ScenarioEntry {
+    fn applies_to(&self, loan: &str) -> bool {
+        self.loans.is_empty() || self.loans.iter().any(|name| name == loan)
+    }
+}
+
+impl PortfolioConfig {
+    pub fn from_json(filepath: &str) -> Result<PortfolioConfig, ConfigError> {
+        let json_data = fs::read_to_string(filepath).map_err(serde_json::Error::io)?;
+        let config: PortfolioConfig = serde_json::from_str(&json_data)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // every loan's terms hold and every scenario only names loans in the file
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for entry in &self.loans {
+            entry.loan.validate().map_err(|e| ConfigError::Loan(entry.name.clone(), e))?;
+        }
+        for scenario in &self.scenarios {
+            if let Some(loan) = scenario.loans.iter().find(|name| !self.loans.iter().any(|entry| entry.name == **name)) {
+                return Err(ConfigError::UnknownLoan { scenario: scenario.spec.name.clone(), loan: loan.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn run(&self) -> Vec<ScenarioResult> {
+        self.run_from(&Clock::system())
+    }
+
+    // "As is" first, then each scenario in the file's order, totalled over every loan. the
+    // payments due before the clock's time are taken as made, so scenarios only change the
+    // ones still to come
+    pub fn run_from(&self, clock: &Clock) -> Vec<ScenarioResult> {
+        let loans: Vec<LoanEntry> = self.loans.iter().map(|entry| {
+            let mut loan = entry.loan.clone();
+            if loan.origin_date <= clock.now() {
+                loan.generate_history(clock);
+            }
+            LoanEntry { name: entry.name.clone(), loan }
+        }).collect();
+        let as_is = ScenarioEntry { spec: ScenarioSpec::new("As is"), loans: Vec::new() };
+        std::iter::once(&as_is).chain(&self.scenarios).map(|scenario| total(&loans, clock, scenario)).collect()
+    }
+}
+
+fn total(loans: &[LoanEntry], clock: &Clock, scenario: &ScenarioEntry) -> ScenarioResult {
+    // the loans the scenario leaves alone still count, unchanged, toward the totals
+    let results: Vec<ScenarioResult> = loans.iter().map(|entry| {
+        let spec = if scenario.applies_to(&entry.name) { scenario.spec.clone() } else { ScenarioSpec::new(&entry.name) };
+        entry.loan.compare_scenarios_from(clock, [spec]).remove(0)
+    }).collect();
+    let sum = |amount: fn(&ScenarioResult) -> money::Money| results.iter().map(amount).sum();
+    ScenarioResult {
+        spec: scenario.spec.clone(),
+        outlay: sum(|r| r.outlay),
+        total_payments: results.iter().map(|r| r.total_payments).sum(),
+        total_interest: sum(|r| r.total_interest),
+        payoff_date: results.iter().map(|r| r.payoff_date).max().unwrap_or(clock.now()),
+        interest_savings: sum(|r| r.interest_savings),
+        real_interest_savings: results.iter().map(|r| r.real_interest_savings).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{ScenarioOrder, sort_scenarios};
+    use chrono::{TimeZone, Utc};
+    use money::Money;
+
+    const CONFIG: &str = r#"{
+        "loans": [
+            { "name": "Home", "loan": { "origin_date": "2023-08-01T00:00:00Z", "principal": "479000.00 USD", "annual_rate": 7.0, "term_years": 30 } },
+            { "name": "Rental", "loan": { "origin_date": "2023-08-01T00:00:00Z", "principal": "200000.00 USD", "annual_rate": 6.5, "term_years": 30 } }
+        ],
+        "scenarios": [
+            { "name": "$500 extra on everything", "additional_payment": "500.00 USD" },
+            { "name": "Refinance the home", "loans": ["Home"], "rate": 5.5 },
+            { "name": "Bonus to the rental", "loans": ["Rental"], "lump_sums": [["2024-03-01T00:00:00Z", "20000.00 USD"]] }
+        ]
+    }"#;
+
+    fn clock() -> Clock {
+        Clock::starting_at(Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn runs_every_scenario_over_the_portfolio() {
+        let config: PortfolioConfig = serde_json::from_str(CONFIG).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.scenarios[1].spec, ScenarioSpec::new("Refinance the home").with_rate(5.5));
+
+        let mut results = config.run_from(&clock());
+        assert_eq!(results.len(), 4);
+        let as_is = &results[0];
+        let home = config.loans[0].loan.schedule_from(&clock());
+        let rental = config.loans[1].loan.schedule_from(&clock());
+        assert_eq!(as_is.total_interest, home.total_interest_paid + rental.total_interest_paid);
+        assert_eq!(as_is.total_payments, 720);
+        assert!(as_is.interest_savings.is_zero());
+
+        // the lump sum only moves the rental; the home still runs its full term
+        assert_eq!(results[3].payoff_date, as_is.payoff_date);
+        assert!(results[3].interest_savings.is_positive());
+        assert_eq!(results[1].outlay, as_is.outlay + Money::dollars(1000.0));
+
+        sort_scenarios(&mut results, ScenarioOrder::TotalInterest);
+        assert_eq!(results[0].spec.name, "$500 extra on everything");
+        assert_eq!(results[3].spec.name, "As is");
+    }
+
+    #[test]
+    fn rejects_unknown_loans() {
+        let mut config: PortfolioConfig = serde_json::from_str(CONFIG).unwrap();
+        config.scenarios[1].loans = vec!["Cabin".to_string()];
+        let error = config.validate().unwrap_err();
+        assert!(matches!(&error, ConfigError::UnknownLoan { loan, .. } if loan == "Cabin"));
+        assert!(error.to_string().contains("isn't in the config"));
+
+        config.loans[1].loan = Mortgage::new(Utc::now(), Money::dollars(-1.0), 6.5, 30);
+        assert!(matches!(config.validate(), Err(ConfigError::Loan(name, MortgageError::NonPositivePrincipal(_))) if name == "Rental"));
+    }
+}
@@ -0,0 +1,233 @@
+// two schedules side by side, month by month: what each pays, what each charges in interest
+// and what each still owes, and the months one overtakes the other. for a before and after,
+// e.g. refinancing or paying extra, with the after as the other schedule
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Utc};
+use money::Money;
+
+use crate::AmortizationSchedule;
+
+// one calendar month. payments in the same month are added up, and a schedule without a
+// payment in it still owes its last balance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffRow {
+    pub year: i32,
+    pub month: u32,
+    pub payment: Money,
+    pub other_payment: Money,
+    pub interest: Money,
+    pub other_interest: Money,
+    pub balance: Money,
+    pub other_balance: Money,
+    // interest paid so far
+    pub total_interest: Money,
+    pub other_total_interest: Money,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Payment,
+    Balance,
+    TotalInterest,
+}
+
+// a month the other schedule goes from above to below this one on a measure, or back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossover {
+    pub measure: Measure,
+    pub year: i32,
+    pub month: u32,
+    pub other_lower: bool,
+}
+
+pub struct ScheduleDiff {
+    pub rows: Vec<DiffRow>,
+    pub crossovers: Vec<Crossover>,
+}
+
+impl DiffRow {
+    // each difference is the other schedule less this one
+    pub fn payment_difference(&self) -> Money {
+        self.other_payment - self.payment
+    }
+
+    pub fn interest_difference(&self) -> Money {
+        self.other_interest - self.interest
+    }
+
+    pub fn balance_difference(&self) -> Money {
+        self.other_balance - self.balance
+    }
+
+    pub fn total_interest_difference(&self) -> Money {
+        self.other_total_interest - self.total_interest
+    }
+
+    fn difference(&self, measure: Measure) -> Money {
+        match measure {
+            Measure::Payment => self.payment_difference(),
+            Measure::Balance => self.balance_difference(),
+            Measure::TotalInterest => self.total_interest_difference(),
+        }
+    }
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Measure::Payment => write!(f, "payment"),
+            Measure::Balance => write!(f, "balance"),
+            Measure::TotalInterest => write!(f, "interest paid so far"),
+        }
+    }
+}
+
+impl AmortizationSchedule {
+    pub fn diff(&self, other: &AmortizationSchedule) -> ScheduleDiff {
+        let mut keys: Vec<(i32, u32)> = self.payments.iter().chain(&other.payments)
+            .map(|p| (p.payment_date.year(), p.payment_date.month()))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let zero = Money::zero(self.total_paid.currency());
+        // (payment, interest, balance, interest so far) for each schedule, carried month to month
+        let mut ours = (zero, zero, self.starting_balance(), zero);
+        let mut theirs = (zero, zero, other.starting_balance(), zero);
+        let month = |schedule: &AmortizationSchedule, totals: &mut (Money, Money, Money, Money), year: i32, month: u32| {
+            let in_month = |date: DateTime<Utc>| date.year() == year && date.month() == month;
+            totals.0 = zero;
+            totals.1 = zero;
+            for p in schedule.payments.iter().filter(|p| in_month(p.payment_date)) {
+                totals.0 += p.payment_amount;
+                totals.1 += p.interest_payment;
+                totals.2 = p.remaining_principal;
+                totals.3 += p.interest_payment;
+            }
+        };
+        let rows: Vec<DiffRow> = keys.into_iter().map(|(year, m)| {
+            month(self, &mut ours, year, m);
+            month(other, &mut theirs, year, m);
+            DiffRow {
+                year,
+                month: m,
+                payment: ours.0,
+                other_payment: theirs.0,
+                interest: ours.1,
+                other_interest: theirs.1,
+                balance: ours.2,
+                other_balance: theirs.2,
+                total_interest: ours.3,
+                other_total_interest: theirs.3,
+            }
+        }).collect();
+
+        // a crossover is where a difference changes sign; months where they're equal don't count
+        let mut crossovers = Vec::new();
+        for measure in [Measure::Payment, Measure::Balance, Measure::TotalInterest] {
+            let mut previous = Ordering::Equal;
+            for row in &rows {
+                let sign = row.difference(measure).cents().cmp(&0);
+                if sign == Ordering::Equal {
+                    continue;
+                }
+                if previous != Ordering::Equal && sign != previous {
+                    crossovers.push(Crossover { measure, year: row.year, month: row.month, other_lower: sign == Ordering::Less });
+                }
+                previous = sign;
+            }
+        }
+        crossovers.sort_by_key(|c| (c.year, c.month));
+        ScheduleDiff { rows, crossovers }
+    }
+}
+
+impl fmt::Display for ScheduleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "-".repeat(80);
+        writeln!(f, "Schedule Differences (other less this)")?;
+        writeln!(f, "{}", rule)?;
+        writeln!(f, "{:>7} | {:>14} | {:>14} | {:>16} | {:>16}", "Month", "Payment", "Interest", "Balance", "Interest So Far")?;
+        writeln!(f, "{}", rule)?;
+        for row in &self.rows {
+            writeln!(f, "{:4}-{:02} | {:>14} | {:>14} | {:>16} | {:>16}", row.year, row.month,
+                row.payment_difference(), row.interest_difference(), row.balance_difference(), row.total_interest_difference())?;
+        }
+        writeln!(f, "{}", rule)?;
+        for crossover in &self.crossovers {
+            let direction = if crossover.other_lower { "below" } else { "above" };
+            writeln!(f, "{}-{:02}: the other {} goes {} this one", crossover.year, crossover.month, crossover.measure, direction)?;
+        }
+        if let Some(last) = self.rows.last() {
+            writeln!(f, "Total Interest: {} vs {} ({})", last.total_interest, last.other_total_interest, last.total_interest_difference())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mortgage;
+    use simclock::Clock;
+    use chrono::TimeZone;
+
+    fn origin_date() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap()
+    }
+
+    fn schedule(principal: f64, rate: f64, extra: f64) -> AmortizationSchedule {
+        Mortgage::new(origin_date(), Money::dollars(principal), rate, 30)
+            .with_additional_payment(Money::dollars(extra))
+            .schedule_from(&Clock::starting_at(origin_date()))
+    }
+
+    #[test]
+    fn lines_up_by_month() {
+        let base = schedule(479000.0, 5.5, 0.0);
+        let extra = schedule(479000.0, 5.5, 500.0);
+        let diff = base.diff(&extra);
+
+        // runs as long as the longer schedule, with the extra paid each month until it's done
+        assert_eq!(diff.rows.len(), 360);
+        let first = &diff.rows[0];
+        assert_eq!((first.year, first.month), (2023, 9));
+        assert_eq!(first.payment_difference(), Money::dollars(500.0));
+        assert_eq!(first.balance_difference(), Money::dollars(-500.0));
+        let last = diff.rows.last().unwrap();
+        assert_eq!(last.other_payment, Money::dollars(0.0));
+        assert!(last.other_balance.is_zero() && last.balance.is_zero());
+        assert_eq!(last.total_interest_difference(), extra.total_interest_paid - base.total_interest_paid);
+
+        // the payments cross with the faster loan's smaller last payment; the balances never do
+        assert_eq!(diff.crossovers.len(), 1);
+        let payoff = extra.payments.last().unwrap().payment_date;
+        assert_eq!(diff.crossovers[0], Crossover {
+            measure: Measure::Payment,
+            year: payoff.year(),
+            month: payoff.month(),
+            other_lower: true,
+        });
+    }
+
+    #[test]
+    fn finds_the_balance_crossover() {
+        // a refinance with costs rolled in owes more at first, but pays down faster at the lower rate
+        let original = schedule(479000.0, 7.0, 0.0);
+        let refinanced = schedule(485000.0, 5.5, 0.0);
+        let diff = original.diff(&refinanced);
+
+        let crossover = diff.crossovers.iter().find(|c| c.measure == Measure::Balance).unwrap();
+        assert!(crossover.other_lower);
+        assert!(crossover.year > 2024 && crossover.year < 2030);
+        let at = diff.rows.iter().position(|r| (r.year, r.month) == (crossover.year, crossover.month)).unwrap();
+        assert!(diff.rows[at - 1].balance_difference().is_positive());
+        assert!(diff.rows[at].balance_difference().is_negative());
+
+        // less interest from the first month, so that never crosses
+        assert!(diff.crossovers.iter().all(|c| c.measure != Measure::TotalInterest));
+        assert!(diff.to_string().contains("the other balance goes below this one"));
+    }
+}
@@ -0,0 +1,284 @@
+// a home equity line of credit: borrow against the house up to a limit during the draw period,
+// paying only the interest, then pay down whatever was drawn over the repayment period. the
+// rate floats with an index, like prime, plus a margin. a household schedule puts it next to
+// the first mortgage to show everything owed each month
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Months, Utc};
+use money::Money;
+use serde::{Deserialize, Serialize};
+
+use crate::{AmortizationSchedule, level_payment, periodic_interest};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Heloc {
+    pub origin_date: DateTime<Utc>,
+    pub credit_limit: Money,
+    // rates are percents. the index changes on the dates given, holding until the next change
+    pub index_rate: f64,
+    pub margin: f64,
+    pub index_changes: Vec<(DateTime<Utc>, f64)>,
+    pub draw_months: u32,
+    pub repayment_months: u32,
+    // each added to the balance at the start of the month it falls in, as far as the limit allows.
+    // a month starts on a payment date, so a draw on one is charged interest from the next payment
+    pub draws: Vec<(DateTime<Utc>, Money)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HelocPayment {
+    pub payment_date: DateTime<Utc>,
+    pub payment_number: u32,
+    pub draw: Money,
+    pub payment_amount: Money,
+    pub principal_payment: Money,
+    pub interest_payment: Money,
+    pub balance: Money,
+    pub rate: f64,
+    // still in the draw period, when only the interest is due
+    pub interest_only: bool,
+}
+
+impl Heloc {
+    pub fn new(origin_date: DateTime<Utc>, credit_limit: Money, index_rate: f64, margin: f64, draw_years: u32, repayment_years: u32) -> Self {
+        Heloc {
+            origin_date,
+            credit_limit,
+            index_rate,
+            margin,
+            index_changes: Vec::new(),
+            draw_months: draw_years * 12,
+            repayment_months: repayment_years * 12,
+            draws: Vec::new(),
+        }
+    }
+
+    pub fn with_draw(mut self, date: DateTime<Utc>, amount: Money) -> Self {
+        self.draws.push((date, amount));
+        self
+    }
+
+    pub fn with_index_change(mut self, date: DateTime<Utc>, index_rate: f64) -> Self {
+        self.index_changes.push((date, index_rate));
+        self
+    }
+
+    pub fn rate_on(&self, date: DateTime<Utc>) -> f64 {
+        // the latest change on or before the date; a rate never goes below zero
+        let index = self.index_changes.iter()
+            .filter(|(when, _)| *when <= date)
+            .max_by_key(|(when, _)| *when)
+            .map_or(self.index_rate, |(_, rate)| *rate);
+        (index + self.margin).max(0.0)
+    }
+
+    pub fn schedule(&self) -> Vec<HelocPayment> {
+        // monthly payments from a month after opening until the repayment period ends or,
+        // once the draw period is over, the balance is paid off
+        let zero = Money::zero(self.credit_limit.currency());
+        let total_months = self.draw_months + self.repayment_months;
+        let mut payments = Vec::new();
+        let mut balance = zero;
+        let mut previous = None;
+        for payment_number in 1..=total_months {
+            let payment_date = self.origin_date.checked_add_months(Months::new(payment_number)).unwrap();
+            let interest_only = payment_number <= self.draw_months;
+            let draw = if interest_only {
+                self.draws.iter()
+                    .filter(|(when, _)| *when < payment_date && previous.is_none_or(|previous| *when >= previous))
+                    .fold(zero, |total, (_, amount)| total + *amount)
+                    .min(self.credit_limit - balance)
+            } else {
+                zero
+            };
+            balance += draw;
+            if !interest_only && !balance.is_positive() {
+                break;
+            }
+
+            let rate = self.rate_on(payment_date);
+            let interest_payment = periodic_interest(balance, rate, 1.0 / 12.0);
+            // the repayment period re-amortizes every month, so a rate change moves the payment
+            let months_left = total_months - payment_number + 1;
+            let payment_amount = if interest_only {
+                interest_payment
+            } else if months_left == 1 {
+                balance + interest_payment
+            } else {
+                level_payment(balance, rate, months_left).min(balance + interest_payment)
+            };
+            let principal_payment = payment_amount - interest_payment;
+            balance -= principal_payment;
+
+            payments.push(HelocPayment {
+                payment_date,
+                payment_number,
+                draw,
+                payment_amount,
+                principal_payment,
+                interest_payment,
+                balance,
+                rate,
+                interest_only,
+            });
+            previous = Some(payment_date);
+        }
+        payments
+    }
+}
+
+// what the household owes on both loans in one calendar month
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HouseholdMonth {
+    pub year: i32,
+    pub month: u32,
+    // everything due on the first mortgage: principal, interest, escrow, pmi, hoa and fees
+    pub mortgage: Money,
+    pub heloc: Money,
+    pub total: Money,
+    // owed on both loans at the end of the month
+    pub balance: Money,
+}
+
+pub struct HouseholdSchedule {
+    pub months: Vec<HouseholdMonth>,
+}
+
+impl HouseholdSchedule {
+    pub fn new(mortgage: &AmortizationSchedule, heloc: &[HelocPayment]) -> Self {
+        // payments are grouped by calendar month, so bi-weekly mortgage payments add up.
+        // a loan without a payment in a month still owes its last balance
+        let mut keys: Vec<(i32, u32)> = mortgage.payments.iter().map(|p| p.payment_date)
+            .chain(heloc.iter().map(|p| p.payment_date))
+            .map(|date| (date.year(), date.month()))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let zero = mortgage.payments.first().map_or(Money::default(), |p| Money::zero(p.payment_amount.currency()));
+        let (mut mortgage_balance, mut heloc_balance) = (mortgage.payments.first().map_or(zero, |p| p.remaining_principal + p.principal_payment), zero);
+        let months = keys.into_iter().map(|(year, month)| {
+            let in_month = |date: DateTime<Utc>| date.year() == year && date.month() == month;
+            let mut mortgage_due = zero;
+            for p in mortgage.payments.iter().filter(|p| in_month(p.payment_date)) {
+                mortgage_due += p.total_due();
+                mortgage_balance = p.remaining_principal;
+            }
+            let mut heloc_due = zero;
+            for p in heloc.iter().filter(|p| in_month(p.payment_date)) {
+                heloc_due += p.payment_amount;
+                heloc_balance = p.balance;
+            }
+            HouseholdMonth {
+                year,
+                month,
+                mortgage: mortgage_due,
+                heloc: heloc_due,
+                total: mortgage_due + heloc_due,
+                balance: mortgage_balance + heloc_balance,
+            }
+        }).collect();
+        HouseholdSchedule { months }
+    }
+
+    // the month with the most to pay, the earliest if there's a tie
+    pub fn peak(&self) -> Option<&HouseholdMonth> {
+        self.months.iter().rev().max_by_key(|m| m.total.cents())
+    }
+}
+
+impl fmt::Display for HouseholdSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "-".repeat(72);
+        writeln!(f, "Household Debt Service")?;
+        writeln!(f, "{}", rule)?;
+        writeln!(f, "{:>7} | {:>12} | {:>12} | {:>12} | {:>14}", "Month", "Mortgage", "HELOC", "Total", "Owed")?;
+        writeln!(f, "{}", rule)?;
+        for m in &self.months {
+            writeln!(f, "{:4}-{:02} | {:>12} | {:>12} | {:>12} | {:>14}", m.year, m.month, m.mortgage, m.heloc, m.total, m.balance)?;
+        }
+        writeln!(f, "{}", rule)?;
+        if let Some(peak) = self.peak() {
+            writeln!(f, "Highest Month: {} in {}-{:02}", peak.total, peak.year, peak.month)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mortgage;
+    use chrono::TimeZone;
+    use simclock::Clock;
+
+    fn date(year: i32, month: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap()
+    }
+
+    fn heloc() -> Heloc {
+        Heloc::new(date(2024, 1), Money::dollars(100000.0), 8.5, 0.5, 10, 20)
+            .with_draw(date(2024, 1), Money::dollars(40000.0))
+            .with_draw(date(2025, 6), Money::dollars(80000.0))
+    }
+
+    #[test]
+    fn draws_then_repays() {
+        let schedule = heloc().schedule();
+
+        // the first draw, then interest only on it at index plus margin
+        let first = &schedule[0];
+        assert_eq!((first.payment_date, first.draw, first.rate), (date(2024, 2), Money::dollars(40000.0), 9.0));
+        assert_eq!(first.payment_amount, Money::dollars(300.0));
+        assert!(first.principal_payment.is_zero() && first.interest_only);
+
+        // the second draw, on the June payment date, only gets what's left of the limit and is
+        // charged interest from there: June's payment is still on the first draw alone
+        let june = schedule.iter().find(|p| p.payment_date == date(2025, 6)).unwrap();
+        assert!(june.draw.is_zero());
+        assert_eq!(june.interest_payment, Money::dollars(300.0));
+        let july = schedule.iter().find(|p| p.draw.is_positive() && p.payment_number > 1).unwrap();
+        assert_eq!(july.payment_date, date(2025, 7));
+        assert_eq!(july.draw, Money::dollars(60000.0));
+        assert_eq!(july.interest_payment, Money::dollars(750.0));
+        assert_eq!(july.balance, Money::dollars(100000.0));
+
+        // after ten years it amortizes over twenty and pays off
+        let repaying = &schedule[120];
+        assert!(!repaying.interest_only && repaying.principal_payment.is_positive());
+        assert_eq!(schedule.len(), 360);
+        assert!(schedule.last().unwrap().balance.is_zero());
+    }
+
+    #[test]
+    fn follows_the_index() {
+        let schedule = heloc().with_index_change(date(2027, 3), 6.5).schedule();
+        assert_eq!(schedule[36].rate, 9.0);
+        assert_eq!(schedule[37].payment_date, date(2027, 3));
+        assert_eq!(schedule[37].rate, 7.0);
+        assert!(schedule[37].payment_amount < schedule[36].payment_amount);
+    }
+
+    #[test]
+    fn household_adds_both_loans() {
+        let mortgage = Mortgage::new(date(2023, 8), Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .schedule_from(&Clock::starting_at(date(2023, 8)));
+        let heloc = heloc().schedule();
+        let household = HouseholdSchedule::new(&mortgage, &heloc);
+
+        // September 2023 to the last HELOC payment in January 2054
+        assert_eq!(household.months.len(), 365);
+        let feb_2024 = household.months.iter().find(|m| (m.year, m.month) == (2024, 2)).unwrap();
+        assert_eq!(feb_2024.mortgage, mortgage.payments[5].total_due());
+        assert_eq!(feb_2024.heloc, Money::dollars(300.0));
+        assert_eq!(feb_2024.total, feb_2024.mortgage + feb_2024.heloc);
+        assert_eq!(feb_2024.balance, mortgage.payments[5].remaining_principal + Money::dollars(40000.0));
+
+        // repaying the full line on top of the mortgage is the most expensive stretch
+        let peak = household.peak().unwrap();
+        assert!(peak.year >= 2034 && peak.heloc > Money::dollars(750.0));
+        assert!(household.months.iter().all(|m| m.total <= peak.total));
+        assert!(household.to_string().contains("Highest Month: "));
+    }
+}
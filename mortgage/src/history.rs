@@ -0,0 +1,378 @@
+// the payments that were actually made, from statements instead of the formula, so extra
+// principal and odd amounts are counted and the projection starts from the true balance
+use std::fmt;
+use std::fs::File;
+use std::io;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use money::Money;
+use simclock::Clock;
+
+use crate::{Mortgage, Payment};
+
+// what's wrong with one statement line
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatementProblem {
+    NoDate,
+    BadDate(String),
+    NoAmount,
+    BadAmount(String),
+    // principal and interest that don't make up the amount paid
+    Split { principal: Money, interest: Money, amount: Money },
+    // on or before the line above it, or the last payment already recorded
+    NotAfter(NaiveDate),
+}
+
+impl fmt::Display for StatementProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatementProblem::NoDate => write!(f, "no date"),
+            StatementProblem::BadDate(text) => write!(f, "'{}' isn't a YYYY-MM-DD date", text),
+            StatementProblem::NoAmount => write!(f, "no amount"),
+            StatementProblem::BadAmount(text) => write!(f, "'{}' isn't an amount of money", text),
+            StatementProblem::Split { principal, interest, amount } =>
+                write!(f, "principal {} and interest {} don't make up the {} paid", principal, interest, amount),
+            StatementProblem::NotAfter(date) => write!(f, "{} is out of date order", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Csv(csv::Error),
+    // date or amount
+    NoColumn(&'static str),
+    // the statement lines that can't be used, by line number
+    Statements(Vec<(u64, StatementProblem)>),
+    // payments have to be added in date order, after any already recorded
+    OutOfOrder(DateTime<Utc>),
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::Csv(e) => write!(f, "could not read statements: {}", e),
+            HistoryError::NoColumn(column) => write!(f, "statements need a {} column", column),
+            HistoryError::Statements(problems) => {
+                let lines: Vec<String> = problems.iter().map(|(line, problem)| format!("line {} has {}", line, problem)).collect();
+                write!(f, "statements not recorded: {}", lines.join("; "))
+            }
+            HistoryError::OutOfOrder(date) =>
+                write!(f, "payment on {} is not after the last recorded payment", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl From<csv::Error> for HistoryError {
+    fn from(e: csv::Error) -> Self {
+        HistoryError::Csv(e)
+    }
+}
+
+// what the extra principal in the history has done so far: the balance it's paid down, the
+// interest it's already saved and how much sooner the loan ends, with no more extra from here
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtraPaymentReport {
+    // the last recorded payment
+    pub as_of: DateTime<Utc>,
+    // paid over what was due. the balance is lower than on the formula alone by this plus
+    // the interest saved so far
+    pub extra_principal: Money,
+    pub interest_saved_to_date: Money,
+    pub payoff_date: DateTime<Utc>,
+    pub payoff_date_without_extra: DateTime<Utc>,
+    pub interest_saved_over_loan: Money,
+}
+
+impl ExtraPaymentReport {
+    pub fn months_saved(&self) -> i32 {
+        let months = |date: DateTime<Utc>| date.year() * 12 + date.month() as i32;
+        months(self.payoff_date_without_extra) - months(self.payoff_date)
+    }
+}
+
+impl fmt::Display for ExtraPaymentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Extra principal paid through {}: {}", self.as_of.format("%Y-%b-%d"), self.extra_principal)?;
+        writeln!(f, "Interest saved so far: {}", self.interest_saved_to_date)?;
+        writeln!(f, "Payoff moved from {} to {}, {} months sooner", self.payoff_date_without_extra.format("%Y-%b-%d"),
+            self.payoff_date.format("%Y-%b-%d"), self.months_saved())?;
+        write!(f, "Interest saved over the loan: {}", self.interest_saved_over_loan)
+    }
+}
+
+// one statement line: when, how much principal and interest was paid, and optionally how the
+// servicer split it. without a split the interest is worked out from the balance
+struct Record {
+    date: DateTime<Utc>,
+    amount: Money,
+    principal: Option<Money>,
+    interest: Option<Money>,
+}
+
+impl Mortgage {
+    // records a payment after the history so far. it's numbered to follow on, and its
+    // remaining principal is worked out from the balance before it and the principal it paid
+    pub fn add_payment(&mut self, mut payment: Payment) -> Result<(), HistoryError> {
+        let last = self.historical_payments.last();
+        if last.is_some_and(|p| p.payment_date >= payment.payment_date) {
+            return Err(HistoryError::OutOfOrder(payment.payment_date));
+        }
+        payment.payment_number = last.map_or(1, |p| p.payment_number + 1);
+        payment.remaining_principal = self.remaining_principal() - payment.principal_payment;
+        self.historical_payments.push(payment);
+        Ok(())
+    }
+
+    // records a payment of `amount` toward principal and interest on `date`. escrow, pmi and
+    // the rest come from the loan's terms, and anything over what was due is extra principal
+    pub fn record_payment(&mut self, date: DateTime<Utc>, amount: Money) -> Result<(), HistoryError> {
+        self.record(Record { date, amount, principal: None, interest: None })
+    }
+
+    fn record(&mut self, record: Record) -> Result<(), HistoryError> {
+        let zero = Money::zero(self.principal.currency());
+        let last = self.historical_payments.last();
+        let number = last.map_or(1, |p| p.payment_number + 1);
+        let mut scheduled = self.scheduled_payment();
+        let mut payment = self.payment_on(number, record.date, last.map(|p| p.payment_date),
+            self.remaining_principal(), &mut scheduled, zero);
+
+        let due = payment.payment_amount;
+        payment.interest_payment = record.interest.unwrap_or(payment.interest_payment);
+        payment.principal_payment = record.principal.unwrap_or(record.amount - payment.interest_payment);
+        payment.payment_amount = record.amount;
+        payment.shortfall = (due - record.amount).max(zero);
+        self.add_payment(payment)
+    }
+
+    // looks back at the recorded payments against the same months on the formula alone. None
+    // without any history
+    pub fn past_extra_savings(&self) -> Option<ExtraPaymentReport> {
+        let last = self.historical_payments.last()?;
+        // the history as it was, with nothing extra from here on
+        let mut actual = self.clone();
+        actual.additional_payment = Money::zero(self.principal.currency());
+        actual.extra_payments.clear();
+        actual.lump_sums.retain(|(date, _)| *date <= last.payment_date);
+        actual.recasts.retain(|(date, _)| *date <= last.payment_date);
+        // and the same payment dates as if only what was due had been paid
+        let mut without = actual.clone();
+        without.lump_sums.clear();
+        without.recasts.clear();
+        without.historical_payments.clear();
+        let zero = Money::zero(self.principal.currency());
+        let mut scheduled = without.scheduled_payment();
+        let mut previous = None;
+        for p in &self.historical_payments {
+            let payment = without.payment_on(p.payment_number, p.payment_date, previous, without.remaining_principal(), &mut scheduled, zero);
+            without.historical_payments.push(payment);
+            previous = Some(p.payment_date);
+        }
+        let clock = Clock::starting_at(last.payment_date + Duration::seconds(1));
+
+        let interest = |payments: &[Payment]| payments.iter().map(|p| p.interest_payment).sum::<Money>();
+        let paid = |payments: &[Payment]| payments.iter().map(|p| p.payment_amount).sum::<Money>();
+        let actual_schedule = actual.schedule_from(&clock);
+        let without_schedule = without.schedule_from(&clock);
+        let payoff = |schedule: &crate::AmortizationSchedule| schedule.payments.last().map_or(last.payment_date, |p| p.payment_date);
+        Some(ExtraPaymentReport {
+            as_of: last.payment_date,
+            extra_principal: paid(&actual.historical_payments) - paid(&without.historical_payments),
+            interest_saved_to_date: interest(&without.historical_payments) - interest(&actual.historical_payments),
+            payoff_date: payoff(&actual_schedule),
+            payoff_date_without_extra: payoff(&without_schedule),
+            interest_saved_over_loan: without_schedule.total_interest_paid - actual_schedule.total_interest_paid,
+        })
+    }
+
+    // reads statement lines into the history: a header row with date (YYYY-MM-DD) and amount
+    // columns, plus principal and interest if the statements split the payment
+    pub fn load_history_csv(&mut self, filepath: &str) -> Result<(), HistoryError> {
+        let file = File::open(filepath).map_err(csv::Error::from)?;
+        self.read_history(file)
+    }
+
+    pub fn read_history<R: io::Read>(&mut self, source: R) -> Result<(), HistoryError> {
+        // a file is recorded whole or not at all: every line is checked against the ones
+        // before it first, and the problems come back together
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(source);
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_lowercase()).collect();
+        let column = |name: &str| headers.iter().position(|h| h == name);
+        let date = column("date").ok_or(HistoryError::NoColumn("date"))?;
+        let amount = column("amount").ok_or(HistoryError::NoColumn("amount"))?;
+        let (principal, interest) = (column("principal"), column("interest"));
+
+        let mut records = Vec::new();
+        let mut problems = Vec::new();
+        let mut previous = self.historical_payments.last().map(|p| p.payment_date);
+        for row in reader.records() {
+            let row = row?;
+            let line = row.position().map_or(0, |p| p.line());
+            let field = |index: Option<usize>| index.and_then(|i| row.get(i)).filter(|text| !text.is_empty());
+            let money = |index: Option<usize>| field(index)
+                .map(|text| text.parse::<Money>().map_err(|_| StatementProblem::BadAmount(text.to_string())))
+                .transpose();
+            let checked = (|| {
+                let text = field(Some(date)).ok_or(StatementProblem::NoDate)?;
+                let day = NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| StatementProblem::BadDate(text.to_string()))?;
+                let date = day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+                if previous.is_some_and(|previous| previous >= date) {
+                    return Err(StatementProblem::NotAfter(day));
+                }
+                let amount = money(Some(amount))?.ok_or(StatementProblem::NoAmount)?;
+                // a split has to account for the whole amount, so one column gives the other
+                let (principal, interest) = match (money(principal)?, money(interest)?) {
+                    (Some(principal), Some(interest)) if principal + interest != amount =>
+                        return Err(StatementProblem::Split { principal, interest, amount }),
+                    (Some(principal), None) => (Some(principal), Some(amount - principal)),
+                    (None, Some(interest)) => (Some(amount - interest), Some(interest)),
+                    split => split,
+                };
+                Ok(Record { date, amount, principal, interest })
+            })();
+            match checked {
+                Ok(record) => {
+                    previous = Some(record.date);
+                    records.push(record);
+                }
+                Err(problem) => problems.push((line, problem)),
+            }
+        }
+        if !problems.is_empty() {
+            return Err(HistoryError::Statements(problems));
+        }
+
+        for record in records {
+            self.record(record)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn mortgage() -> Mortgage {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+    }
+
+    #[test]
+    fn imports_statements() {
+        let csv = "Date,Amount,Principal,Interest\n\
+            2023-09-01,2719.71,,\n\
+            2023-10-01,3719.71,,\n\
+            2023-11-01,2719.71,530.00,2189.71\n";
+        let mut mort = mortgage();
+        mort.read_history(csv.as_bytes()).unwrap();
+        let history = mort.history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].payment_number, 3);
+
+        // the first matches the formula, the second has $1,000 of extra principal in it
+        let formula = mortgage().schedule_from(&Clock::starting_at(mort.origin_date())).payments;
+        assert_eq!(history[0].remaining_principal, formula[0].remaining_principal);
+        assert_eq!(history[1].principal_payment, formula[1].principal_payment + Money::dollars(1000.0));
+        assert_eq!(history[2].principal_payment, Money::dollars(530.0));
+        assert_eq!(history[2].remaining_principal, history[1].remaining_principal - Money::dollars(530.0));
+
+        // the projection carries on from the true balance at the same payment
+        let schedule = mort.schedule_from(&Clock::starting_at(mort.origin_date()));
+        assert_eq!(schedule.payments[3].payment_date, Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(schedule.payments[3].payment_amount, mortgage().monthly_payment());
+        assert!(schedule.payments.len() < 360);
+        assert!(schedule.payments.last().unwrap().remaining_principal.is_zero());
+    }
+
+    #[test]
+    fn reports_bad_rows_and_order() {
+        let mut mort = mortgage();
+        let err = mort.read_history("date,amount\n2023-09-01,abc\n09/01/2023,100\n2023-11-01,\n".as_bytes()).unwrap_err();
+        match err {
+            HistoryError::Statements(problems) => assert_eq!(problems, vec![
+                (2, StatementProblem::BadAmount("abc".to_string())),
+                (3, StatementProblem::BadDate("09/01/2023".to_string())),
+                (4, StatementProblem::NoAmount),
+            ]),
+            other => panic!("expected bad rows, got {}", other),
+        }
+        assert!(mort.history().is_empty());
+        assert!(matches!(mort.read_history("when,amount\n".as_bytes()), Err(HistoryError::NoColumn("date"))));
+
+        // a split that doesn't make up the amount, and rows out of order, are caught before
+        // anything is recorded
+        let csv = "date,amount,principal,interest\n\
+            2023-09-01,2719.71,600.00,2000.00\n\
+            2023-11-01,2719.71,,\n\
+            2023-10-01,2719.71,,\n";
+        match mort.read_history(csv.as_bytes()).unwrap_err() {
+            HistoryError::Statements(problems) => {
+                assert_eq!(problems.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![2, 4]);
+                assert!(matches!(problems[0].1, StatementProblem::Split { .. }));
+                assert_eq!(problems[1].1, StatementProblem::NotAfter(NaiveDate::from_ymd_opt(2023, 10, 1).unwrap()));
+            }
+            other => panic!("expected bad rows, got {}", other),
+        }
+        assert!(mort.history().is_empty());
+
+        let date = Utc.with_ymd_and_hms(2023, 9, 1, 0, 0, 0).unwrap();
+        mort.record_payment(date, Money::dollars(2719.71)).unwrap();
+        assert!(matches!(mort.record_payment(date, Money::dollars(2719.71)), Err(HistoryError::OutOfOrder(_))));
+        assert!(matches!(mort.read_history("date,amount\n2023-09-01,2719.71\n".as_bytes()), Err(HistoryError::Statements(_))));
+        assert_eq!(mort.history().len(), 1);
+    }
+
+    #[test]
+    fn one_split_column_gives_the_other() {
+        let csv = "date,amount,principal,interest\n\
+            2023-09-01,2719.71,530.00,\n\
+            2023-10-01,2719.71,,2190.00\n";
+        let mut mort = mortgage();
+        mort.read_history(csv.as_bytes()).unwrap();
+        let history = mort.history();
+        assert_eq!(history[0].interest_payment, Money::dollars(2189.71));
+        assert_eq!(history[1].principal_payment, Money::dollars(529.71));
+        let schedule = mort.schedule_from(&Clock::starting_at(mort.origin_date()));
+        assert!(schedule.verify().is_ok());
+    }
+
+    #[test]
+    fn reports_what_past_extra_saved() {
+        let csv = "date,amount\n\
+            2023-09-01,2719.71\n\
+            2023-10-01,3719.71\n\
+            2023-11-01,2719.71\n\
+            2023-12-01,12719.71\n\
+            2024-01-01,2719.71\n";
+        let mut mort = mortgage().with_additional_payment(Money::dollars(300.0));
+        mort.read_history(csv.as_bytes()).unwrap();
+        let report = mort.past_extra_savings().unwrap();
+        assert_eq!(report.as_of, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+
+        // the $11,000 extra, and a month's interest on the first $1,000 and two on the rest by now
+        assert_eq!(report.extra_principal, Money::dollars(11000.0));
+        let saved = report.interest_saved_to_date;
+        let without = mortgage().schedule_from(&Clock::starting_at(mort.origin_date()));
+        assert_eq!(without.payments[4].remaining_principal - mort.remaining_principal(), report.extra_principal + saved);
+        assert!(saved > Money::dollars(11000.0 * 0.055 / 12.0) && saved < Money::dollars(11000.0 * 0.055 / 12.0 * 3.0));
+        // the additional payment going forward doesn't count
+        assert!(report.months_saved() > 12 && report.months_saved() < 30);
+        assert!(report.interest_saved_over_loan > Money::dollars(11000.0));
+        assert!(report.to_string().contains("months sooner"));
+
+        // the formula alone saves nothing
+        let mut on_schedule = mortgage();
+        on_schedule.generate_history(&Clock::starting_at(Utc.with_ymd_and_hms(2024, 6, 15, 0, 0, 0).unwrap()));
+        let report = on_schedule.past_extra_savings().unwrap();
+        assert!(report.extra_principal.is_zero() && report.interest_saved_over_loan.is_zero());
+        assert_eq!(report.months_saved(), 0);
+        assert!(mortgage().past_extra_savings().is_none());
+    }
+}
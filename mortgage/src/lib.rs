@@ -0,0 +1,2183 @@
+// amortization math for a fixed or adjustable rate loan, shared by the mortgage program, the
+// toolbox cli, the webserver, the simulation and the finance tui. Mortgage::new (or the
+// checked Mortgage::builder) sets up a loan and the with_* methods add escrow, pmi, extra
+// payments, lump sums, recasts and the rest. payments_from streams the payments one at a
+// time and schedule_from collects them into an AmortizationSchedule with its totals; both
+// take a Clock, with payments() and generate_amortization_schedule() using the system's.
+// the modules build on that: scenario compares what-ifs, config runs them from a planning
+// file, refinance and apr cost out new loans, term sets shorter terms against longer ones,
+// history imports real payments, heloc adds a line of credit, allocation splits extra money
+// across loans, and diff, real, tax and verify look at a finished schedule
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use chrono::{Duration, Months, Utc, prelude::*};
+use money::{Currency, FormatOptions, Money, Rounding};
+use serde::{Deserialize, Serialize};
+use bankaccounts::inflation::Inflation;
+use simclock::Clock;
+use tax::TaxProfile;
+
+pub mod allocation;
+pub mod apr;
+pub mod builder;
+pub mod config;
+pub mod diff;
+pub mod heloc;
+pub mod history;
+pub mod real;
+pub mod refinance;
+pub mod tax;
+pub mod term;
+pub mod scenario;
+pub mod verify;
+
+// in a json loan file only the first four fields are required
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mortgage {
+    origin_date: chrono::DateTime<Utc>,
+    principal: Money,
+    annual_rate: f64,
+    term_years: u32,
+    #[serde(default)]
+    additional_payment: Money,
+    #[serde(default)]
+    historical_payments: Vec<Payment>,
+    #[serde(default)]
+    arm: Option<ArmTerms>,
+    // taxes and insurance are yearly bills paid out of escrow; hoa dues are monthly
+    #[serde(default)]
+    property_tax: Money,
+    #[serde(default)]
+    insurance: Money,
+    #[serde(default)]
+    hoa: Money,
+    // private mortgage insurance, a yearly percent of the original loan charged monthly
+    // until the balance falls to PMI_REMOVAL_LTV of the home's value at origination
+    #[serde(default)]
+    home_value: Money,
+    #[serde(default)]
+    pmi_rate: f64,
+    // how much the home's value grows each year, in percent, for tracking equity
+    #[serde(default)]
+    appreciation_rate: f64,
+    #[serde(default)]
+    frequency: PaymentFrequency,
+    // the lender program that drafts half the monthly payment every two weeks but applies it
+    // monthly. 26 drafts don't fit in 12 months two at a time, so a month with a third draft
+    // pays that half as extra principal, two extra halves a year
+    #[serde(default)]
+    half_payment_drafts: bool,
+    // when the first payment is due, for a loan that closed partway into a month. interest
+    // from closing to a period before it is prepaid at closing, and payments step on from it.
+    // without one the first payment is on the origin date
+    #[serde(default)]
+    first_payment_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    day_count: DayCount,
+    // one-off payments toward principal, each made with the first payment on or after its date
+    #[serde(default)]
+    lump_sums: Vec<(DateTime<Utc>, Money)>,
+    // lump sums that re-amortize the loan, lowering the payment instead of shortening the term
+    #[serde(default)]
+    recasts: Vec<(DateTime<Utc>, Money)>,
+    // months at the start that only pay interest; the rest of the term then amortizes the loan
+    #[serde(default)]
+    interest_only_months: u32,
+    // the month whatever is still owed comes due in one final payment
+    #[serde(default)]
+    balloon_month: Option<u32>,
+    // payments that weren't made in full, each applied to the first payment on or after its date.
+    // the unpaid interest is added to the balance and the late fee policy charges for the rest
+    #[serde(default)]
+    shortfalls: Vec<(DateTime<Utc>, Shortfall)>,
+    #[serde(default)]
+    late_fees: Option<LateFeePolicy>,
+    // payments paused by agreement: nothing is due, interest is added to the balance, and the
+    // first payment afterwards re-amortizes it over the rest of the term
+    #[serde(default)]
+    forbearance: Vec<DateRange>,
+    // for estimating what deducting the interest saves
+    #[serde(default)]
+    tax_profile: Option<TaxProfile>,
+    // yearly, in percent, for showing amounts in today's dollars
+    #[serde(default)]
+    inflation_rate: Option<f64>,
+    // extra principal paid with every payment dated within a range, on top of
+    // additional_payment. amounts from overlapping ranges add up
+    #[serde(default)]
+    extra_payments: Vec<(DateRange, Money)>,
+    // paid at closing: each discount point is a percent of the principal, bought to lower
+    // the rate. all of these count as finance charges in the APR
+    #[serde(default)]
+    discount_points: f64,
+    #[serde(default)]
+    origination_fee: Money,
+    #[serde(default)]
+    closing_costs: Money,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Shortfall {
+    Missed,
+    // this much was paid instead of what was due
+    Partial(Money),
+}
+
+// charged on a missed or partial payment: a flat fee plus a percent of what went unpaid
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LateFeePolicy {
+    pub flat: Money,
+    pub percent: f64,
+}
+
+impl LateFeePolicy {
+    pub fn fee(&self, unpaid: Money) -> Money {
+        self.flat + unpaid.mul_rate(self.percent / 100.0, CHARGE_ROUNDING)
+    }
+}
+
+// the dates from start up to but not including end. without an end it runs for the rest of the loan
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        DateRange { start, end: Some(end) }
+    }
+
+    pub fn starting(start: DateTime<Utc>) -> Self {
+        DateRange { start, end: None }
+    }
+
+    // the whole calendar year
+    pub fn year(year: i32) -> Self {
+        let new_year = |year| Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+        DateRange::new(new_year(year), new_year(year + 1))
+    }
+
+    pub fn contains(&self, date: DateTime<Utc>) -> bool {
+        date >= self.start && self.end.is_none_or(|end| date < end)
+    }
+}
+
+// how often payments are made. bi-weekly and weekly pay half or a quarter of the monthly
+// payment, which over 26 or 52 payments a year adds up to a thirteenth monthly payment
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentFrequency {
+    #[default]
+    Monthly,
+    BiWeekly,
+    Weekly,
+}
+
+impl PaymentFrequency {
+    pub fn per_year(&self) -> u32 {
+        match self {
+            PaymentFrequency::Monthly => 12,
+            PaymentFrequency::BiWeekly => 26,
+            PaymentFrequency::Weekly => 52,
+        }
+    }
+
+    // how many of these payments make up one monthly payment
+    fn per_month(&self) -> u32 {
+        match self {
+            PaymentFrequency::Monthly => 1,
+            PaymentFrequency::BiWeekly => 2,
+            PaymentFrequency::Weekly => 4,
+        }
+    }
+
+    fn next_date(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            PaymentFrequency::Monthly => date.checked_add_months(Months::new(1)).unwrap(),
+            PaymentFrequency::BiWeekly => date + Duration::weeks(2),
+            PaymentFrequency::Weekly => date + Duration::weeks(1),
+        }
+    }
+
+    fn previous_date(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            PaymentFrequency::Monthly => date.checked_sub_months(Months::new(1)).unwrap(),
+            PaymentFrequency::BiWeekly => date - Duration::weeks(2),
+            PaymentFrequency::Weekly => date - Duration::weeks(1),
+        }
+    }
+}
+
+// how a payment period's interest is worked out. 30/360 charges every period the same share of
+// the year, the way the level payment is figured. the actual conventions count the days since
+// the previous payment, over 365 or over the days in each year the period falls in, so a
+// 31-day month costs more than February and the final payment takes up the difference
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayCount {
+    #[default]
+    Thirty360,
+    Actual365,
+    ActualActual,
+}
+
+impl DayCount {
+    // the share of a year that interest accrues for between two payments
+    pub fn year_fraction(&self, start: DateTime<Utc>, end: DateTime<Utc>, per_year: u32) -> f64 {
+        match self {
+            DayCount::Thirty360 => 1.0 / per_year as f64,
+            DayCount::Actual365 => (end - start).num_days() as f64 / 365.0,
+            DayCount::ActualActual => {
+                // a period across new year is split, so days in a leap year count as 1/366
+                let mut fraction = 0.0;
+                let mut from = start;
+                while from < end {
+                    let to = Utc.with_ymd_and_hms(from.year() + 1, 1, 1, 0, 0, 0).unwrap().min(end);
+                    let days_in_year = if NaiveDate::from_ymd_opt(from.year(), 2, 29).is_some() { 366.0 } else { 365.0 };
+                    fraction += (to - from).num_days() as f64 / days_in_year;
+                    from = to;
+                }
+                fraction
+            }
+        }
+    }
+
+    // the share of a year between two dates that aren't a payment period apart, like closing
+    // and the start of the first period. 30/360 counts every month as 30 days
+    fn stub_fraction(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+        match self {
+            DayCount::Thirty360 => {
+                let day = |date: DateTime<Utc>| date.day().min(30) as i32;
+                let days = 360 * (end.year() - start.year()) + 30 * (end.month() as i32 - start.month() as i32) + day(end) - day(start);
+                days as f64 / 360.0
+            }
+            _ => self.year_fraction(start, end, 12),
+        }
+    }
+}
+
+pub const PMI_REMOVAL_LTV: f64 = 0.78;
+// the share of the home's value owned outright that's usually enough to drop pmi or refinance
+// without it
+pub const EQUITY_MILESTONE: f64 = 0.20;
+// a payment-capped arm can only add so much unpaid interest to the balance: past this share of
+// the original principal the payment is re-amortized without the cap
+pub const NEGATIVE_AMORTIZATION_LIMIT: f64 = 1.10;
+
+// every amount in a payment is settled to the cent as it's worked out, so balances never carry
+// fractions of a cent and the principal paid always adds up to the loan exactly.
+// interest rounds half-even so hundreds of roundings don't lean one way
+pub const INTEREST_ROUNDING: Rounding = Rounding::HalfEven;
+// a level payment rounds half-up; the final payment of the term takes up the difference
+pub const PAYMENT_ROUNDING: Rounding = Rounding::HalfUp;
+// a monthly payment split into bi-weekly or weekly ones rounds up, so the split never pays less
+pub const SPLIT_ROUNDING: Rounding = Rounding::Up;
+// pmi and hoa charges at the payment frequency round half-up
+pub const CHARGE_ROUNDING: Rounding = Rounding::HalfUp;
+// so do appreciated home values
+pub const VALUE_ROUNDING: Rounding = Rounding::HalfUp;
+
+// how an adjustable-rate mortgage moves. annual_rate on the Mortgage is the initial rate,
+// held for fixed_months; after that the rate resets every adjustment_months to the index
+// plus the margin, limited by the caps. rates are percents, like annual_rate
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArmTerms {
+    pub fixed_months: u32,
+    pub adjustment_months: u32,
+    pub margin: f64,
+    // the most the rate can move at one reset
+    pub periodic_cap: f64,
+    // the most the rate can ever be above the initial rate
+    pub lifetime_cap: f64,
+    // the index at each reset in turn; the last value holds for any resets after it
+    pub index: Vec<f64>,
+    // the most the payment can move at a reset, in percent of the payment before it. a capped
+    // payment can fall short of the interest, and what it doesn't cover is added to the balance
+    #[serde(default)]
+    pub payment_cap: Option<f64>,
+}
+
+impl ArmTerms {
+    fn is_reset(&self, payment_number: u32) -> bool {
+        payment_number > self.fixed_months && (payment_number - self.fixed_months - 1).is_multiple_of(self.adjustment_months)
+    }
+
+    fn resets_through(&self, payment_number: u32) -> u32 {
+        if payment_number <= self.fixed_months {
+            0
+        } else {
+            (payment_number - self.fixed_months - 1) / self.adjustment_months + 1
+        }
+    }
+
+    fn cap_payment(&self, previous: Money, payment: Money) -> Money {
+        match self.payment_cap {
+            Some(cap) => payment
+                .max(previous.mul_rate(1.0 - cap / 100.0, PAYMENT_ROUNDING))
+                .min(previous.mul_rate(1.0 + cap / 100.0, PAYMENT_ROUNDING)),
+            None => payment,
+        }
+    }
+
+    fn rate_at(&self, initial_rate: f64, payment_number: u32) -> f64 {
+        // the rate never goes below the margin or above the lifetime cap
+        let ceiling = initial_rate + self.lifetime_cap;
+        let floor = self.margin.min(ceiling);
+        (0..self.resets_through(payment_number) as usize).fold(initial_rate, |rate, reset| {
+            let index = self.index.get(reset).or(self.index.last()).copied().unwrap_or(0.0);
+            (index + self.margin)
+                .clamp(rate - self.periodic_cap, rate + self.periodic_cap)
+                .clamp(floor, ceiling)
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub payment_date: chrono::DateTime<Utc>,
+    pub payment_number: u32,
+    pub payment_amount: Money,
+    pub principal_payment: Money,
+    pub interest_payment: Money,
+    pub remaining_principal: Money,
+    // the annual rate this payment's interest was charged at
+    pub rate: f64,
+    // an adjustable-rate mortgage reset its rate and payment this month
+    pub rate_reset: bool,
+    // this payment's share of the yearly property tax and insurance, collected into escrow
+    pub tax_payment: Money,
+    pub insurance_payment: Money,
+    pub hoa_payment: Money,
+    pub pmi_payment: Money,
+    // the part of payment_amount that came from lump sums
+    pub lump_sum_payment: Money,
+    // the part that came from a recast; the payments after it are re-amortized
+    pub recast_payment: Money,
+    #[serde(default)]
+    pub interest_only: bool,
+    // the final payment of a balloon loan, paying off everything still owed
+    #[serde(default)]
+    pub balloon: bool,
+    // what the home is worth on the payment date
+    #[serde(default)]
+    pub home_value: Money,
+    // how much less than the scheduled payment was paid, and the fee charged for it
+    #[serde(default)]
+    pub shortfall: Money,
+    #[serde(default)]
+    pub late_fee: Money,
+    // paused by agreement, with the interest added to the balance
+    #[serde(default)]
+    pub forbearance: bool,
+    // the payment due didn't cover the interest, so the balance went up
+    #[serde(default)]
+    pub negative_amortization: bool,
+    // the balance passed the negative amortization limit, so the payment was re-amortized
+    // without the cap
+    #[serde(default)]
+    pub payment_recast: bool,
+}
+
+impl Payment {
+    pub fn escrow_payment(&self) -> Money {
+        self.tax_payment + self.insurance_payment
+    }
+
+    // principal, interest, taxes and insurance
+    pub fn piti(&self) -> Money {
+        self.payment_amount + self.escrow_payment()
+    }
+
+    // everything due for the month, pmi, hoa dues and late fees included
+    pub fn total_due(&self) -> Money {
+        self.piti() + self.pmi_payment + self.hoa_payment + self.late_fee
+    }
+
+    // the part of the home owned outright after this payment
+    pub fn equity(&self) -> Money {
+        self.home_value - self.remaining_principal
+    }
+
+    // loan to value, what's still owed as a share of what the home is worth
+    pub fn ltv(&self) -> f64 {
+        self.remaining_principal.ratio(self.home_value)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AmortizationSchedule {
+    pub payments: Vec<Payment>,
+    pub total_interest_paid: Money,
+    pub total_paid: Money,
+    pub total_escrow_paid: Money,
+    pub total_hoa_paid: Money,
+    pub total_piti_paid: Money,
+    pub total_pmi_paid: Money,
+    // the first payment without pmi, if it was ever charged and then dropped
+    pub pmi_removed_at: Option<u32>,
+    pub frequency: PaymentFrequency,
+    pub total_lump_sums: Money,
+    // interest saved against the same loan without its lump sums
+    pub lump_sum_interest_saved: Money,
+    #[serde(default)]
+    pub total_late_fees: Money,
+    #[serde(default)]
+    pub tax_profile: Option<TaxProfile>,
+    // paid at closing for a first period that started partway into a month
+    #[serde(default)]
+    pub prepaid_interest: Money,
+    // as of when the schedule was worked out
+    #[serde(default)]
+    pub inflation: Option<Inflation>,
+    // a payment-capped ARM owed more at some point than before the payment
+    #[serde(default)]
+    pub negative_amortization: bool,
+}
+
+// the extra payment that pays a loan off by a target date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PayoffPlan {
+    pub extra_payment: Money,
+    pub payoff_date: DateTime<Utc>,
+    pub interest_saved: Money,
+}
+
+impl fmt::Display for PayoffPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pay {} extra each payment to pay off by {}, saving {} in interest",
+            self.extra_payment, self.payoff_date.format("%Y-%b-%d"), self.interest_saved)
+    }
+}
+
+impl Mortgage {
+    pub fn new(origin_date: chrono::DateTime<Utc>, principal: Money, annual_rate: f64, term_years: u32) -> Self {
+        Mortgage {
+            origin_date,
+            principal,
+            annual_rate,
+            term_years,
+            additional_payment: Money::zero(principal.currency()),
+            historical_payments: Vec::new(),
+            arm: None,
+            property_tax: Money::zero(principal.currency()),
+            insurance: Money::zero(principal.currency()),
+            hoa: Money::zero(principal.currency()),
+            home_value: principal,
+            pmi_rate: 0.0,
+            appreciation_rate: 0.0,
+            frequency: PaymentFrequency::Monthly,
+            half_payment_drafts: false,
+            first_payment_date: None,
+            day_count: DayCount::Thirty360,
+            lump_sums: Vec::new(),
+            recasts: Vec::new(),
+            interest_only_months: 0,
+            balloon_month: None,
+            extra_payments: Vec::new(),
+            discount_points: 0.0,
+            origination_fee: Money::zero(principal.currency()),
+            closing_costs: Money::zero(principal.currency()),
+            shortfalls: Vec::new(),
+            late_fees: None,
+            forbearance: Vec::new(),
+            tax_profile: None,
+            inflation_rate: None,
+        }
+    }
+
+    pub fn with_frequency(mut self, frequency: PaymentFrequency) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    // for a monthly loan; drafts start two weeks before the first payment, so it has two
+    pub fn with_half_payment_drafts(mut self) -> Self {
+        self.half_payment_drafts = true;
+        self
+    }
+
+    fn drafted_extra(&self, previous_date: Option<DateTime<Utc>>, payment_date: DateTime<Utc>, regular: Money) -> Money {
+        // a half for every draft past two since the previous payment
+        if !self.half_payment_drafts || self.frequency != PaymentFrequency::Monthly {
+            return Money::zero(self.principal.currency());
+        }
+        let first_draft = self.first_payment_date() - Duration::weeks(2);
+        let drafts_through = |date: DateTime<Utc>| if date < first_draft { 0 } else { (date - first_draft).num_weeks() / 2 + 1 };
+        let previous = previous_date.unwrap_or_else(|| self.frequency.previous_date(payment_date));
+        let drafts = drafts_through(payment_date) - drafts_through(previous);
+        regular.mul_rate((drafts - 2).max(0) as f64 / 2.0, SPLIT_ROUNDING)
+    }
+
+    // e.g. closing on August 15th with the first payment on October 1st
+    pub fn with_first_payment(mut self, date: DateTime<Utc>) -> Self {
+        self.first_payment_date = Some(date);
+        self
+    }
+
+    pub fn first_payment_date(&self) -> DateTime<Utc> {
+        self.first_payment_date.unwrap_or(self.origin_date)
+    }
+
+    // interest for the stub from closing to a period before the first payment, since that
+    // payment only covers the period just before it. zero if there's no stub
+    pub fn prepaid_interest(&self) -> Money {
+        let stub_end = self.first_payment_date.map(|first| self.frequency.previous_date(first));
+        match stub_end {
+            Some(end) if end > self.origin_date =>
+                periodic_interest(self.principal, self.rate_at(1), self.day_count.stub_fraction(self.origin_date, end)),
+            _ => Money::zero(self.principal.currency()),
+        }
+    }
+
+    pub fn with_day_count(mut self, day_count: DayCount) -> Self {
+        self.day_count = day_count;
+        self
+    }
+
+    // added to every payment, at whatever frequency
+    pub fn with_additional_payment(mut self, additional_payment: Money) -> Self {
+        self.additional_payment = additional_payment;
+        self
+    }
+
+    // e.g. $200 a payment through 2024 and $500 from 2026 on is two calls. can be called for
+    // as many ranges as needed
+    pub fn with_extra_payment(mut self, range: DateRange, amount: Money) -> Self {
+        self.extra_payments.push((range, amount));
+        self
+    }
+
+    fn extra_due(&self, payment_date: DateTime<Utc>) -> Money {
+        self.extra_payments.iter()
+            .filter(|(range, _)| range.contains(payment_date))
+            .fold(Money::zero(self.principal.currency()), |total, (_, amount)| total + *amount)
+    }
+
+    // can be called as many times as there are lump sums
+    pub fn with_lump_sum(mut self, date: DateTime<Utc>, amount: Money) -> Self {
+        self.lump_sums.push((date, amount));
+        self
+    }
+
+    pub fn with_interest_only(mut self, months: u32) -> Self {
+        self.interest_only_months = months;
+        self
+    }
+
+    // nothing is paid with the payment due on date, and the interest it would have covered is
+    // added to the balance
+    pub fn with_missed_payment(mut self, date: DateTime<Utc>) -> Self {
+        self.shortfalls.push((date, Shortfall::Missed));
+        self
+    }
+
+    pub fn with_partial_payment(mut self, date: DateTime<Utc>, paid: Money) -> Self {
+        self.shortfalls.push((date, Shortfall::Partial(paid)));
+        self
+    }
+
+    pub fn with_late_fees(mut self, policy: LateFeePolicy) -> Self {
+        self.late_fees = Some(policy);
+        self
+    }
+
+    pub fn with_tax_profile(mut self, profile: TaxProfile) -> Self {
+        self.tax_profile = Some(profile);
+        self
+    }
+
+    // in percent. the schedule then also shows amounts in dollars as of the clock's time
+    pub fn with_inflation(mut self, annual_rate: f64) -> Self {
+        self.inflation_rate = Some(annual_rate);
+        self
+    }
+
+    pub fn with_forbearance(mut self, range: DateRange) -> Self {
+        self.forbearance.push(range);
+        self
+    }
+
+    fn shortfall_due(&self, after: Option<DateTime<Utc>>, through: DateTime<Utc>) -> Option<Shortfall> {
+        self.shortfalls.iter()
+            .find(|(date, _)| *date <= through && after.is_none_or(|after| *date > after))
+            .map(|(_, shortfall)| *shortfall)
+    }
+
+    fn in_forbearance(&self, payment_date: DateTime<Utc>) -> bool {
+        self.forbearance.iter().any(|range| range.contains(payment_date))
+    }
+
+    // payments are figured over the full term, but the balance is due at the end of `month`
+    pub fn with_balloon(mut self, month: u32) -> Self {
+        self.balloon_month = Some(month);
+        self
+    }
+
+    // pays lump_sum toward principal with the first payment on or after date, then spreads
+    // what's left over the rest of the term
+    pub fn recast(mut self, date: DateTime<Utc>, lump_sum: Money) -> Self {
+        self.recasts.push((date, lump_sum));
+        self
+    }
+
+    fn lump_sums_due(&self, after: Option<DateTime<Utc>>, through: DateTime<Utc>) -> Money {
+        due_between(&self.lump_sums, after, through, self.principal.currency())
+    }
+
+    fn recasts_due(&self, after: Option<DateTime<Utc>>, through: DateTime<Utc>) -> Money {
+        due_between(&self.recasts, after, through, self.principal.currency())
+    }
+
+    pub fn with_property_tax(mut self, annual_tax: Money) -> Self {
+        self.property_tax = annual_tax;
+        self
+    }
+
+    pub fn with_insurance(mut self, annual_premium: Money) -> Self {
+        self.insurance = annual_premium;
+        self
+    }
+
+    pub fn with_hoa(mut self, monthly_dues: Money) -> Self {
+        self.hoa = monthly_dues;
+        self
+    }
+
+    pub fn with_pmi(mut self, home_value: Money, pmi_rate: f64) -> Self {
+        self.home_value = home_value;
+        self.pmi_rate = pmi_rate;
+        self
+    }
+
+    pub fn with_home_value(mut self, home_value: Money) -> Self {
+        self.home_value = home_value;
+        self
+    }
+
+    pub fn with_appreciation(mut self, annual_rate: f64) -> Self {
+        self.appreciation_rate = annual_rate;
+        self
+    }
+
+    fn home_value_at(&self, payment_number: u32) -> Money {
+        // grown at the appreciation rate from origination. a loan file without a home value
+        // is taken to have borrowed all of it
+        let value = if self.home_value.is_zero() { self.principal } else { self.home_value };
+        let years = payment_number as f64 / self.frequency.per_year() as f64;
+        value.mul_rate((1.0 + self.appreciation_rate / 100.0).powf(years), VALUE_ROUNDING)
+    }
+
+    fn pmi_due(&self, balance: Money) -> Money {
+        // charged on any payment that starts above the threshold
+        if self.pmi_rate > 0.0 && balance.ratio(self.home_value) > PMI_REMOVAL_LTV {
+            self.principal.mul_rate(self.pmi_rate / 100.0 / self.frequency.per_year() as f64, CHARGE_ROUNDING)
+        } else {
+            Money::zero(self.principal.currency())
+        }
+    }
+
+    pub fn with_points(mut self, points: f64) -> Self {
+        self.discount_points = points;
+        self
+    }
+
+    pub fn with_origination_fee(mut self, fee: Money) -> Self {
+        self.origination_fee = fee;
+        self
+    }
+
+    // any other lender fees paid at closing
+    pub fn with_closing_costs(mut self, costs: Money) -> Self {
+        self.closing_costs = costs;
+        self
+    }
+
+    pub fn with_arm(mut self, arm: ArmTerms) -> Self {
+        self.arm = Some(arm);
+        self
+    }
+
+    // refinancing is always into a fixed rate
+    pub fn refinance(mut self, new_rate: f64) -> Self {
+        self.annual_rate = new_rate;
+        self.arm = None;
+        self
+    }
+
+    // which month of the loan a payment falls in, counting from 1. ARM terms are in months
+    fn month_of(&self, payment_number: u32) -> u32 {
+        (payment_number - 1) * 12 / self.frequency.per_year() + 1
+    }
+
+    pub fn rate_at(&self, payment_number: u32) -> f64 {
+        match &self.arm {
+            Some(arm) => arm.rate_at(self.annual_rate, self.month_of(payment_number)),
+            None => self.annual_rate,
+        }
+    }
+
+    fn starts_month(&self, payment_number: u32) -> bool {
+        payment_number == 1 || self.month_of(payment_number - 1) != self.month_of(payment_number)
+    }
+
+    fn is_reset(&self, payment_number: u32) -> bool {
+        // the first payment in a reset month
+        self.starts_month(payment_number) && self.arm.as_ref().is_some_and(|arm| arm.is_reset(self.month_of(payment_number)))
+    }
+
+    fn is_interest_only(&self, payment_number: u32) -> bool {
+        self.month_of(payment_number) <= self.interest_only_months
+    }
+
+    fn ends_interest_only(&self, payment_number: u32) -> bool {
+        // the first payment with principal in it
+        self.interest_only_months > 0 && self.starts_month(payment_number)
+            && self.month_of(payment_number) == self.interest_only_months + 1
+    }
+
+    fn total_payments(&self) -> u32 {
+        self.term_years * self.frequency.per_year()
+    }
+
+    // the initial monthly payment; an ARM's changes at each reset
+    pub fn monthly_payment(&self) -> Money {
+        level_payment(self.principal, self.annual_rate, self.term_years * 12)
+    }
+
+    // the initial payment at the mortgage's frequency
+    pub fn periodic_payment(&self) -> Money {
+        self.split_monthly(self.monthly_payment())
+    }
+
+    fn split_monthly(&self, monthly: Money) -> Money {
+        monthly.mul_rate(1.0 / self.frequency.per_month() as f64, SPLIT_ROUNDING)
+    }
+
+    fn reamortized_payment(&self, balance: Money, rate: f64, payment_number: u32) -> Money {
+        // the payment that clears balance over the months left in the term
+        let months_left = (self.term_years * 12 + 1).saturating_sub(self.month_of(payment_number)).max(1);
+        self.split_monthly(level_payment(balance, rate, months_left))
+    }
+
+    pub fn from_json(filepath: &str) -> serde_json::Result<Mortgage> {
+        let json_data = fs::read_to_string(filepath)
+            .map_err(serde_json::Error::io)?;
+        serde_json::from_str(&json_data)
+    }
+
+    pub fn save_to_json(&self, filepath: &str) -> serde_json::Result<()> {
+        let json_data = serde_json::to_string_pretty(&self)?;
+        let mut file = File::create(filepath)
+            .map_err(serde_json::Error::io)?;
+
+        file.write_all(json_data.as_bytes())
+            .map_err(serde_json::Error::io)?;
+
+        Ok(())
+    }
+
+    pub fn origin_date(&self) -> DateTime<Utc> {
+        self.origin_date
+    }
+
+    pub fn history(&self) -> &[Payment] {
+        &self.historical_payments
+    }
+
+    pub fn remaining_principal(&self) -> Money {
+        self.historical_payments.last().map_or(self.principal, |p| p.remaining_principal)
+    }
+
+    fn scheduled_payment(&self) -> Money {
+        self.scheduled_through(&self.historical_payments)
+    }
+
+    fn scheduled_through(&self, history: &[Payment]) -> Money {
+        // the principal and interest due going forward: set by the most recent reset, recast or
+        // end of interest-only payments or forbearance in the history, or the initial payment if
+        // there hasn't been one. a capped reset depends on the payment before it
+        let ends_forbearance = |i: usize| i > 0 && history[i - 1].forbearance && !history[i].forbearance;
+        match history.iter().enumerate().rev()
+            .find(|&(i, p)| p.rate_reset || p.recast_payment.is_positive() || self.ends_interest_only(p.payment_number)
+                || ends_forbearance(i) || p.payment_recast) {
+            Some((i, reset)) => {
+                let payment = self.reamortized_payment(reset.remaining_principal + reset.principal_payment - reset.recast_payment,
+                    reset.rate, reset.payment_number);
+                let capped = reset.rate_reset && reset.recast_payment.is_zero() && !self.ends_interest_only(reset.payment_number)
+                    && !ends_forbearance(i) && !reset.payment_recast;
+                match &self.arm {
+                    Some(arm) if capped && arm.payment_cap.is_some() => arm.cap_payment(self.scheduled_through(&history[..i]), payment),
+                    _ => payment,
+                }
+            }
+            None => self.periodic_payment(),
+        }
+    }
+
+    pub(crate) fn accrual(&self, payment_date: DateTime<Utc>, previous_date: Option<DateTime<Utc>>) -> f64 {
+        // interest runs from the previous payment. the first runs for a period, or from the
+        // origin date if the loan closed partway into it
+        let start = previous_date.unwrap_or_else(|| {
+            let period = self.frequency.previous_date(payment_date);
+            if self.origin_date < payment_date { period.max(self.origin_date) } else { period }
+        });
+        self.day_count.year_fraction(start, payment_date, self.frequency.per_year())
+    }
+
+    fn payment_on(&self, payment_number: u32, payment_date: DateTime<Utc>, previous_date: Option<DateTime<Utc>>,
+        balance: Money, scheduled: &mut Money, extra: Money) -> Payment {
+        // one payment on the given balance, with the extra for its date and any lump sums dated
+        // since the previous one. a reset, a recast or the end of interest-only payments or
+        // forbearance re-amortizes what's left over the rest of the term
+        let rate = self.rate_at(payment_number);
+        let rate_reset = self.is_reset(payment_number);
+        let recast = self.recasts_due(previous_date, payment_date).min(balance);
+        let forbearance = self.in_forbearance(payment_date);
+        let ends_forbearance = !forbearance && previous_date.is_some_and(|date| self.in_forbearance(date));
+        let ends_interest_only = self.ends_interest_only(payment_number);
+        if rate_reset || recast.is_positive() || ends_interest_only || ends_forbearance {
+            let payment = self.reamortized_payment(balance - recast, rate, payment_number);
+            // only a reset on its own is held to the payment cap
+            *scheduled = match &self.arm {
+                Some(arm) if rate_reset && !recast.is_positive() && !ends_interest_only && !ends_forbearance =>
+                    arm.cap_payment(*scheduled, payment),
+                _ => payment,
+            };
+        }
+
+        let per_year = self.frequency.per_year();
+        let interest_payment = periodic_interest(balance, rate, self.accrual(payment_date, previous_date));
+        let interest_only = self.is_interest_only(payment_number);
+        let payment_recast = !interest_only && !forbearance && *scheduled < interest_payment && self.past_amortization_limit(balance);
+        if payment_recast {
+            *scheduled = self.reamortized_payment(balance, rate, payment_number);
+        }
+        let regular = if interest_only { interest_payment } else { *scheduled };
+        let lump_sum = self.lump_sums_due(previous_date, payment_date);
+        let extra = extra + self.extra_due(payment_date) + self.drafted_extra(previous_date, payment_date, regular);
+        let mut payment_amount = regular + extra + lump_sum + recast;
+
+        // the last payment of the term, or a balloon, clears whatever is still owed
+        let balloon = self.balloon_month.is_some_and(|month| self.month_of(payment_number) >= month);
+        let last = payment_number >= self.total_payments() || balloon;
+        let balloon = balloon && payment_amount < balance + interest_payment;
+        if payment_amount > balance + interest_payment || last {
+            payment_amount = balance + interest_payment;
+        }
+
+        let negative_amortization = !forbearance && payment_amount < interest_payment;
+
+        // what was actually paid. anything short of the interest is added to the balance
+        let zero = Money::zero(self.principal.currency());
+        let paid = match self.shortfall_due(previous_date, payment_date) {
+            _ if forbearance => zero,
+            Some(Shortfall::Missed) => zero,
+            Some(Shortfall::Partial(paid)) => paid.min(payment_amount),
+            None => payment_amount,
+        };
+        let shortfall = if forbearance { zero } else { payment_amount - paid };
+        let late_fee = match self.late_fees {
+            Some(policy) if shortfall.is_positive() => policy.fee(shortfall),
+            _ => zero,
+        };
+        let payment_amount = paid;
+        let principal_payment = payment_amount - interest_payment;
+        let applied = principal_payment.max(zero);
+
+        Payment {
+            payment_date,
+            payment_number,
+            payment_amount,
+            principal_payment,
+            interest_payment,
+            remaining_principal: balance - principal_payment,
+            rate,
+            rate_reset,
+            tax_payment: yearly_share(self.property_tax, payment_number, per_year),
+            insurance_payment: yearly_share(self.insurance, payment_number, per_year),
+            hoa_payment: self.hoa.mul_rate(12.0 / per_year as f64, CHARGE_ROUNDING),
+            pmi_payment: self.pmi_due(balance),
+            lump_sum_payment: lump_sum.min(applied - recast.min(applied)),
+            recast_payment: recast.min(applied),
+            interest_only,
+            balloon,
+            home_value: self.home_value_at(payment_number),
+            shortfall,
+            late_fee,
+            forbearance,
+            negative_amortization,
+            payment_recast,
+        }
+    }
+
+    fn past_amortization_limit(&self, balance: Money) -> bool {
+        self.arm.as_ref().is_some_and(|arm| arm.payment_cap.is_some())
+            && balance > self.principal.mul_rate(NEGATIVE_AMORTIZATION_LIMIT, PAYMENT_ROUNDING)
+    }
+
+    pub fn generate_history(&mut self, clock: &Clock) {
+        // record every payment due before the clock's time. picks up after the last recorded
+        // payment, so stepping a clock forward and calling this again only adds the new ones
+        let start = match self.historical_payments.last() {
+            Some(last) => self.frequency.next_date(last.payment_date),
+            None => self.first_payment_date(),
+        };
+        let no_extra = Money::zero(self.principal.currency());
+        let new: Vec<Payment> = PaymentIterator::projecting(self, start, no_extra)
+            .take_while(|p| p.payment_date < clock.now())
+            .collect();
+        self.historical_payments.extend(new);
+    }
+
+    // the first payment due on or after as_of, with its principal, interest and escrow split,
+    // e.g. for showing what's due next. the payments up to it follow the loan's own calendar
+    // from origination, the same as generate_history, but nothing after it is worked out.
+    // None once the loan is paid off
+    pub fn next_payment(&self, as_of: DateTime<Utc>) -> Option<Payment> {
+        let mut loan = self.clone();
+        let clock = Clock::starting_at(as_of);
+        if loan.origin_date <= as_of {
+            loan.generate_history(&clock);
+        }
+        loan.payments_from(&clock).find(|p| p.payment_date >= as_of)
+    }
+
+    pub fn payments(&self) -> PaymentIterator<'_> {
+        self.payments_from(&Clock::system())
+    }
+
+    pub fn payments_from(&self, clock: &Clock) -> PaymentIterator<'_> {
+        // the recorded history, then projected payments. without any history the projection
+        // starts one payment period after the clock's time, or on the first payment date after
+        // it if one was set
+        let start = match (self.historical_payments.last(), self.first_payment_date) {
+            (Some(last), _) => self.frequency.next_date(last.payment_date),
+            (None, Some(first)) => {
+                let mut date = first;
+                while date <= clock.now() {
+                    date = self.frequency.next_date(date);
+                }
+                date
+            }
+            (None, None) => self.frequency.next_date(clock.now()),
+        };
+        let mut payments = PaymentIterator::projecting(self, start, self.additional_payment);
+        payments.history = self.historical_payments.iter();
+        payments
+    }
+
+    pub fn generate_amortization_schedule(&self) -> AmortizationSchedule {
+        self.schedule_from(&Clock::system())
+    }
+
+    pub fn schedule_from(&self, clock: &Clock) -> AmortizationSchedule {
+        self.schedule_of(self.payments_from(clock).collect(), clock)
+    }
+
+    fn schedule_of(&self, payments: Vec<Payment>, clock: &Clock) -> AmortizationSchedule {
+        // the totals for payments made on this loan, however they were worked out
+        let total_interest = payments.iter().fold(Money::zero(self.principal.currency()), |total, p| total + p.interest_payment);
+
+        let total_paid = self.principal + total_interest;
+        let zero = Money::zero(self.principal.currency());
+        let total_escrow_paid = payments.iter().fold(zero, |total, p| total + p.escrow_payment());
+        let total_hoa_paid = payments.iter().fold(zero, |total, p| total + p.hoa_payment);
+        let total_pmi_paid = payments.iter().fold(zero, |total, p| total + p.pmi_payment);
+        let total_lump_sums = payments.iter().fold(zero, |total, p| total + p.lump_sum_payment);
+        let lump_sum_interest_saved = if self.lump_sums.is_empty() {
+            zero
+        } else {
+            self.without_lump_sums().schedule_from(clock).total_interest_paid - total_interest
+        };
+        let total_late_fees = payments.iter().fold(zero, |total, p| total + p.late_fee);
+        let negative_amortization = payments.iter().any(|p| p.negative_amortization);
+        let pmi_removed_at = payments.windows(2)
+            .find(|pair| pair[0].pmi_payment.is_positive() && pair[1].pmi_payment.is_zero())
+            .map(|pair| pair[1].payment_number);
+
+        AmortizationSchedule {
+            payments,
+            total_interest_paid: total_interest,
+            total_paid,
+            total_escrow_paid,
+            total_hoa_paid,
+            total_piti_paid: total_paid + total_escrow_paid,
+            total_pmi_paid,
+            pmi_removed_at,
+            frequency: self.frequency,
+            total_lump_sums,
+            lump_sum_interest_saved,
+            total_late_fees,
+            tax_profile: self.tax_profile,
+            prepaid_interest: self.prepaid_interest(),
+            inflation: self.inflation_rate.map(|rate| Inflation::new(rate / 100.0, clock.now())),
+            negative_amortization,
+        }
+    }
+
+    pub fn extra_payment_for_payoff(&self, target_date: DateTime<Utc>) -> Option<PayoffPlan> {
+        self.payoff_plan_from(&Clock::system(), target_date)
+    }
+
+    pub fn payoff_plan_from(&self, clock: &Clock, target_date: DateTime<Utc>) -> Option<PayoffPlan> {
+        // the smallest additional payment, to the cent, whose schedule ends on or before the
+        // target. it replaces any additional payment already set, and the savings are against
+        // the loan as it stands. None if the target is before the next payment
+        let payoff = |extra: i64| {
+            let schedule = self.clone()
+                .with_additional_payment(Money::from_cents(extra, self.principal.currency()))
+                .schedule_from(clock);
+            let date = schedule.payments.last().map_or(self.origin_date, |p| p.payment_date);
+            (date, schedule.total_interest_paid)
+        };
+
+        // paying off everything with the next payment is as fast as it gets
+        let (mut low, mut high) = (0, self.remaining_principal().cents());
+        if payoff(high).0 > target_date {
+            return None;
+        }
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if payoff(middle).0 <= target_date {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+
+        let (payoff_date, interest) = payoff(low);
+        Some(PayoffPlan {
+            extra_payment: Money::from_cents(low, self.principal.currency()),
+            payoff_date,
+            interest_saved: self.schedule_from(clock).total_interest_paid - interest,
+        })
+    }
+
+    fn without_lump_sums(&self) -> Mortgage {
+        // the same loan and recorded months, as if the lump sums had never been paid
+        let mut baseline = self.clone();
+        baseline.lump_sums.clear();
+        baseline.historical_payments.clear();
+        if let Some(last) = self.historical_payments.last() {
+            baseline.generate_history(&Clock::starting_at(last.payment_date + Duration::seconds(1)));
+        }
+        baseline
+    }
+
+}
+
+// a mortgage's payments one at a time: the recorded history, then projected payments until the
+// loan is paid off. nothing is worked out until it's asked for, so taking a date range or
+// adding up a year doesn't build the whole schedule
+#[derive(Clone)]
+pub struct PaymentIterator<'a> {
+    mortgage: &'a Mortgage,
+    history: std::slice::Iter<'a, Payment>,
+    // the next projected payment
+    payment_number: u32,
+    payment_date: DateTime<Utc>,
+    previous_date: Option<DateTime<Utc>>,
+    balance: Money,
+    scheduled: Money,
+    extra: Money,
+}
+
+impl<'a> PaymentIterator<'a> {
+    fn projecting(mortgage: &'a Mortgage, payment_date: DateTime<Utc>, extra: Money) -> Self {
+        // picks up after the mortgage's recorded history, without yielding it
+        let last = mortgage.historical_payments.last();
+        PaymentIterator {
+            mortgage,
+            history: [].iter(),
+            payment_number: last.map_or(1, |p| p.payment_number + 1),
+            payment_date,
+            previous_date: last.map(|p| p.payment_date),
+            balance: mortgage.remaining_principal(),
+            scheduled: mortgage.scheduled_payment(),
+            extra,
+        }
+    }
+}
+
+impl Iterator for PaymentIterator<'_> {
+    type Item = Payment;
+
+    fn next(&mut self) -> Option<Payment> {
+        if let Some(payment) = self.history.next() {
+            return Some(payment.clone());
+        }
+        // a loan that never pays down stops after a century rather than running forever
+        if !self.balance.is_positive() || self.payment_number > 100 * self.mortgage.frequency.per_year() {
+            return None;
+        }
+
+        let payment = self.mortgage.payment_on(self.payment_number, self.payment_date, self.previous_date,
+            self.balance, &mut self.scheduled, self.extra);
+        self.balance = payment.remaining_principal;
+        self.payment_number += 1;
+        self.previous_date = Some(self.payment_date);
+        self.payment_date = self.mortgage.frequency.next_date(self.payment_date);
+        Some(payment)
+    }
+}
+
+// the payment that pays off balance over months at an annual rate
+fn level_payment(balance: Money, annual_rate: f64, months: u32) -> Money {
+    let r = annual_rate / 100.0 / 12.0;
+    let n = months as f64;
+
+    // an interest-free loan is just the principal split evenly
+    let factor = if r == 0.0 { 1.0 / n } else { r * (1.0+r).powf(n) / ((1.0 + r).powf(n) - 1.0) };
+
+    balance.mul_rate(factor, PAYMENT_ROUNDING)
+}
+
+// the total of the dated amounts in (after, through]
+fn due_between(amounts: &[(DateTime<Utc>, Money)], after: Option<DateTime<Utc>>, through: DateTime<Utc>, currency: Currency) -> Money {
+    amounts.iter()
+        .filter(|(date, _)| *date <= through && after.is_none_or(|after| *date > after))
+        .fold(Money::zero(currency), |total, (_, amount)| total + *amount)
+}
+
+// a yearly bill spread over a year of payments. the odd cents go to the first payments of
+// each loan year, so every year collects exactly the bill
+fn yearly_share(annual: Money, payment_number: u32, per_year: u32) -> Money {
+    let per_year = i64::from(per_year);
+    let index = i64::from(payment_number - 1) % per_year;
+    let cents = annual.cents() / per_year + i64::from(index < annual.cents() % per_year);
+    Money::from_cents(cents, annual.currency())
+}
+
+// interest for one payment period, some share of a year, on the given balance, settled to the cent
+pub(crate) fn periodic_interest(balance: Money, annual_rate: f64, year_fraction: f64) -> Money {
+    balance.mul_rate(annual_rate / 100.0 * year_fraction, INTEREST_ROUNDING)
+}
+
+// one calendar year of a schedule
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct YearSummary {
+    pub year: i32,
+    pub payments: usize,
+    pub principal: Money,
+    pub interest: Money,
+    pub escrow: Money,
+    pub ending_balance: Money,
+    pub ending_equity: Money,
+}
+
+impl AmortizationSchedule {
+    pub fn summary_by_year(&self) -> Vec<YearSummary> {
+        self.payments
+            .chunk_by(|a, b| a.payment_date.year() == b.payment_date.year())
+            .map(|year| YearSummary {
+                year: year[0].payment_date.year(),
+                payments: year.len(),
+                principal: year.iter().map(|p| p.principal_payment).sum(),
+                interest: year.iter().map(|p| p.interest_payment).sum(),
+                escrow: year.iter().map(Payment::escrow_payment).sum(),
+                ending_balance: year[year.len() - 1].remaining_principal,
+                ending_equity: year[year.len() - 1].equity(),
+            })
+            .collect()
+    }
+
+    // the first payment that leaves at least `share` of the home's value owned outright
+    pub fn equity_reached(&self, share: f64) -> Option<&Payment> {
+        self.payments.iter().find(|p| p.equity().ratio(p.home_value) >= share)
+    }
+
+    fn write_payments(&self, f: &mut fmt::Formatter<'_>, rule: &str, options: &FormatOptions) -> fmt::Result {
+        let m = |amount: Money| amount.format(options);
+        writeln!(f, "{}", rule)?;
+        write!(f, "{:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>6}",
+            "Pmt#", "Payment Date", "Payment", "Principal", "Interest", "Escrow", "Remaining", "Equity", "LTV")?;
+        // the payment in today's dollars, with an inflation rate
+        if self.inflation.is_some() {
+            write!(f, " | {:>12}", "Today's $")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", rule)?;
+
+        let mut previous: Option<&Payment> = None;
+        for payment in &self.payments {
+            write!(f,"{:4} | {}   | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>5.1}%",
+            payment.payment_number,
+            payment.payment_date.format("%Y-%b-%d"),
+            m(payment.payment_amount),
+            m(payment.principal_payment),
+            m(payment.interest_payment),
+            m(payment.escrow_payment()),
+            m(payment.remaining_principal),
+            m(payment.equity()),
+            payment.ltv() * 100.0)?;
+            if let Some(real) = self.real_value(payment, payment.payment_amount) {
+                write!(f, " | {:>12}", m(real))?;
+            }
+            if payment.rate_reset {
+                write!(f, "  * rate resets to {:.3}%", payment.rate)?;
+            }
+            if previous.is_some_and(|p| p.interest_only) && !payment.interest_only {
+                write!(f, "  * interest-only period ends")?;
+            }
+            if payment.balloon {
+                write!(f, "  * BALLOON PAYMENT")?;
+            }
+            if payment.negative_amortization {
+                write!(f, "  * negative amortization")?;
+            }
+            if payment.payment_recast {
+                write!(f, "  * negative amortization limit, payment recast")?;
+            }
+            if payment.forbearance {
+                write!(f, "  * forbearance")?;
+            } else if payment.payment_amount.is_zero() {
+                write!(f, "  * missed")?;
+            } else if payment.shortfall.is_positive() {
+                write!(f, "  * {} short", m(payment.shortfall))?;
+            }
+            if payment.late_fee.is_positive() {
+                write!(f, "  + {} late fee", m(payment.late_fee))?;
+            }
+            if !payment.lump_sum_payment.is_zero() {
+                write!(f, "  + {} lump sum", m(payment.lump_sum_payment))?;
+            }
+            if !payment.recast_payment.is_zero() {
+                // the regular payment, leaving out this month's one-off amounts
+                let regular = |p: &Payment| p.payment_amount - p.lump_sum_payment - p.recast_payment;
+                write!(f, "  * recast with {}", m(payment.recast_payment))?;
+                if let Some(before) = previous {
+                    write!(f, ", payment {} -> {}", m(regular(before)), m(regular(payment)))?;
+                }
+            }
+            previous = Some(payment);
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    fn write_years(&self, f: &mut fmt::Formatter<'_>, rule: &str, options: &FormatOptions) -> fmt::Result {
+        let m = |amount: Money| amount.format(options);
+        writeln!(f, "{}", rule)?;
+        writeln!(f, "{:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+            "Year", "Payments", "Principal", "Interest", "Escrow", "Balance", "Equity")?;
+        writeln!(f, "{}", rule)?;
+        for year in self.summary_by_year() {
+            writeln!(f, "{:4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+                year.year, year.payments, m(year.principal), m(year.interest), m(year.escrow), m(year.ending_balance), m(year.ending_equity))?;
+        }
+        Ok(())
+    }
+}
+
+// a schedule or scenario result written out with amounts in some other format, e.g. for
+// another locale or in whole dollars. {:#} on a schedule rolls it up by year the same as
+// the schedule itself
+pub struct Formatted<'a, T> {
+    value: &'a T,
+    options: FormatOptions,
+}
+
+impl AmortizationSchedule {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, AmortizationSchedule> {
+        Formatted { value: self, options }
+    }
+}
+
+impl fmt::Display for Formatted<'_, AmortizationSchedule> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.write_schedule(f, &self.options)
+    }
+}
+
+impl fmt::Display for AmortizationSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_schedule(f, &FormatOptions::default())
+    }
+}
+
+impl AmortizationSchedule {
+    fn write_schedule(&self, f: &mut fmt::Formatter<'_>, options: &FormatOptions) -> fmt::Result {
+        let m = |amount: Money| amount.format(options);
+        let rule = "-".repeat(120);
+        writeln!(f, "Amortization Schedule")?;
+        // {:#} rolls the payments up into one row per calendar year
+        if f.alternate() {
+            self.write_years(f, &rule, options)?;
+        } else {
+            self.write_payments(f, &rule, options)?;
+        }
+        writeln!(f, "{}", rule)?;
+        writeln!(f, "Total Payments: {}", m(self.total_paid))?;
+        writeln!(f, "Total Interest Paid: {}", m(self.total_interest_paid))?;
+        if let (Some(inflation), Some(paid), Some(interest)) = (self.inflation, self.real_total_paid(), self.real_total_interest()) {
+            writeln!(f, "Total Payments in Today's Dollars: {}", m(paid))?;
+            writeln!(f, "Total Interest in Today's Dollars: {} (at {:.1}% inflation)", m(interest), inflation.annual_rate * 100.0)?;
+        }
+        writeln!(f, "Number of Payments: {:.0}", self.payments.len())?;
+        if self.prepaid_interest.is_positive() {
+            writeln!(f, "Prepaid Interest at Closing: {}", m(self.prepaid_interest))?;
+        }
+        if let Some(payment) = self.equity_reached(EQUITY_MILESTONE) {
+            writeln!(f, "{:.0}% Equity Reached: payment {} on {}",
+                EQUITY_MILESTONE * 100.0, payment.payment_number, payment.payment_date.format("%Y-%b-%d"))?;
+        }
+
+        if let Some(balloon) = self.payments.iter().find(|p| p.balloon) {
+            writeln!(f, "Balloon Payment: {} due {}", m(balloon.payment_amount), balloon.payment_date.format("%Y-%b-%d"))?;
+        }
+        if !self.total_lump_sums.is_zero() {
+            writeln!(f, "Lump Sums Paid: {} (saves {} in interest)", m(self.total_lump_sums), m(self.lump_sum_interest_saved))?;
+        }
+        let short = self.payments.iter().filter(|p| p.shortfall.is_positive()).count();
+        if short > 0 {
+            writeln!(f, "Missed or Partial Payments: {} (late fees {})", short, m(self.total_late_fees))?;
+        }
+        if self.negative_amortization {
+            let short = self.payments.iter().filter(|p| p.negative_amortization).count();
+            let peak = self.payments.iter().map(|p| p.remaining_principal).fold(self.starting_balance(), Money::max);
+            writeln!(f, "WARNING: Negative Amortization: {} payments didn't cover the interest; the balance peaks at {}", short, m(peak))?;
+            if let Some(recast) = self.payments.iter().find(|p| p.payment_recast) {
+                writeln!(f, "Payment Recast: the balance passed {:.0}% of the loan on {}, and the payment went to {} without the cap",
+                    NEGATIVE_AMORTIZATION_LIMIT * 100.0, recast.payment_date.format("%Y-%b-%d"), m(recast.payment_amount))?;
+            }
+        }
+        let paused = self.payments.iter().filter(|p| p.forbearance).count();
+        if paused > 0 {
+            writeln!(f, "Payments in Forbearance: {}", paused)?;
+        }
+        if self.tax_profile.is_some() {
+            let years = self.tax_years();
+            let itemizing = years.iter().filter(|y| y.deduction_value.is_positive()).count();
+            writeln!(f, "Estimated Tax Savings: {} ({} of {} years worth itemizing)", m(self.total_tax_savings()), itemizing, years.len())?;
+            writeln!(f, "After-Tax Interest: {}", m(self.after_tax_interest()))?;
+        }
+        if !self.total_pmi_paid.is_zero() {
+            match self.pmi_removed_at {
+                Some(number) => writeln!(f, "Total PMI Paid: {} (removed at payment {})", m(self.total_pmi_paid), number)?,
+                None => writeln!(f, "Total PMI Paid: {}", m(self.total_pmi_paid))?,
+            }
+        }
+
+        if self.total_escrow_paid.is_zero() && self.total_hoa_paid.is_zero() {
+            return Ok(());
+        }
+        writeln!(f, "Total Escrow Paid: {}", m(self.total_escrow_paid))?;
+        writeln!(f, "Total PITI: {}", m(self.total_piti_paid))?;
+        if !self.total_hoa_paid.is_zero() {
+            writeln!(f, "Total HOA Dues: {}", m(self.total_hoa_paid))?;
+        }
+
+        // what went into escrow each loan year and what it paid out for. the compact view
+        // already has escrow by year
+        if f.alternate() {
+            return Ok(());
+        }
+        writeln!(f)?;
+        writeln!(f, "Annual Escrow Analysis")?;
+        writeln!(f, "{}", rule)?;
+        writeln!(f, "{:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+            "Year", "Property Tax", "Insurance", "Escrow", "HOA", "PITI")?;
+        writeln!(f, "{}", rule)?;
+        let per_year = self.frequency.per_year();
+        for year in self.payments.chunk_by(|a, b| (a.payment_number - 1) / per_year == (b.payment_number - 1) / per_year) {
+            let sum = |amount: fn(&Payment) -> Money| year.iter().map(amount).sum::<Money>();
+            writeln!(f, "{:4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>12}",
+                (year[0].payment_number - 1) / per_year + 1,
+                m(sum(|p| p.tax_payment)),
+                m(sum(|p| p.insurance_payment)),
+                m(sum(Payment::escrow_payment)),
+                m(sum(|p| p.hoa_payment)),
+                m(sum(Payment::piti)))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_additional_payments() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort1 = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        
+
+        let amort1 = mort1.generate_amortization_schedule();
+        let payment1 = amort1.total_paid;
+
+        let mort2 = mort1.with_additional_payment(Money::dollars(200.0));
+        let amort2 = mort2.generate_amortization_schedule();
+
+
+        let payment2 = amort2.total_paid;
+
+        assert!(payment1 > payment2);
+    }
+
+    #[test]
+    fn test_refinance_lower() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort1 = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        
+
+        let amort1 = mort1.generate_amortization_schedule();
+        let payment1 = amort1.total_paid;
+
+        let mort2 = mort1.refinance(2.5);
+        let amort2 = mort2.generate_amortization_schedule();
+
+
+        let payment2 = amort2.total_paid;
+
+        assert!(payment1 > payment2);
+    }
+
+    #[test]
+    fn test_refinance_higher() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort1 = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        
+
+        let amort1 = mort1.generate_amortization_schedule();
+        let payment1 = amort1.total_paid;
+
+        let mort2 = mort1.refinance(7.5);
+        let amort2 = mort2.generate_amortization_schedule();
+
+
+        let payment2 = amort2.total_paid;
+
+        assert!(payment1 < payment2);
+    }
+
+    #[test]
+    fn test_schedule_has_no_drift() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30).with_additional_payment(Money::dollars(123.45));
+        let amort = mort.generate_amortization_schedule();
+
+        // every cent of principal is paid off exactly, and the totals add up to the cent
+        let principal: Money = amort.payments.iter().map(|p| p.principal_payment).sum();
+        let paid: Money = amort.payments.iter().map(|p| p.payment_amount).sum();
+        assert_eq!(principal, Money::dollars(479000.0));
+        assert_eq!(paid, amort.total_paid);
+        assert!(amort.payments.last().unwrap().remaining_principal.is_zero());
+    }
+
+    #[test]
+    fn test_every_structure_settles_to_the_cent() {
+        // whatever the payments look like, principal adds up to the loan and the balance lands on zero
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let base = Mortgage::new(origin_date, Money::dollars(312345.67), 6.125, 30);
+        let loans = [
+            base.clone().with_additional_payment(Money::dollars(33.33)),
+            base.clone().with_frequency(PaymentFrequency::BiWeekly).with_hoa(Money::dollars(41.17)),
+            base.clone().with_frequency(PaymentFrequency::Weekly).with_interest_only(60),
+            base.clone().with_arm(five_one_arm()).recast(Utc.with_ymd_and_hms(2027, 3, 1, 0, 0, 0).unwrap(), Money::dollars(10000.01)),
+            base.clone().with_lump_sum(Utc.with_ymd_and_hms(2024, 1, 9, 0, 0, 0).unwrap(), Money::dollars(7777.77)).with_balloon(120),
+        ];
+        for loan in loans {
+            let amort = loan.schedule_from(&Clock::starting_at(origin_date));
+            let principal: Money = amort.payments.iter().map(|p| p.principal_payment).sum();
+            let paid: Money = amort.payments.iter().map(|p| p.payment_amount).sum();
+            assert_eq!(principal, Money::dollars(312345.67));
+            assert_eq!(paid, amort.total_paid);
+            assert!(amort.payments.last().unwrap().remaining_principal.is_zero());
+            assert!(amort.payments.iter().all(|p| !p.remaining_principal.is_negative() && !p.principal_payment.is_negative()));
+            assert_eq!(amort.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_history_follows_the_clock() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mut mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let mut clock = Clock::starting_at(origin_date);
+        clock.advance_months(6).unwrap();
+        mort.generate_history(&clock);
+        assert_eq!(mort.historical_payments.len(), 6);
+
+        // stepping the clock only adds the newly due payments
+        clock.advance_months(1).unwrap();
+        mort.generate_history(&clock);
+        mort.generate_history(&clock);
+        assert_eq!(mort.historical_payments.len(), 7);
+        assert_eq!(mort.historical_payments[6].payment_number, 7);
+
+        let schedule = mort.schedule_from(&clock);
+        assert_eq!(schedule.payments[7].payment_date, Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+        assert_eq!(schedule.payments.len(), 360);
+
+        // running the clock past the term stops at the final payment
+        clock.jump_to(Utc.with_ymd_and_hms(2060, 1, 1, 0, 0, 0).unwrap()).unwrap();
+        mort.generate_history(&clock);
+        assert_eq!(mort.historical_payments.len(), 360);
+        assert!(mort.historical_payments.last().unwrap().remaining_principal.is_zero());
+    }
+
+    #[test]
+    fn test_escrow_and_piti() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.05))
+            .with_insurance(Money::dollars(1800.0))
+            .with_hoa(Money::dollars(75.0));
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+
+        // the odd cents of the tax bill land in the first five months of each year
+        let first = &amort.payments[0];
+        assert_eq!(first.tax_payment, Money::dollars(500.01));
+        assert_eq!(amort.payments[5].tax_payment, Money::dollars(500.0));
+        assert_eq!(first.escrow_payment(), Money::dollars(650.01));
+        assert_eq!(first.piti(), first.payment_amount + Money::dollars(650.01));
+        assert_eq!(first.total_due(), first.piti() + Money::dollars(75.0));
+
+        let first_year: Money = amort.payments[..12].iter().map(Payment::escrow_payment).sum();
+        assert_eq!(first_year, Money::dollars(7800.05));
+        assert_eq!(amort.total_escrow_paid, Money::dollars(7800.05 * 30.0));
+        assert_eq!(amort.total_hoa_paid, Money::dollars(75.0 * 360.0));
+        assert_eq!(amort.total_piti_paid, amort.total_paid + amort.total_escrow_paid);
+
+        let text = amort.to_string();
+        assert!(text.contains("Annual Escrow Analysis"));
+        assert!(text.contains("Total PITI: "));
+    }
+
+    #[test]
+    fn test_pmi_drops_at_78_percent() {
+        // 5% down on a $500,000 home
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(475000.0), 5.5, 30)
+            .with_pmi(Money::dollars(500000.0), 0.5);
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+
+        let monthly_pmi = Money::dollars(197.92);
+        assert_eq!(amort.payments[0].pmi_payment, monthly_pmi);
+        assert_eq!(amort.payments[0].total_due(), amort.payments[0].payment_amount + monthly_pmi);
+
+        // the first payment without pmi is the first to start at or below $390,000
+        let removed = amort.pmi_removed_at.unwrap();
+        let before = &amort.payments[removed as usize - 2];
+        let after = &amort.payments[removed as usize - 1];
+        assert!(before.remaining_principal + before.principal_payment > Money::dollars(390000.0));
+        assert!(before.remaining_principal <= Money::dollars(390000.0));
+        assert!(after.pmi_payment.is_zero());
+        assert!(amort.payments[removed as usize..].iter().all(|p| p.pmi_payment.is_zero()));
+        assert_eq!(amort.total_pmi_paid, Money::dollars(197.92 * (removed - 1) as f64));
+        assert!(amort.to_string().contains(&format!("(removed at payment {})", removed)));
+
+        // paying extra gets there sooner
+        let faster = mort.with_additional_payment(Money::dollars(500.0)).schedule_from(&Clock::starting_at(origin_date));
+        assert!(faster.pmi_removed_at.unwrap() < removed);
+        assert!(faster.total_pmi_paid < amort.total_pmi_paid);
+    }
+
+    #[test]
+    fn test_biweekly_pays_off_sooner() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let clock = Clock::starting_at(origin_date);
+        let monthly = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0));
+        let biweekly = monthly.clone().with_frequency(PaymentFrequency::BiWeekly);
+        assert_eq!(biweekly.periodic_payment(), monthly.monthly_payment().mul_rate(0.5, Rounding::Up));
+
+        let by_month = monthly.schedule_from(&clock);
+        let by_fortnight = biweekly.schedule_from(&clock);
+        assert_eq!(by_fortnight.payments[1].payment_date - by_fortnight.payments[0].payment_date, Duration::weeks(2));
+        assert!(by_fortnight.payments.last().unwrap().remaining_principal.is_zero());
+
+        // a thirteenth monthly payment each year takes years off and saves interest
+        let payoff = |schedule: &AmortizationSchedule| schedule.payments.last().unwrap().payment_date;
+        assert!(payoff(&by_fortnight) < payoff(&by_month) - Duration::days(365 * 4));
+        assert!(by_fortnight.total_interest_paid < by_month.total_interest_paid);
+
+        // a year's escrow is the same bill however it's paid
+        let first_year: Money = by_fortnight.payments[..26].iter().map(Payment::escrow_payment).sum();
+        assert_eq!(first_year, Money::dollars(6000.0));
+
+        let weekly = monthly.with_frequency(PaymentFrequency::Weekly).schedule_from(&clock);
+        assert!(payoff(&weekly) <= payoff(&by_fortnight) + Duration::weeks(2));
+    }
+
+    #[test]
+    fn test_lump_sums() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let bonus = Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0).unwrap();
+        let refund = Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_lump_sum(bonus, Money::dollars(10000.0))
+            .with_lump_sum(refund, Money::dollars(2500.0));
+        let plain = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let clock = Clock::starting_at(origin_date);
+        let amort = mort.schedule_from(&clock);
+
+        // both land with the April 1 payment, the first on or after their dates
+        let april = &amort.payments[7];
+        assert_eq!(april.payment_date, refund);
+        assert_eq!(april.lump_sum_payment, Money::dollars(12500.0));
+        assert_eq!(april.payment_amount, mort.monthly_payment() + Money::dollars(12500.0));
+        assert!(amort.payments.iter().filter(|p| p.payment_number != 8).all(|p| p.lump_sum_payment.is_zero()));
+
+        let baseline = plain.schedule_from(&clock);
+        assert_eq!(amort.total_lump_sums, Money::dollars(12500.0));
+        assert_eq!(amort.lump_sum_interest_saved, baseline.total_interest_paid - amort.total_interest_paid);
+        assert!(amort.lump_sum_interest_saved.is_positive());
+        assert!(amort.payments.len() < baseline.payments.len());
+        assert!(amort.to_string().contains("Lump Sums Paid: $12,500.00"));
+
+        // a lump sum already in the recorded history counts too. history starts at the origin
+        let mut recorded = mort.clone();
+        let mut later = clock;
+        later.advance_months(12).unwrap();
+        recorded.generate_history(&later);
+        assert_eq!(recorded.history()[8].payment_date, refund);
+        assert_eq!(recorded.history()[8].lump_sum_payment, Money::dollars(12500.0));
+        let resumed = recorded.schedule_from(&later);
+        assert_eq!(resumed.total_lump_sums, Money::dollars(12500.0));
+        assert!(resumed.lump_sum_interest_saved.is_positive());
+    }
+
+    #[test]
+    fn test_extra_payments_by_date() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_extra_payment(DateRange::year(2024), Money::dollars(200.0))
+            .with_extra_payment(DateRange::starting(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()), Money::dollars(500.0));
+        let monthly = mort.monthly_payment();
+        let extra_in = |year: i32, payments: &[Payment]| {
+            payments.iter()
+                .filter(|p| p.payment_date.year() == year)
+                .map(|p| p.payment_amount - monthly)
+                .collect::<Vec<_>>()
+        };
+
+        // each payment gets the extra for its own date
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+        assert!(extra_in(2023, &amort.payments).iter().all(Money::is_zero));
+        assert_eq!(extra_in(2024, &amort.payments), vec![Money::dollars(200.0); 12]);
+        assert!(extra_in(2025, &amort.payments).iter().all(Money::is_zero));
+        assert_eq!(extra_in(2030, &amort.payments), vec![Money::dollars(500.0); 12]);
+        assert!(amort.payments.len() < 360);
+
+        // a flat additional payment adds to it, and recorded history counts the extra too
+        let both = mort.clone().with_additional_payment(Money::dollars(50.0)).schedule_from(&Clock::starting_at(origin_date));
+        assert_eq!(extra_in(2024, &both.payments)[0], Money::dollars(250.0));
+        let mut recorded = mort.clone();
+        recorded.generate_history(&Clock::starting_at(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+        assert_eq!(extra_in(2024, recorded.history()), vec![Money::dollars(200.0); 12]);
+
+        assert!(DateRange::year(2024).contains(Utc.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap()));
+        assert!(!DateRange::year(2024).contains(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_recast_lowers_the_payment() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let recast_date = Utc.with_ymd_and_hms(2025, 8, 1, 0, 0, 0).unwrap();
+        let plain = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let mort = plain.clone().recast(recast_date, Money::dollars(50000.0));
+        let clock = Clock::starting_at(origin_date);
+        let amort = mort.schedule_from(&clock);
+        let baseline = plain.schedule_from(&clock);
+
+        // payment 24 is the one on the recast date; the term doesn't change, the payment does
+        let recast = &amort.payments[23];
+        assert_eq!(recast.payment_date, recast_date);
+        assert_eq!(recast.recast_payment, Money::dollars(50000.0));
+        assert!(recast.lump_sum_payment.is_zero());
+        let before = amort.payments[22].payment_amount;
+        let after = amort.payments[24].payment_amount;
+        assert_eq!(before, plain.monthly_payment());
+        assert!(after < before - Money::dollars(250.0));
+        assert_eq!(recast.payment_amount, after + Money::dollars(50000.0));
+        assert_eq!(amort.payments.len(), baseline.payments.len());
+        assert!(amort.payments.last().unwrap().remaining_principal.is_zero());
+        assert!(amort.to_string().contains(&format!("* recast with $50,000.00, payment {} -> {}", before, after)));
+
+        // projecting on from recorded history keeps the recast payment. history starts at the
+        // origin, so the recast is payment 25 there
+        let mut recorded = mort.clone();
+        let mut later = clock;
+        later.advance_months(30).unwrap();
+        recorded.generate_history(&later);
+        assert_eq!(recorded.history()[24].recast_payment, Money::dollars(50000.0));
+        let resumed = recorded.schedule_from(&later);
+        assert_eq!(resumed.payments[40].payment_amount, recorded.history()[25].payment_amount);
+        assert_eq!(resumed.payments.len(), 360);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        // a hand-written loan only needs the basics
+        let json = r#"{"origin_date": "2023-08-01T00:00:00Z", "principal": "479000.00 USD", "annual_rate": 5.5, "term_years": 30}"#;
+        let loan: Mortgage = serde_json::from_str(json).unwrap();
+        assert_eq!(loan.monthly_payment(), Mortgage::new(loan.origin_date, Money::dollars(479000.0), 5.5, 30).monthly_payment());
+        assert_eq!(loan.frequency, PaymentFrequency::Monthly);
+
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mut mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .with_frequency(PaymentFrequency::BiWeekly)
+            .with_lump_sum(Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0).unwrap(), Money::dollars(10000.0))
+            .with_arm(five_one_arm());
+        let mut clock = Clock::starting_at(origin_date);
+        clock.advance_months(6).unwrap();
+        mort.generate_history(&clock);
+
+        let path = std::env::temp_dir().join(format!("mortgage-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        mort.save_to_json(path).unwrap();
+        let loaded = Mortgage::from_json(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(loaded.history().len(), mort.history().len());
+        assert_eq!(loaded.arm, mort.arm);
+
+        let schedule = loaded.schedule_from(&clock);
+        assert_eq!(schedule.total_interest_paid, mort.schedule_from(&clock).total_interest_paid);
+        let json = serde_json::to_string(&schedule).unwrap();
+        let back: AmortizationSchedule = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.payments.len(), schedule.payments.len());
+        assert_eq!(back.total_escrow_paid, schedule.total_escrow_paid);
+        assert!(serde_json::from_str::<Mortgage>(r#"{"principal": "1.00 USD"}"#).is_err());
+    }
+
+    #[test]
+    fn test_extra_payment_for_payoff() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let clock = Clock::starting_at(origin_date);
+        let target = Utc.with_ymd_and_hms(2043, 8, 1, 0, 0, 0).unwrap();
+
+        let plan = mort.payoff_plan_from(&clock, target).unwrap();
+        assert!(plan.payoff_date <= target);
+        assert!(plan.interest_saved.is_positive());
+
+        // a cent less misses the target
+        let short = mort.clone().with_additional_payment(plan.extra_payment - Money::usd(1)).schedule_from(&clock);
+        assert!(short.payments.last().unwrap().payment_date > target);
+        let exact = mort.clone().with_additional_payment(plan.extra_payment).schedule_from(&clock);
+        assert_eq!(exact.payments.last().unwrap().payment_date, plan.payoff_date);
+
+        // a target past the natural payoff needs nothing extra; one before the first payment can't be hit
+        let late = mort.payoff_plan_from(&clock, Utc.with_ymd_and_hms(2060, 1, 1, 0, 0, 0).unwrap()).unwrap();
+        assert!(late.extra_payment.is_zero());
+        assert!(late.interest_saved.is_zero());
+        assert_eq!(mort.payoff_plan_from(&clock, origin_date), None);
+    }
+
+    #[test]
+    fn test_interest_only_then_amortizing() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30).with_interest_only(120);
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+
+        // ten years of interest, then the principal over the last twenty
+        assert!(amort.payments[..120].iter().all(|p| p.interest_only && p.principal_payment.is_zero()));
+        assert_eq!(amort.payments[119].remaining_principal, Money::dollars(479000.0));
+        let amortizing = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 20).monthly_payment();
+        assert_eq!(amort.payments[120].payment_amount, amortizing);
+        assert_eq!(amort.payments.len(), 360);
+        assert!(amort.payments.last().unwrap().remaining_principal.is_zero());
+        assert!(amort.to_string().contains("* interest-only period ends"));
+
+        // projecting on from recorded history after the switch keeps the amortizing payment
+        let mut recorded = mort.clone();
+        let mut clock = Clock::starting_at(origin_date);
+        clock.advance_months(130).unwrap();
+        recorded.generate_history(&clock);
+        assert_eq!(recorded.schedule_from(&clock).payments[200].payment_amount, recorded.history()[125].payment_amount);
+    }
+
+    #[test]
+    fn test_balloon_payment() {
+        // a 30/7 balloon: thirty-year payments, with the balance due after seven years
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30).with_balloon(84);
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+
+        assert_eq!(amort.payments.len(), 84);
+        let balloon = amort.payments.last().unwrap();
+        assert!(balloon.balloon);
+        assert!(balloon.remaining_principal.is_zero());
+        assert!(balloon.payment_amount > Money::dollars(400000.0));
+        assert_eq!(amort.payments.iter().filter(|p| p.balloon).count(), 1);
+        assert_eq!(amort.payments[82].payment_amount, mort.monthly_payment());
+        let text = amort.to_string();
+        assert!(text.contains("* BALLOON PAYMENT"));
+        assert!(text.contains(&format!("Balloon Payment: {} due 2030-Aug-01", balloon.payment_amount)));
+    }
+
+    #[test]
+    fn test_payments_stream() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mut mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let mut clock = Clock::starting_at(origin_date);
+        clock.advance_months(3).unwrap();
+        mort.generate_history(&clock);
+
+        // the same payments the schedule collects, history first
+        let schedule = mort.schedule_from(&clock);
+        assert_eq!(mort.payments_from(&clock).count(), schedule.payments.len());
+        assert_eq!(mort.payments_from(&clock).nth(2).unwrap().payment_number, 3);
+
+        // one calendar year's interest, without building the rest
+        let in_2025 = |p: &Payment| p.payment_date.year() == 2025;
+        let interest_2025: Money = mort.payments_from(&clock)
+            .skip_while(|p| !in_2025(p))
+            .take_while(in_2025)
+            .map(|p| p.interest_payment)
+            .sum();
+        let expected: Money = schedule.payments.iter().filter(|p| in_2025(p)).map(|p| p.interest_payment).sum();
+        assert_eq!(interest_2025, expected);
+        assert!(interest_2025.is_positive());
+    }
+
+    #[test]
+    fn test_summary_by_year() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0));
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+        let years = amort.summary_by_year();
+
+        // September 2023 to August 2053, in calendar years
+        assert_eq!(years.len(), 31);
+        assert_eq!((years[0].year, years[0].payments), (2023, 4));
+        assert_eq!((years[1].year, years[1].payments), (2024, 12));
+        assert_eq!(years[1].ending_balance, amort.payments[15].remaining_principal);
+        assert_eq!(years.iter().map(|y| y.principal).sum::<Money>(), Money::dollars(479000.0));
+        assert_eq!(years.iter().map(|y| y.interest).sum::<Money>(), amort.total_interest_paid);
+        assert_eq!(years.iter().map(|y| y.escrow).sum::<Money>(), amort.total_escrow_paid);
+        assert!(years.last().unwrap().ending_balance.is_zero());
+
+        // the compact display has a row per year instead of per payment, and the same totals
+        let compact = format!("{:#}", amort);
+        assert!(compact.contains(&format!("2024 | {:>12} |", 12)));
+        assert!(compact.lines().count() < 60);
+        assert!(compact.contains("Total Interest Paid: "));
+    }
+
+    #[test]
+    fn test_equity_with_appreciation() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let flat = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_home_value(Money::dollars(532000.0));
+        let rising = flat.clone().with_appreciation(4.0);
+        let clock = Clock::starting_at(origin_date);
+        let flat = flat.schedule_from(&clock);
+        let rising = rising.schedule_from(&clock);
+
+        // a year in, the home is worth 4% more and all of that is equity
+        let year = &rising.payments[11];
+        assert_eq!(year.home_value, Money::dollars(553280.0));
+        assert_eq!(year.equity(), year.home_value - year.remaining_principal);
+        assert_eq!(year.equity() - flat.payments[11].equity(), Money::dollars(21280.0));
+        assert!((flat.payments[11].ltv() - flat.payments[11].remaining_principal.ratio(Money::dollars(532000.0))).abs() < 1e-12);
+
+        // starting at 10% down, appreciation gets to 20% equity years sooner
+        let flat_20 = flat.equity_reached(EQUITY_MILESTONE).unwrap();
+        let rising_20 = rising.equity_reached(EQUITY_MILESTONE).unwrap();
+        assert!(rising_20.payment_number < flat_20.payment_number);
+        assert!(rising_20.ltv() <= 0.8 && rising.payments[rising_20.payment_number as usize - 2].ltv() > 0.8);
+        assert!(rising.to_string().contains(&format!("20% Equity Reached: payment {}", rising_20.payment_number)));
+        assert_eq!(rising.summary_by_year()[1].ending_equity, rising.payments[15].equity());
+
+        // a loan file without a home value borrowed all of it
+        let mut json = serde_json::to_value(Mortgage::new(origin_date, Money::dollars(100000.0), 5.0, 30)).unwrap();
+        json.as_object_mut().unwrap().remove("home_value");
+        let loaded: Mortgage = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.schedule_from(&clock).payments[0].home_value, Money::dollars(100000.0));
+    }
+
+    #[test]
+    fn test_day_count_conventions() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let clock = Clock::starting_at(origin_date);
+        let thirty = mort.clone().schedule_from(&clock);
+        let actual = mort.clone().with_day_count(DayCount::Actual365).schedule_from(&clock);
+        let exact = mort.clone().with_day_count(DayCount::ActualActual).schedule_from(&clock);
+
+        // September's interest is for the 31 days of August
+        let september = &actual.payments[0];
+        assert_eq!(september.interest_payment, Money::dollars(479000.0).mul_rate(0.055 * 31.0 / 365.0, INTEREST_ROUNDING));
+        assert!(september.interest_payment > thirty.payments[0].interest_payment);
+        // March 2024 pays for a leap-year February, which is a smaller share of the year
+        let march = &exact.payments[6];
+        let balance = march.remaining_principal + march.principal_payment;
+        assert_eq!(march.interest_payment, balance.mul_rate(0.055 * 29.0 / 366.0, INTEREST_ROUNDING));
+
+        // the payment is the same and the loan still pays off on time
+        for schedule in [&actual, &exact] {
+            assert_eq!(schedule.payments.len(), 360);
+            assert!(schedule.payments.last().unwrap().remaining_principal.is_zero());
+            assert_eq!(schedule.payments[1].payment_amount, thirty.payments[1].payment_amount);
+            assert!((schedule.total_interest_paid - thirty.total_interest_paid).abs() < Money::dollars(2000.0));
+        }
+
+        // a loan closing on the 15th accrues only from then to the first payment
+        let closing = Utc.with_ymd_and_hms(2023, 8, 15, 0, 0, 0).unwrap();
+        let odd = Mortgage::new(closing, Money::dollars(479000.0), 5.5, 30).with_day_count(DayCount::Actual365);
+        let first = &odd.schedule_from(&clock).payments[0];
+        assert_eq!(first.payment_date, Utc.with_ymd_and_hms(2023, 9, 1, 0, 0, 0).unwrap());
+        assert_eq!(first.interest_payment, Money::dollars(479000.0).mul_rate(0.055 * 17.0 / 365.0, INTEREST_ROUNDING));
+    }
+
+    #[test]
+    fn test_first_payment_after_a_stub() {
+        // closing on August 15th with the first payment on October 1st
+        let closing = Utc.with_ymd_and_hms(2023, 8, 15, 0, 0, 0).unwrap();
+        let first_payment = Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(closing, Money::dollars(479000.0), 5.5, 30).with_first_payment(first_payment);
+
+        // the 16 days to September 1st are paid at closing; October pays September's interest
+        assert_eq!(mort.prepaid_interest(), Money::dollars(479000.0).mul_rate(0.055 * 16.0 / 360.0, INTEREST_ROUNDING));
+        assert_eq!(mort.clone().with_day_count(DayCount::Actual365).prepaid_interest(),
+            Money::dollars(479000.0).mul_rate(0.055 * 17.0 / 365.0, INTEREST_ROUNDING));
+        let amort = mort.schedule_from(&Clock::starting_at(closing));
+        assert_eq!(amort.payments[0].payment_date, first_payment);
+        assert_eq!(amort.payments[0].interest_payment, Money::dollars(479000.0).mul_rate(0.055 / 12.0, INTEREST_ROUNDING));
+        assert_eq!(amort.payments.len(), 360);
+        assert_eq!(amort.payments.last().unwrap().payment_date, Utc.with_ymd_and_hms(2053, 9, 1, 0, 0, 0).unwrap());
+        assert_eq!(amort.prepaid_interest, mort.prepaid_interest());
+        assert!(amort.to_string().contains("Prepaid Interest at Closing: "));
+        assert_eq!(mort.upfront_costs(), mort.prepaid_interest());
+
+        // history starts at the first payment and steps on from it
+        let mut recorded = mort.clone();
+        recorded.generate_history(&Clock::starting_at(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()));
+        assert_eq!(recorded.history().len(), 4);
+        assert_eq!(recorded.history()[0].payment_date, first_payment);
+
+        // a first payment in the month after closing leaves nothing to prepay
+        let soon = mort.clone().with_first_payment(Utc.with_ymd_and_hms(2023, 9, 1, 0, 0, 0).unwrap());
+        assert!(soon.prepaid_interest().is_zero());
+        assert!(Mortgage::builder(closing, Money::dollars(479000.0), 5.5, 30).with_first_payment(closing).build().is_err());
+    }
+
+    #[test]
+    fn test_missed_payments_and_forbearance() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let date = |y, m| Utc.with_ymd_and_hms(y, m, 1, 0, 0, 0).unwrap();
+        let plain = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let late = plain.clone()
+            .with_late_fees(LateFeePolicy { flat: Money::dollars(25.0), percent: 5.0 })
+            .with_missed_payment(date(2024, 1))
+            .with_partial_payment(date(2024, 2), Money::dollars(2000.0));
+        let mut recorded = late.clone();
+        recorded.generate_history(&Clock::starting_at(date(2024, 6)));
+        let history = recorded.history();
+        let monthly = plain.monthly_payment();
+
+        // January pays nothing, so its interest goes onto the balance, and February comes up short
+        let january = &history[5];
+        assert_eq!(january.payment_date, date(2024, 1));
+        assert!(january.payment_amount.is_zero());
+        assert_eq!(january.remaining_principal, history[4].remaining_principal + january.interest_payment);
+        assert_eq!(january.shortfall, monthly);
+        assert_eq!(january.late_fee, Money::dollars(25.0) + monthly.mul_rate(0.05, CHARGE_ROUNDING));
+        let february = &history[6];
+        assert_eq!(february.payment_amount, Money::dollars(2000.0));
+        assert_eq!(february.shortfall, monthly - Money::dollars(2000.0));
+        assert!(history[7].shortfall.is_zero() && history[7].late_fee.is_zero());
+
+        // the schedule picks up from the true balance and still pays off with the last payment
+        let schedule = recorded.schedule_from(&Clock::starting_at(date(2024, 6)));
+        assert_eq!(schedule.total_late_fees, january.late_fee + february.late_fee);
+        assert!(schedule.total_interest_paid > plain.schedule_from(&Clock::starting_at(origin_date)).total_interest_paid);
+        assert_eq!(schedule.payments.len(), 360);
+        assert!(schedule.to_string().contains("Missed or Partial Payments: 2"));
+
+        // six months of forbearance charge no fees, and the payment afterwards goes up to cover
+        // the deferred interest over the rest of the term
+        let paused = plain.clone().with_forbearance(DateRange::new(date(2024, 1), date(2024, 7)));
+        let schedule = paused.schedule_from(&Clock::starting_at(origin_date));
+        let months = &schedule.payments[4..10];
+        assert!(months.iter().all(|p| p.forbearance && p.payment_amount.is_zero() && p.late_fee.is_zero()));
+        assert_eq!(months[5].remaining_principal - schedule.payments[3].remaining_principal,
+            months.iter().map(|p| p.interest_payment).sum::<Money>());
+        let after = &schedule.payments[10];
+        assert!(!after.forbearance && after.payment_amount > monthly);
+        assert_eq!(schedule.payments.len(), 360);
+        assert!(schedule.payments.last().unwrap().remaining_principal.is_zero());
+
+        // projecting after recorded history keeps the re-amortized payment. history starts at
+        // the origin, so July 2024 is its twelfth payment
+        let mut recorded = paused.clone();
+        recorded.generate_history(&Clock::starting_at(date(2024, 9)));
+        let july = &recorded.history()[11];
+        assert!(!july.forbearance && recorded.history()[10].forbearance);
+        let resumed = recorded.schedule_from(&Clock::starting_at(date(2024, 9)));
+        assert_eq!(resumed.payments[13].payment_date, date(2024, 9));
+        assert_eq!(resumed.payments[13].payment_amount, july.payment_amount);
+    }
+
+    fn five_one_arm() -> ArmTerms {
+        ArmTerms {
+            fixed_months: 60,
+            adjustment_months: 12,
+            margin: 2.75,
+            periodic_cap: 2.0,
+            lifetime_cap: 5.0,
+            index: vec![4.5, 6.0, 3.0],
+            payment_cap: None,
+        }
+    }
+
+    #[test]
+    fn test_arm_resets() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(300000.0), 4.0, 30).with_arm(five_one_arm());
+
+        // the periodic cap holds the first reset to 6%, the lifetime cap the second to 9%,
+        // then the index falls and the rate follows it down as far as the cap allows
+        assert_eq!(mort.rate_at(60), 4.0);
+        assert_eq!(mort.rate_at(61), 6.0);
+        assert_eq!(mort.rate_at(72), 6.0);
+        assert_eq!(mort.rate_at(73), 8.0);
+        assert_eq!(mort.rate_at(85), 6.0);
+        assert_eq!(mort.rate_at(97), 5.75);
+
+        let amort = mort.schedule_from(&Clock::starting_at(origin_date));
+        let resets: Vec<u32> = amort.payments.iter().filter(|p| p.rate_reset).map(|p| p.payment_number).collect();
+        assert_eq!(resets, (61..=349).step_by(12).collect::<Vec<u32>>());
+        assert!(amort.payments[60].payment_amount > amort.payments[59].payment_amount);
+        assert_eq!(amort.payments.len(), 360);
+        assert!(amort.payments.last().unwrap().remaining_principal.is_zero());
+        assert!(amort.to_string().contains("* rate resets to 6.000%"));
+
+        // recorded history picks up the reset payment where the schedule would have
+        let mut recorded = mort.clone();
+        let mut clock = Clock::starting_at(origin_date);
+        clock.advance_months(70).unwrap();
+        recorded.generate_history(&clock);
+        let resumed = recorded.schedule_from(&clock);
+        assert_eq!(resumed.payments[80].payment_amount, amort.payments[80].payment_amount);
+        assert_eq!(resumed.total_interest_paid, amort.total_interest_paid);
+    }
+
+    #[test]
+    fn test_formats_for_another_locale() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let amort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .schedule_from(&Clock::starting_at(origin_date));
+        assert_eq!(amort.formatted(FormatOptions::default()).to_string(), amort.to_string());
+
+        let european = amort.formatted(FormatOptions::european()).to_string();
+        assert!(european.contains(&format!("Total Interest Paid: {}\n", amort.total_interest_paid.format(&FormatOptions::european()))));
+        let payment = amort.payments[0].payment_amount;
+        assert!(european.contains(&format!(" {} |", payment.format(&FormatOptions::european()))) && !european.contains(&payment.to_string()));
+        let whole = format!("{:#}", amort.formatted(FormatOptions::default().with_decimals(0, Rounding::HalfUp)));
+        assert!(whole.contains(" $0 |") && !whole.contains(".00"));
+    }
+
+    #[test]
+    fn test_finds_the_next_payment() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .with_insurance(Money::dollars(1800.0));
+        let mut recorded = mort.clone();
+        recorded.generate_history(&Clock::starting_at(Utc.with_ymd_and_hms(2060, 1, 1, 0, 0, 0).unwrap()));
+
+        let as_of = Utc.with_ymd_and_hms(2024, 3, 15, 0, 0, 0).unwrap();
+        let next = mort.next_payment(as_of).unwrap();
+        let expected = recorded.history().iter().find(|p| p.payment_date >= as_of).unwrap();
+        assert_eq!(next.payment_date, Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(next.payment_number, expected.payment_number);
+        assert_eq!((next.principal_payment, next.interest_payment), (expected.principal_payment, expected.interest_payment));
+        assert_eq!(next.escrow_payment(), Money::dollars(650.0));
+
+        // due on the day counts as next, and nothing is due once it's paid off
+        assert_eq!(mort.next_payment(next.payment_date).unwrap().payment_number, next.payment_number);
+        let last = recorded.history().last().unwrap();
+        assert!(mort.next_payment(last.payment_date + Duration::days(1)).is_none());
+    }
+
+    #[test]
+    fn test_capped_payments_amortize_negatively() {
+        // a teaser rate that jumps five points after a year, with the payment held to 7.5% more
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let arm = ArmTerms {
+            fixed_months: 12,
+            adjustment_months: 12,
+            margin: 2.75,
+            periodic_cap: 5.0,
+            lifetime_cap: 10.0,
+            index: vec![6.0],
+            payment_cap: Some(7.5),
+        };
+        let mort = Mortgage::new(origin_date, Money::dollars(300000.0), 2.0, 30).with_arm(arm);
+        let clock = Clock::starting_at(origin_date);
+        let amort = mort.schedule_from(&clock);
+
+        let (before, reset) = (&amort.payments[11], &amort.payments[12]);
+        assert!(reset.rate_reset && reset.rate == 7.0);
+        assert_eq!(reset.payment_amount, before.payment_amount.mul_rate(1.075, PAYMENT_ROUNDING));
+        assert!(reset.negative_amortization && !before.negative_amortization);
+        assert!(reset.remaining_principal > before.remaining_principal);
+        assert!(amort.negative_amortization);
+        assert!(amort.to_string().contains("WARNING: Negative Amortization"));
+
+        // the payment catches up a step at a time and still pays the loan off
+        assert!(amort.payments.iter().rev().take(100).all(|p| !p.negative_amortization));
+        assert_eq!(amort.verify(), Ok(()));
+        assert!(!mort.clone().with_arm(five_one_arm()).schedule_from(&clock).negative_amortization);
+
+        // picking up from history keeps the capped payment
+        let mut recorded = mort.clone();
+        let mut later = clock;
+        later.advance_months(40).unwrap();
+        recorded.generate_history(&later);
+        let resumed = recorded.schedule_from(&later);
+        assert_eq!(resumed.payments[45].payment_amount, amort.payments[45].payment_amount);
+        assert_eq!(resumed.total_interest_paid, amort.total_interest_paid);
+    }
+
+    #[test]
+    fn test_negative_amortization_limit_recasts_the_payment() {
+        // the rate jumps ten points and the payment can only move 2.5% a year, so it never
+        // catches up with the interest on its own
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let arm = ArmTerms {
+            fixed_months: 12,
+            adjustment_months: 12,
+            margin: 2.75,
+            periodic_cap: 10.0,
+            lifetime_cap: 10.0,
+            index: vec![10.0],
+            payment_cap: Some(2.5),
+        };
+        let principal = Money::dollars(300000.0);
+        let mort = Mortgage::new(origin_date, principal, 2.0, 30).with_arm(arm);
+        let clock = Clock::starting_at(origin_date);
+        let amort = mort.schedule_from(&clock);
+
+        let recasts: Vec<&Payment> = amort.payments.iter().filter(|p| p.payment_recast).collect();
+        assert_eq!(recasts.len(), 1);
+        let recast = recasts[0];
+        let limit = principal.mul_rate(NEGATIVE_AMORTIZATION_LIMIT, PAYMENT_ROUNDING);
+        assert!(recast.remaining_principal + recast.principal_payment > limit);
+        assert!(!recast.negative_amortization && recast.principal_payment.is_positive());
+        // the balance stops growing there, and the last payment is an ordinary one
+        let peak = amort.payments.iter().map(|p| p.remaining_principal).fold(principal, Money::max);
+        assert_eq!(peak, recast.remaining_principal + recast.principal_payment);
+        assert!(peak < limit + recast.interest_payment);
+        assert!(amort.payments.iter().skip(recast.payment_number as usize).all(|p| !p.negative_amortization));
+        let last = amort.payments.last().unwrap();
+        assert!(last.payment_amount < recast.payment_amount + Money::dollars(1.0));
+        assert_eq!(amort.verify(), Ok(()));
+        assert!(amort.to_string().contains("Payment Recast: the balance passed 110% of the loan"));
+
+        // picking up from history after the recast keeps the recast payment
+        let mut recorded = mort.clone();
+        let mut later = clock;
+        later.advance_months(recast.payment_number + 3).unwrap();
+        recorded.generate_history(&later);
+        let resumed = recorded.schedule_from(&later);
+        assert_eq!(resumed.total_interest_paid, amort.total_interest_paid);
+        assert_eq!(resumed.payments.last().unwrap().payment_amount, last.payment_amount);
+    }
+}
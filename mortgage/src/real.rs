@@ -0,0 +1,60 @@
+// a loan in today's dollars. a fixed payment gets cheaper as prices rise, so the same interest
+// costs less the later it's paid. with an inflation rate each amount is brought back to what
+// it's worth when the schedule was worked out
+use money::Money;
+
+use crate::{AmortizationSchedule, Payment};
+
+impl AmortizationSchedule {
+    // None without an inflation rate
+    pub fn real_value(&self, payment: &Payment, amount: Money) -> Option<Money> {
+        self.inflation.map(|inflation| inflation.real(amount, payment.payment_date))
+    }
+
+    pub fn real_total_paid(&self) -> Option<Money> {
+        self.real_sum(|p| p.payment_amount)
+    }
+
+    pub fn real_total_interest(&self) -> Option<Money> {
+        self.real_sum(|p| p.interest_payment)
+    }
+
+    fn real_sum(&self, amount: fn(&Payment) -> Money) -> Option<Money> {
+        let inflation = self.inflation?;
+        Some(self.payments.iter()
+            .fold(Money::zero(self.total_paid.currency()), |total, p| total + inflation.real(amount(p), p.payment_date)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Mortgage;
+    use chrono::{TimeZone, Utc};
+    use money::Money;
+    use simclock::Clock;
+
+    #[test]
+    fn discounts_later_payments() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let clock = Clock::starting_at(origin_date);
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30);
+        let nominal = mort.schedule_from(&clock);
+        assert_eq!(nominal.real_total_interest(), None);
+        assert!(!nominal.to_string().contains("Today's Dollars"));
+
+        let schedule = mort.with_inflation(3.0).schedule_from(&clock);
+        let first = &schedule.payments[0];
+        let last = schedule.payments.last().unwrap();
+        let real_first = schedule.real_value(first, first.payment_amount).unwrap();
+        let real_last = schedule.real_value(last, last.payment_amount).unwrap();
+        // a month out is worth a little less; thirty years out, well under half
+        assert!(real_first < first.payment_amount && real_first > first.payment_amount.mul_rate(0.99, money::Rounding::HalfEven));
+        assert!(real_last < last.payment_amount.mul_rate(0.45, money::Rounding::HalfEven));
+
+        let real_interest = schedule.real_total_interest().unwrap();
+        assert!(real_interest < schedule.total_interest_paid && real_interest.is_positive());
+        assert!(schedule.real_total_paid().unwrap() < schedule.total_paid);
+        let text = schedule.to_string();
+        assert!(text.contains("Total Interest in Today's Dollars: ") && text.contains("Today's $"));
+    }
+}
@@ -0,0 +1,347 @@
+// refinancing partway through a loan: pay off what's owed on a date with a new loan, then
+// compare what each path costs from that date on to find where the refinance pays for itself.
+// a rate grid puts quotes for several rates and terms side by side
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use money::Money;
+use simclock::Clock;
+
+use crate::{AmortizationSchedule, Mortgage, Payment};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClosingCosts {
+    // added to the new loan's principal
+    RolledIn(Money),
+    // paid up front on the refinance date
+    Cash(Money),
+}
+
+impl ClosingCosts {
+    pub fn amount(&self) -> Money {
+        match self {
+            ClosingCosts::RolledIn(amount) | ClosingCosts::Cash(amount) => *amount,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Refinance {
+    pub date: DateTime<Utc>,
+    // owed on the original loan on the refinance date: the principal left plus the interest
+    // accrued since the last payment, which the payoff covers
+    pub balance: Money,
+    pub accrued_interest: Money,
+    pub closing_costs: ClosingCosts,
+    // the original loan with its payments up to the refinance date
+    pub original: Mortgage,
+    pub new_loan: Mortgage,
+}
+
+// what each path has cost by some month after the refinance: interest, pmi and closing costs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostComparison {
+    pub month: u32,
+    pub date: DateTime<Utc>,
+    pub original: Money,
+    pub refinanced: Money,
+}
+
+// one lender quote: a new loan for the balance at a rate over a term
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateQuote {
+    pub rate: f64,
+    pub term_years: u32,
+    pub monthly_payment: Money,
+    pub total_interest: Money,
+    pub payoff_date: DateTime<Utc>,
+}
+
+// quotes for every rate and term, a row per rate
+pub struct RateGrid {
+    pub rates: Vec<f64>,
+    pub terms: Vec<u32>,
+    pub quotes: Vec<RateQuote>,
+}
+
+pub struct BreakEven {
+    pub date: DateTime<Utc>,
+    pub closing_costs: ClosingCosts,
+    pub original_payment: Money,
+    pub new_payment: Money,
+    pub months: Vec<CostComparison>,
+    // the first month the refinance has cost no more than keeping the original loan
+    pub break_even: Option<CostComparison>,
+}
+
+impl Mortgage {
+    pub fn refinance_at(&self, date: DateTime<Utc>, new_rate: f64, term_years: u32, closing_costs: ClosingCosts) -> Refinance {
+        // the payments made before the date, whether or not they were already recorded
+        let mut original = self.clone();
+        original.historical_payments.retain(|p| p.payment_date < date);
+        original.generate_history(&Clock::starting_at(date));
+        let last = original.historical_payments.last();
+        let next_number = last.map_or(1, |p| p.payment_number + 1);
+        let since = last.map_or(original.origin_date, |p| p.payment_date);
+        let accrued_interest = crate::periodic_interest(original.remaining_principal(), original.rate_at(next_number),
+            original.accrual(date, Some(since)));
+        let balance = original.remaining_principal() + accrued_interest;
+
+        let principal = match closing_costs {
+            ClosingCosts::RolledIn(amount) => balance + amount,
+            ClosingCosts::Cash(_) => balance,
+        };
+        // escrow, pmi, frequency and extra payments carry over to the new loan, as do any
+        // lump sums and recasts still to come. it amortizes normally, without the original's
+        // interest-only period or balloon. its closing costs are the ones given here
+        let mut new_loan = self.clone().refinance(new_rate);
+        new_loan.origin_date = date;
+        new_loan.first_payment_date = None;
+        new_loan.principal = principal;
+        new_loan.term_years = term_years;
+        new_loan.historical_payments.clear();
+        new_loan.lump_sums.retain(|(when, _)| *when >= date);
+        new_loan.recasts.retain(|(when, _)| *when >= date);
+        new_loan.interest_only_months = 0;
+        new_loan.balloon_month = None;
+        new_loan.discount_points = 0.0;
+        new_loan.origination_fee = Money::zero(principal.currency());
+        new_loan.closing_costs = Money::zero(principal.currency());
+
+        Refinance { date, balance, accrued_interest, closing_costs, original, new_loan }
+    }
+
+    pub fn rate_sensitivity(&self, rates: &[f64], terms: &[u32]) -> RateGrid {
+        self.rate_sensitivity_from(&Clock::system(), rates, terms)
+    }
+
+    pub fn rate_sensitivity_from(&self, clock: &Clock, rates: &[f64], terms: &[u32]) -> RateGrid {
+        // each quote refinances what's owed at the clock's time, without closing costs, so
+        // the quotes compare on rate and term alone
+        let no_costs = ClosingCosts::Cash(Money::zero(self.principal.currency()));
+        let quotes = rates.iter()
+            .flat_map(|&rate| terms.iter().map(move |&term_years| (rate, term_years)))
+            .map(|(rate, term_years)| {
+                let refi = self.refinance_at(clock.now(), rate, term_years, no_costs);
+                let schedule = refi.new_schedule();
+                RateQuote {
+                    rate,
+                    term_years,
+                    monthly_payment: refi.new_loan.monthly_payment(),
+                    total_interest: schedule.total_interest_paid,
+                    payoff_date: schedule.payments.last().map_or(refi.date, |p| p.payment_date),
+                }
+            })
+            .collect();
+        RateGrid { rates: rates.to_vec(), terms: terms.to_vec(), quotes }
+    }
+}
+
+impl RateGrid {
+    pub fn quote(&self, rate: usize, term: usize) -> Option<&RateQuote> {
+        if term >= self.terms.len() {
+            return None;
+        }
+        self.quotes.get(rate * self.terms.len() + term)
+    }
+}
+
+impl Refinance {
+    pub fn original_schedule(&self) -> Vec<Payment> {
+        // the original loan's payments from the refinance date on, had it been kept
+        self.original.payments_from(&Clock::starting_at(self.date))
+            .filter(|p| p.payment_date >= self.date)
+            .collect()
+    }
+
+    pub fn new_schedule(&self) -> AmortizationSchedule {
+        self.new_loan.schedule_from(&Clock::starting_at(self.date))
+    }
+
+    pub fn break_even(&self) -> BreakEven {
+        let original = self.original_schedule();
+        let refinanced = self.new_schedule().payments;
+        let zero = Money::zero(self.balance.currency());
+        let cost = |p: &Payment| p.interest_payment + p.pmi_payment;
+
+        // both paths run to whichever loan lasts longer; a paid-off loan costs nothing more. the
+        // original's next payment covers the interest accrued to the refinance date, which the
+        // payoff pays on the refinanced path
+        let mut original_total = zero;
+        let mut refinanced_total = self.closing_costs.amount() + self.accrued_interest;
+        let months = (0..original.len().max(refinanced.len()))
+            .map(|i| {
+                original_total += original.get(i).map_or(zero, cost);
+                refinanced_total += refinanced.get(i).map_or(zero, cost);
+                let date = refinanced.get(i).or(original.get(i)).map(|p| p.payment_date).unwrap_or(self.date);
+                CostComparison { month: i as u32 + 1, date, original: original_total, refinanced: refinanced_total }
+            })
+            .collect::<Vec<_>>();
+        let break_even = months.iter().find(|m| m.refinanced <= m.original).copied();
+
+        BreakEven {
+            date: self.date,
+            closing_costs: self.closing_costs,
+            original_payment: original.first().map_or(zero, |p| p.payment_amount),
+            new_payment: self.new_loan.periodic_payment(),
+            months,
+            break_even,
+        }
+    }
+}
+
+impl BreakEven {
+    // what refinancing saves over the life of both loans, negative if it costs more
+    pub fn lifetime_savings(&self) -> Money {
+        self.months.last().map_or(Money::zero(self.original_payment.currency()) - self.closing_costs.amount(),
+            |m| m.original - m.refinanced)
+    }
+}
+
+impl fmt::Display for BreakEven {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (how, amount) = match self.closing_costs {
+            ClosingCosts::RolledIn(amount) => ("rolled into the loan", amount),
+            ClosingCosts::Cash(amount) => ("paid in cash", amount),
+        };
+        writeln!(f, "Refinance on {}", self.date.format("%Y-%b-%d"))?;
+        writeln!(f, "Closing costs: {} {}", amount, how)?;
+        writeln!(f, "Payment: {} -> {}", self.original_payment, self.new_payment)?;
+        match &self.break_even {
+            Some(month) => writeln!(f, "Breaks even after {} payments, on {}", month.month, month.date.format("%Y-%b-%d"))?,
+            None => writeln!(f, "Never breaks even")?,
+        }
+        writeln!(f, "Lifetime savings: {}", self.lifetime_savings())?;
+
+        // cumulative interest, pmi and closing costs at the end of each year
+        writeln!(f, "--------------------------------------------------")?;
+        writeln!(f, "{:>4} | {:>12} | {:>14} | {:>12}", "Year", "Original", "Refinanced", "Difference")?;
+        writeln!(f, "--------------------------------------------------")?;
+        for (year, chunk) in self.months.chunks(12).enumerate() {
+            let end = chunk[chunk.len() - 1];
+            writeln!(f, "{:4} | {:>12} | {:>14} | {:>12}", year + 1, end.original, end.refinanced, end.original - end.refinanced)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RateGrid {
+    // a row per rate with a column per term: the monthly payment, total interest and payoff
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "-".repeat(8 + 39 * self.terms.len());
+        write!(f, "{:>6} ", "Rate")?;
+        for term in &self.terms {
+            write!(f, "| {:^36} ", format!("{} years", term))?;
+        }
+        writeln!(f)?;
+        write!(f, "{:>6} ", "")?;
+        for _ in &self.terms {
+            write!(f, "| {:>11} {:>14} {:>9} ", "Payment", "Interest", "Payoff")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", rule)?;
+        for (row, quotes) in self.quotes.chunks(self.terms.len().max(1)).enumerate() {
+            write!(f, "{:>5.3}% ", self.rates[row])?;
+            for quote in quotes {
+                write!(f, "| {:>11} {:>14} {:>9} ", quote.monthly_payment, quote.total_interest, quote.payoff_date.format("%Y-%b"))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn mortgage() -> Mortgage {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        Mortgage::new(origin_date, Money::dollars(479000.0), 7.0, 30)
+    }
+
+    #[test]
+    fn restarts_from_the_balance() {
+        let date = Utc.with_ymd_and_hms(2025, 8, 1, 0, 0, 0).unwrap();
+        let refi = mortgage().refinance_at(date, 5.5, 30, ClosingCosts::RolledIn(Money::dollars(6000.0)));
+
+        // two years of payments made, then a new loan for what's owed plus the costs
+        assert_eq!(refi.original.history().len(), 24);
+        assert_eq!(refi.balance, refi.original.remaining_principal() + refi.accrued_interest);
+        assert_eq!(refi.new_loan.principal, refi.balance + Money::dollars(6000.0));
+        let schedule = refi.new_schedule();
+        assert_eq!(schedule.payments[0].payment_date, Utc.with_ymd_and_hms(2025, 9, 1, 0, 0, 0).unwrap());
+        assert_eq!(schedule.payments.len(), 360);
+        assert_eq!(refi.original_schedule()[0].payment_date, date);
+
+        // history recorded past the date doesn't change what was owed on it
+        let mut recorded = mortgage();
+        recorded.generate_history(&Clock::starting_at(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()));
+        let late = recorded.refinance_at(date, 5.5, 30, ClosingCosts::RolledIn(Money::dollars(6000.0)));
+        assert_eq!(late.balance, refi.balance);
+    }
+
+    #[test]
+    fn both_paths_start_from_the_same_balance_and_date() {
+        // on a payment date the payoff takes the month's interest the original payment would have
+        let date = Utc.with_ymd_and_hms(2025, 8, 1, 0, 0, 0).unwrap();
+        let refi = mortgage().refinance_at(date, 5.5, 30, ClosingCosts::Cash(Money::dollars(6000.0)));
+        let kept = &refi.original_schedule()[0];
+        assert_eq!((kept.payment_date, refi.new_loan.origin_date), (date, date));
+        assert_eq!(refi.accrued_interest, kept.interest_payment);
+        assert_eq!(refi.balance, kept.remaining_principal + kept.principal_payment + kept.interest_payment);
+        assert_eq!(refi.new_loan.principal, refi.balance);
+
+        // partway through a month only the days since the last payment have accrued
+        let mid = mortgage().refinance_at(Utc.with_ymd_and_hms(2025, 8, 16, 0, 0, 0).unwrap(), 5.5, 30,
+            ClosingCosts::Cash(Money::dollars(6000.0)));
+        assert!(mid.accrued_interest > Money::zero(mid.balance.currency()) && mid.accrued_interest < kept.interest_payment);
+    }
+
+    #[test]
+    fn finds_the_break_even_month() {
+        let date = Utc.with_ymd_and_hms(2025, 8, 1, 0, 0, 0).unwrap();
+        let cash = mortgage().refinance_at(date, 5.5, 30, ClosingCosts::Cash(Money::dollars(6000.0))).break_even();
+        let month = cash.break_even.unwrap();
+        assert!(month.month > 1 && month.month < 24);
+        let before = &cash.months[month.month as usize - 2];
+        assert!(before.refinanced > before.original);
+        assert!(cash.new_payment < cash.original_payment);
+        assert!(cash.lifetime_savings().is_positive());
+        assert!(cash.to_string().contains(&format!("Breaks even after {} payments", month.month)));
+
+        // rolling the same costs in takes longer to pay off
+        let rolled = mortgage().refinance_at(date, 5.5, 30, ClosingCosts::RolledIn(Money::dollars(6000.0))).break_even();
+        assert!(rolled.break_even.unwrap().month >= month.month);
+
+        // a higher rate never pays for itself
+        let worse = mortgage().refinance_at(date, 7.5, 30, ClosingCosts::Cash(Money::dollars(6000.0))).break_even();
+        assert_eq!(worse.break_even, None);
+        assert!(worse.lifetime_savings().is_negative());
+    }
+
+    #[test]
+    fn quotes_every_rate_and_term() {
+        let clock = Clock::starting_at(Utc.with_ymd_and_hms(2025, 8, 1, 0, 0, 0).unwrap());
+        let grid = mortgage().rate_sensitivity_from(&clock, &[5.0, 5.5, 6.0], &[15, 30]);
+        assert_eq!(grid.quotes.len(), 6);
+
+        // the same as refinancing on the clock's date with nothing to close
+        let refi = mortgage().refinance_at(clock.now(), 5.5, 15, ClosingCosts::Cash(Money::dollars(0.0)));
+        let quote = grid.quote(1, 0).unwrap();
+        assert_eq!((quote.rate, quote.term_years), (5.5, 15));
+        assert_eq!(quote.monthly_payment, refi.new_loan.monthly_payment());
+        assert_eq!(quote.total_interest, refi.new_schedule().total_interest_paid);
+        assert_eq!(quote.payoff_date, Utc.with_ymd_and_hms(2040, 8, 1, 0, 0, 0).unwrap());
+        assert!(grid.quote(0, 2).is_none());
+
+        // a lower rate or a longer term lowers the payment; a shorter term saves interest
+        let (low, high) = (grid.quote(0, 1).unwrap(), grid.quote(2, 1).unwrap());
+        assert!(low.monthly_payment < high.monthly_payment);
+        assert!(grid.quote(1, 1).unwrap().monthly_payment < quote.monthly_payment);
+        assert!(quote.total_interest < grid.quote(1, 1).unwrap().total_interest);
+        let table = grid.to_string();
+        assert!(table.contains("5.500% ") && table.contains("15 years"));
+    }
+}
@@ -0,0 +1,336 @@
+// what-ifs on a loan: extra payments, a different rate or a different term, alone or together,
+// each run against the loan as it stands and compared on what it costs and when it ends
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use money::{FormatOptions, Money};
+use serde::{Deserialize, Serialize};
+use simclock::Clock;
+
+use crate::{Formatted, Mortgage, SPLIT_ROUNDING};
+
+// the changes to make. anything left as None keeps the loan's own value. in json only the
+// name is required
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioSpec {
+    pub name: String,
+    pub additional_payment: Option<Money>,
+    pub rate: Option<f64>,
+    pub term_years: Option<u32>,
+    // half the monthly payment drafted every two weeks
+    #[serde(default)]
+    pub half_payment_drafts: bool,
+    // on top of any the loan already has
+    #[serde(default)]
+    pub lump_sums: Vec<(DateTime<Utc>, Money)>,
+}
+
+impl ScenarioSpec {
+    pub fn new(name: &str) -> Self {
+        ScenarioSpec { name: name.to_string(), ..ScenarioSpec::default() }
+    }
+
+    pub fn with_additional_payment(mut self, additional_payment: Money) -> Self {
+        self.additional_payment = Some(additional_payment);
+        self
+    }
+
+    pub fn with_rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    pub fn with_term(mut self, term_years: u32) -> Self {
+        self.term_years = Some(term_years);
+        self
+    }
+
+    pub fn with_lump_sum(mut self, date: DateTime<Utc>, amount: Money) -> Self {
+        self.lump_sums.push((date, amount));
+        self
+    }
+
+    pub fn with_half_payment_drafts(mut self) -> Self {
+        self.half_payment_drafts = true;
+        self
+    }
+
+    // the loan with this scenario's changes made to it
+    pub fn apply(&self, mortgage: &Mortgage) -> Mortgage {
+        let mut mortgage = mortgage.clone();
+        if let Some(rate) = self.rate {
+            mortgage = mortgage.refinance(rate);
+        }
+        if let Some(term_years) = self.term_years {
+            mortgage.term_years = term_years;
+        }
+        if let Some(additional_payment) = self.additional_payment {
+            mortgage = mortgage.with_additional_payment(additional_payment);
+        }
+        if self.half_payment_drafts {
+            mortgage = mortgage.with_half_payment_drafts();
+        }
+        for &(date, amount) in &self.lump_sums {
+            mortgage = mortgage.with_lump_sum(date, amount);
+        }
+        mortgage
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioResult {
+    pub spec: ScenarioSpec,
+    // the scheduled payment plus the extra, what comes out of the budget each period
+    pub outlay: Money,
+    pub total_payments: usize,
+    pub total_interest: Money,
+    pub payoff_date: DateTime<Utc>,
+    // against the loan with no changes, negative if the scenario costs more
+    pub interest_savings: Money,
+    // the same in today's dollars, if the loan has an inflation rate
+    pub real_interest_savings: Option<Money>,
+}
+
+impl ScenarioResult {
+    // interest saved over the loan per dollar of extra payment each period
+    pub fn savings_ratio(&self) -> Option<f64> {
+        self.spec.additional_payment.filter(|extra| extra.is_positive()).map(|extra| self.interest_savings.ratio(extra))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioOrder {
+    // least interest first
+    TotalInterest,
+    // soonest payoff first
+    PayoffDate,
+    // smallest outlay first
+    Outlay,
+    // most saved per dollar of extra payment first, scenarios without extra payments last
+    SavingsRatio,
+}
+
+impl ScenarioOrder {
+    // results of one loan share a currency, so comparing cents is enough
+    pub fn compare(&self, a: &ScenarioResult, b: &ScenarioResult) -> Ordering {
+        match self {
+            ScenarioOrder::TotalInterest => a.total_interest.cents().cmp(&b.total_interest.cents()),
+            ScenarioOrder::PayoffDate => a.payoff_date.cmp(&b.payoff_date),
+            ScenarioOrder::Outlay => a.outlay.cents().cmp(&b.outlay.cents()),
+            ScenarioOrder::SavingsRatio => match (a.savings_ratio(), b.savings_ratio()) {
+                (Some(a), Some(b)) => b.total_cmp(&a),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            },
+        }
+    }
+}
+
+// a monthly payment split into two-week drafts comes to a thirteenth payment a year, about
+// the same as paying a twelfth of it extra every month. the twelfth goes in sooner, but a
+// year is a day or two more than 26 drafts, so every eleven years or so there's a 27th
+pub fn half_payment_scenarios(mortgage: &Mortgage) -> [ScenarioSpec; 2] {
+    let twelfth = mortgage.monthly_payment().mul_rate(1.0 / 12.0, SPLIT_ROUNDING);
+    [
+        ScenarioSpec::new("Half-payments every two weeks").with_half_payment_drafts(),
+        ScenarioSpec::new(&format!("{} extra monthly (1/12)", twelfth)).with_additional_payment(mortgage.additional_payment + twelfth),
+    ]
+}
+
+pub fn sort_scenarios(results: &mut [ScenarioResult], order: ScenarioOrder) {
+    results.sort_by(|a, b| order.compare(a, b));
+}
+
+impl Mortgage {
+    pub fn compare_scenarios(&self, specs: impl IntoIterator<Item = ScenarioSpec>) -> Vec<ScenarioResult> {
+        self.compare_scenarios_from(&Clock::system(), specs)
+    }
+
+    pub fn compare_scenarios_from(&self, clock: &Clock, specs: impl IntoIterator<Item = ScenarioSpec>) -> Vec<ScenarioResult> {
+        // recorded payments stay as they were, the changes apply to the payments still to come
+        let baseline = self.schedule_from(clock);
+        specs
+            .into_iter()
+            .map(|spec| {
+                let mortgage = spec.apply(self);
+                let schedule = mortgage.schedule_from(clock);
+                let last = schedule.payments.last().expect("a loan with a balance has payments");
+                // drafts average out to 13 payments over 12 months
+                let drafts = if spec.half_payment_drafts { 13.0 / 12.0 } else { 1.0 };
+                ScenarioResult {
+                    outlay: mortgage.periodic_payment().mul_rate(drafts, SPLIT_ROUNDING) + mortgage.additional_payment,
+                    total_payments: schedule.payments.len(),
+                    total_interest: schedule.total_interest_paid,
+                    payoff_date: last.payment_date,
+                    interest_savings: baseline.total_interest_paid - schedule.total_interest_paid,
+                    real_interest_savings: baseline.real_total_interest().zip(schedule.real_total_interest())
+                        .map(|(baseline, scenario)| baseline - scenario),
+                    spec,
+                }
+            })
+            .collect()
+    }
+}
+
+impl ScenarioResult {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, ScenarioResult> {
+        Formatted { value: self, options }
+    }
+
+    fn write_result(&self, f: &mut fmt::Formatter<'_>, options: &FormatOptions) -> fmt::Result {
+        let m = |amount: Money| amount.format(options);
+        writeln!(f, "{}", self.spec.name)?;
+        writeln!(f, "   Payment: {}", m(self.outlay))?;
+        writeln!(f, "   Total Payments: {}", self.total_payments)?;
+        writeln!(f, "   Total Interest: {}", m(self.total_interest))?;
+        writeln!(f, "   Payoff Date: {}", self.payoff_date.format("%Y-%b-%d"))?;
+        write!(f, "   Interest savings: {}", m(self.interest_savings))?;
+        if let Some(real) = self.real_interest_savings {
+            write!(f, "\n   In today's dollars: {}", m(real))?;
+        }
+        if let Some(ratio) = self.savings_ratio() {
+            write!(f, "\n   Savings Ratio: ${:.2} per $1 per month", ratio)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ScenarioResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_result(f, &FormatOptions::default())
+    }
+}
+
+impl fmt::Display for Formatted<'_, ScenarioResult> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.write_result(f, &self.options)
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    // names like "$1,000.00 extra" need quoting
+    if text.contains([',', '"']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn write_csv<W: Write>(results: &[ScenarioResult], out: W) -> io::Result<()> {
+    write_rows(results, out, |amount| amount.to_f64().to_string())
+}
+
+// the same columns with amounts written the options' way, e.g. with a decimal comma for a
+// spreadsheet in another locale. fields with the separator in them are quoted
+pub fn write_csv_with<W: Write>(results: &[ScenarioResult], out: W, options: &FormatOptions) -> io::Result<()> {
+    write_rows(results, out, |amount| amount.format(options))
+}
+
+fn write_rows<W: Write>(results: &[ScenarioResult], mut out: W, amount: impl Fn(Money) -> String) -> io::Result<()> {
+    writeln!(out, "name,additional_payment,rate,term_years,outlay,total_payments,total_interest,payoff_date,interest_savings,savings_ratio,real_interest_savings")?;
+    for r in results {
+        let additional = r.spec.additional_payment.map(&amount).unwrap_or_default();
+        let rate = r.spec.rate.map(|rate| rate.to_string()).unwrap_or_default();
+        let term = r.spec.term_years.map(|years| years.to_string()).unwrap_or_default();
+        let ratio = r.savings_ratio().map(|s| format!("{s:.4}")).unwrap_or_default();
+        let real = r.real_interest_savings.map(&amount).unwrap_or_default();
+        writeln!(out, "{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&r.spec.name), csv_field(&additional), rate, term,
+            csv_field(&amount(r.outlay)), r.total_payments, csv_field(&amount(r.total_interest)),
+            r.payoff_date.format("%Y-%m-%d"), csv_field(&amount(r.interest_savings)), ratio, csv_field(&real))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn compares_extra_rate_and_term() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mortgage = Mortgage::new(origin_date, Money::dollars(479000.0), 7.0, 30);
+        let clock = Clock::starting_at(origin_date);
+        let specs = vec![
+            ScenarioSpec::new("as is"),
+            ScenarioSpec::new("$200 extra").with_additional_payment(Money::dollars(200.0)),
+            ScenarioSpec::new("refinance at 5.5%").with_rate(5.5),
+            ScenarioSpec::new("15 years").with_term(15),
+            ScenarioSpec::new("15 years at 5.5%").with_rate(5.5).with_term(15),
+        ];
+        let mut results = mortgage.compare_scenarios_from(&clock, specs);
+
+        assert_eq!(results[0].interest_savings, Money::zero(money::Currency::Usd));
+        assert_eq!(results[0].total_payments, 360);
+        assert_eq!(results[0].savings_ratio(), None);
+        assert!(results[1].total_payments < 360 && results[1].savings_ratio().unwrap() > 1.0);
+        assert!(results[2].interest_savings.is_positive() && results[2].outlay < results[0].outlay);
+        assert_eq!(results[3].total_payments, 180);
+        assert!(results[3].outlay > results[0].outlay);
+
+        sort_scenarios(&mut results, ScenarioOrder::TotalInterest);
+        assert_eq!(results[0].spec.name, "15 years at 5.5%");
+        assert_eq!(results[4].spec.name, "as is");
+        sort_scenarios(&mut results, ScenarioOrder::SavingsRatio);
+        assert_eq!(results[0].spec.name, "$200 extra");
+
+        let mut csv = Vec::new();
+        write_csv(&results, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 6);
+        assert!(csv.lines().nth(1).unwrap().starts_with("$200 extra,200,,,"));
+        assert_eq!(csv_field("$1,000.00 extra"), "\"$1,000.00 extra\"");
+        assert!(results[0].to_string().contains("Savings Ratio"));
+        assert!(results.iter().all(|r| r.real_interest_savings.is_none()));
+
+        // a decimal comma is quoted, the same as a name with a comma in it
+        let mut csv = Vec::new();
+        write_csv_with(&results, &mut csv, &FormatOptions::european()).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.lines().nth(1).unwrap().starts_with("$200 extra,\"200,00 $\",,,"));
+        let whole = FormatOptions::default().with_decimals(0, money::Rounding::HalfUp);
+        assert!(results[0].formatted(whole).to_string().contains(&format!("Payment: {}\n", results[0].outlay.format(&whole))));
+    }
+
+    #[test]
+    fn real_savings_are_smaller() {
+        // the interest an extra payment saves is mostly years away, when dollars are worth less
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mortgage = Mortgage::new(origin_date, Money::dollars(479000.0), 7.0, 30).with_inflation(3.0);
+        let results = mortgage.compare_scenarios_from(&Clock::starting_at(origin_date), [
+            ScenarioSpec::new("as is"),
+            ScenarioSpec::new("$200 extra").with_additional_payment(Money::dollars(200.0)),
+        ]);
+        assert_eq!(results[0].real_interest_savings, Some(Money::zero(money::Currency::Usd)));
+        let real = results[1].real_interest_savings.unwrap();
+        assert!(real.is_positive() && real < results[1].interest_savings);
+        assert!(results[1].to_string().contains("In today's dollars: "));
+    }
+
+    #[test]
+    fn half_payments_credit_two_extra_halves_a_year() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mortgage = Mortgage::new(origin_date, Money::dollars(479000.0), 7.0, 30);
+        let clock = Clock::starting_at(origin_date);
+        let base = mortgage.schedule_from(&clock);
+        let drafted = mortgage.clone().with_half_payment_drafts().schedule_from(&clock);
+
+        // every month pays the monthly payment, plus a half in the months with a third draft
+        let half = mortgage.monthly_payment().mul_rate(0.5, SPLIT_ROUNDING);
+        let extras: Vec<Money> = drafted.payments[..24].iter().map(|p| p.payment_amount - mortgage.monthly_payment()).collect();
+        assert!(extras.iter().all(|extra| extra.is_zero() || *extra == half));
+        assert_eq!(extras.iter().filter(|extra| extra.is_positive()).count(), 4);
+        assert!(extras[0].is_zero());
+
+        let results = mortgage.compare_scenarios_from(&clock,
+            std::iter::once(ScenarioSpec::new("as is")).chain(half_payment_scenarios(&mortgage)));
+        assert_eq!(results[1].total_interest, drafted.total_interest_paid);
+        assert!(results[1].total_payments < base.payments.len() - 40);
+        // about the same money a year either way, so they finish within a few months
+        assert!(results[1].total_payments.abs_diff(results[2].total_payments) <= 3);
+        assert!((results[1].total_interest - results[2].total_interest).abs() < results[0].total_interest.mul_rate(0.01, SPLIT_ROUNDING));
+        assert!((results[1].outlay - results[2].outlay).abs() <= Money::dollars(0.02));
+    }
+}
@@ -0,0 +1,129 @@
+// a rough estimate of what deducting mortgage interest is worth. the interest and property tax
+// only save anything in the years they, with any other itemized deductions, come to more than
+// the standard deduction, and then only what they add over the better of the standard deduction
+// and the other deductions alone, at the marginal rate
+use chrono::Datelike;
+use money::Money;
+use serde::{Deserialize, Serialize};
+
+use crate::{AmortizationSchedule, CHARGE_ROUNDING};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaxProfile {
+    // in percent
+    pub marginal_rate: f64,
+    pub standard_deduction: Money,
+    // itemized deductions that have nothing to do with the house, e.g. charity
+    pub other_itemized: Money,
+}
+
+// one calendar year, the way taxes are filed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TaxYear {
+    pub year: i32,
+    pub interest: Money,
+    pub property_tax: Money,
+    pub itemized: Money,
+    // the tax the house saves: the deduction with it against the deduction without it
+    pub deduction_value: Money,
+}
+
+impl TaxProfile {
+    pub fn new(marginal_rate: f64, standard_deduction: Money) -> Self {
+        TaxProfile { marginal_rate, standard_deduction, other_itemized: Money::zero(standard_deduction.currency()) }
+    }
+
+    pub fn with_other_itemized(mut self, other_itemized: Money) -> Self {
+        self.other_itemized = other_itemized;
+        self
+    }
+}
+
+impl AmortizationSchedule {
+    pub fn tax_years(&self) -> Vec<TaxYear> {
+        let Some(profile) = self.tax_profile else {
+            return Vec::new();
+        };
+        let without_house = profile.other_itemized.max(profile.standard_deduction);
+        self.payments
+            .chunk_by(|a, b| a.payment_date.year() == b.payment_date.year())
+            .map(|year| {
+                let interest = year.iter().map(|p| p.interest_payment).sum();
+                let property_tax = year.iter().map(|p| p.tax_payment).sum();
+                let itemized = interest + property_tax + profile.other_itemized;
+                TaxYear {
+                    year: year[0].payment_date.year(),
+                    interest,
+                    property_tax,
+                    itemized,
+                    deduction_value: (itemized.max(profile.standard_deduction) - without_house)
+                        .mul_rate(profile.marginal_rate / 100.0, CHARGE_ROUNDING),
+                }
+            })
+            .collect()
+    }
+
+    pub fn total_tax_savings(&self) -> Money {
+        self.tax_years().iter().fold(Money::zero(self.total_paid.currency()), |total, y| total + y.deduction_value)
+    }
+
+    // interest less what deducting it saved
+    pub fn after_tax_interest(&self) -> Money {
+        self.total_interest_paid - self.total_tax_savings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mortgage;
+    use chrono::{TimeZone, Utc};
+    use simclock::Clock;
+
+    #[test]
+    fn itemizes_while_the_interest_is_high() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0));
+        let clock = Clock::starting_at(origin_date);
+        let plain = mort.schedule_from(&clock);
+        assert!(plain.tax_years().is_empty());
+        assert!(!plain.to_string().contains("Tax Savings"));
+
+        let profile = TaxProfile::new(24.0, Money::dollars(29200.0));
+        let schedule = mort.with_tax_profile(profile).schedule_from(&clock);
+        let years = schedule.tax_years();
+        assert_eq!(years.len(), 31);
+
+        // 2024's interest and property tax are over the standard deduction; by 2040 they aren't
+        let y2024 = &years[1];
+        assert_eq!(y2024.itemized, y2024.interest + Money::dollars(6000.0));
+        assert_eq!(y2024.deduction_value, (y2024.itemized - Money::dollars(29200.0)).mul_rate(0.24, CHARGE_ROUNDING));
+        assert!(years[17].deduction_value.is_zero());
+        assert_eq!(schedule.total_tax_savings(), years.iter().map(|y| y.deduction_value).sum::<Money>());
+        assert_eq!(schedule.after_tax_interest(), schedule.total_interest_paid - schedule.total_tax_savings());
+
+        // other deductions push more years over the line
+        let more = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .with_tax_profile(profile.with_other_itemized(Money::dollars(5000.0)))
+            .schedule_from(&clock);
+        assert!(more.total_tax_savings() > schedule.total_tax_savings());
+        assert!(schedule.to_string().contains("After-Tax Interest: "));
+    }
+
+    #[test]
+    fn other_deductions_over_the_standard_credit_the_house_with_all_of_it() {
+        // charity alone beats the standard deduction, so every dollar of interest and property
+        // tax is deducted on top of it, and the house gets none of the charity's excess
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let profile = TaxProfile::new(24.0, Money::dollars(29200.0)).with_other_itemized(Money::dollars(35000.0));
+        let schedule = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .with_property_tax(Money::dollars(6000.0))
+            .with_tax_profile(profile)
+            .schedule_from(&Clock::starting_at(origin_date));
+        for year in schedule.tax_years() {
+            assert_eq!(year.deduction_value, (year.interest + year.property_tax).mul_rate(0.24, CHARGE_ROUNDING));
+        }
+    }
+}
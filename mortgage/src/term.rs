@@ -0,0 +1,131 @@
+// the same loan written over shorter terms: what each costs a month and in interest, and what
+// paying the higher payment earns. the shorter loan's extra each month buys back the longer
+// one's payments after it ends, and the implied return is the yearly rate that makes those
+// even. at the same rate it's the loan's own rate, to set against what the money could earn
+// invested
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use money::Money;
+
+use crate::{Mortgage, Payment};
+
+pub const COMPARED_TERMS: [u32; 3] = [15, 20, 30];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TermOption {
+    pub term_years: u32,
+    pub monthly_payment: Money,
+    pub total_interest: Money,
+    pub payoff_date: DateTime<Utc>,
+    // against the longest term compared: how much more the payment is, and in percent a year
+    // what that earns. None for the longest term itself
+    pub extra_payment: Money,
+    pub implied_return: Option<f64>,
+}
+
+pub struct TermComparison {
+    // shortest term first
+    pub terms: Vec<TermOption>,
+}
+
+impl Mortgage {
+    pub fn term_comparison(&self) -> TermComparison {
+        self.compare_terms(&COMPARED_TERMS)
+    }
+
+    // re-amortizes the principal at the loan's rate over each term from origination, without
+    // any extra payments
+    pub fn compare_terms(&self, terms: &[u32]) -> TermComparison {
+        let mut terms = terms.to_vec();
+        terms.sort();
+        terms.dedup();
+        let schedules: Vec<(u32, Mortgage, Vec<Payment>)> = terms.iter().map(|&term_years| {
+            let mut loan = self.clone();
+            loan.term_years = term_years;
+            let payments = loan.contract_payments();
+            (term_years, loan, payments)
+        }).collect();
+        let Some((_, longest, longest_payments)) = schedules.last() else {
+            return TermComparison { terms: Vec::new() };
+        };
+
+        let terms = schedules.iter().map(|(term_years, loan, payments)| {
+            let shorter = *term_years < longest.term_years;
+            TermOption {
+                term_years: *term_years,
+                monthly_payment: loan.monthly_payment(),
+                total_interest: payments.iter().map(|p| p.interest_payment).sum(),
+                payoff_date: payments.last().map_or(loan.origin_date, |p| p.payment_date),
+                extra_payment: loan.monthly_payment() - longest.monthly_payment(),
+                implied_return: shorter.then(|| implied_return(payments, longest_payments, self.frequency.per_year())),
+            }
+        }).collect();
+        TermComparison { terms }
+    }
+}
+
+// the yearly rate, in percent, at which paying `shorter` instead of `longer` breaks even: more
+// each period until the shorter loan is paid off, then nothing while the longer one still runs
+fn implied_return(shorter: &[Payment], longer: &[Payment], per_year: u32) -> f64 {
+    let outlay = |payments: &[Payment], i: usize| payments.get(i).map_or(0.0, |p| p.payment_amount.to_f64());
+    let saved: Vec<f64> = (0..shorter.len().max(longer.len())).map(|i| outlay(longer, i) - outlay(shorter, i)).collect();
+    let present_value = |rate: f64| saved.iter().enumerate().map(|(k, amount)| amount / (1.0 + rate).powi(k as i32 + 1)).sum::<f64>();
+
+    // paying more up front for more later, so the present value falls as the rate rises
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if present_value(middle) > 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0 * per_year as f64 * 100.0
+}
+
+impl fmt::Display for TermComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = "-".repeat(84);
+        writeln!(f, "{:>8} | {:>12} | {:>14} | {:>10} | {:>12} | {:>14}", "Term", "Payment", "Total Interest", "Payoff", "Extra", "Implied Return")?;
+        writeln!(f, "{}", rule)?;
+        for term in &self.terms {
+            let implied = term.implied_return.map_or("-".to_string(), |rate| format!("{:.2}%", rate));
+            writeln!(f, "{:>8} | {:>12} | {:>14} | {:>10} | {:>12} | {:>14}", format!("{} years", term.term_years),
+                term.monthly_payment, term.total_interest, term.payoff_date.format("%Y-%b").to_string(), term.extra_payment, implied)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use simclock::Clock;
+
+    #[test]
+    fn compares_fifteen_twenty_and_thirty_years() {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        let mort = Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30).with_additional_payment(Money::dollars(200.0));
+        let comparison = mort.term_comparison();
+        let [fifteen, twenty, thirty] = comparison.terms.as_slice() else { panic!("three terms") };
+
+        assert_eq!(thirty.monthly_payment, mort.monthly_payment());
+        assert_eq!(thirty.total_interest, Mortgage::new(origin_date, Money::dollars(479000.0), 5.5, 30)
+            .schedule_from(&Clock::starting_at(origin_date)).total_interest_paid);
+        assert!(thirty.extra_payment.is_zero() && thirty.implied_return.is_none());
+        assert!(fifteen.monthly_payment > twenty.monthly_payment && twenty.monthly_payment > thirty.monthly_payment);
+        assert!(fifteen.total_interest < twenty.total_interest && twenty.total_interest < thirty.total_interest);
+        assert_eq!(fifteen.payoff_date, Utc.with_ymd_and_hms(2038, 8, 1, 0, 0, 0).unwrap());
+        assert_eq!(fifteen.extra_payment, fifteen.monthly_payment - thirty.monthly_payment);
+
+        // at the same rate, paying it down faster earns the rate
+        for term in [fifteen, twenty] {
+            assert!((term.implied_return.unwrap() - 5.5).abs() < 0.01);
+        }
+        let text = comparison.to_string();
+        assert!(text.contains("15 years") && text.contains("5.50%"));
+    }
+}
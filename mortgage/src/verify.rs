@@ -0,0 +1,128 @@
+// checks that a schedule adds up. every amount is whole cents and the last payment clears
+// the balance plus its interest exactly, so nothing here allows for rounding: each payment
+// splits into its principal and interest, each balance follows from the one before, the
+// principal adds up to what was borrowed and the totals match the payments
+use std::fmt;
+
+use money::Money;
+
+use crate::AmortizationSchedule;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    // the payment isn't its principal plus its interest
+    Split { payment_number: u32, payment: Money, principal: Money, interest: Money },
+    // the remaining principal isn't the previous balance less the principal paid
+    Balance { payment_number: u32, expected: Money, found: Money },
+    NegativeBalance { payment_number: u32, balance: Money },
+    // still owed after the last payment
+    Unpaid(Money),
+    Total { name: &'static str, expected: Money, found: Money },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Split { payment_number, payment, principal, interest } =>
+                write!(f, "payment {}: {} is not {} principal plus {} interest", payment_number, payment, principal, interest),
+            Violation::Balance { payment_number, expected, found } =>
+                write!(f, "payment {}: remaining principal is {}, expected {}", payment_number, found, expected),
+            Violation::NegativeBalance { payment_number, balance } =>
+                write!(f, "payment {}: remaining principal is negative ({})", payment_number, balance),
+            Violation::Unpaid(balance) => write!(f, "{} is still owed after the last payment", balance),
+            Violation::Total { name, expected, found } => write!(f, "{} is {}, the payments add up to {}", name, found, expected),
+        }
+    }
+}
+
+impl AmortizationSchedule {
+    // the balance before the first payment: the principal for a schedule from origination
+    pub fn starting_balance(&self) -> Money {
+        self.payments.first().map_or(self.total_paid - self.total_interest_paid, |p| p.remaining_principal + p.principal_payment)
+    }
+
+    // every broken invariant, in payment order, with the totals last
+    pub fn verify(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        let mut balance = self.starting_balance();
+        for p in &self.payments {
+            if p.payment_amount != p.principal_payment + p.interest_payment {
+                violations.push(Violation::Split {
+                    payment_number: p.payment_number,
+                    payment: p.payment_amount,
+                    principal: p.principal_payment,
+                    interest: p.interest_payment,
+                });
+            }
+            if p.remaining_principal != balance - p.principal_payment {
+                violations.push(Violation::Balance {
+                    payment_number: p.payment_number,
+                    expected: balance - p.principal_payment,
+                    found: p.remaining_principal,
+                });
+            }
+            if p.remaining_principal.is_negative() {
+                violations.push(Violation::NegativeBalance { payment_number: p.payment_number, balance: p.remaining_principal });
+            }
+            balance = p.remaining_principal;
+        }
+        if let Some(last) = self.payments.last()
+            && !last.remaining_principal.is_zero() {
+            violations.push(Violation::Unpaid(last.remaining_principal));
+        }
+
+        let zero = Money::zero(self.total_paid.currency());
+        let interest = self.payments.iter().fold(zero, |total, p| total + p.interest_payment);
+        let paid = self.payments.iter().fold(zero, |total, p| total + p.payment_amount);
+        for (name, expected, found) in [("total interest", interest, self.total_interest_paid), ("total paid", paid, self.total_paid)] {
+            if expected != found {
+                violations.push(Violation::Total { name, expected, found });
+            }
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mortgage;
+    use chrono::{TimeZone, Utc};
+    use simclock::Clock;
+
+    fn schedule() -> AmortizationSchedule {
+        let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+        Mortgage::new(origin_date, Money::dollars(312345.67), 6.125, 30).schedule_from(&Clock::starting_at(origin_date))
+    }
+
+    #[test]
+    fn a_generated_schedule_holds() {
+        let schedule = schedule();
+        assert_eq!(schedule.starting_balance(), Money::dollars(312345.67));
+        assert_eq!(schedule.verify(), Ok(()));
+
+        // the last payment is exactly what was left plus its interest
+        let [.., before, last] = schedule.payments.as_slice() else { panic!("too few payments") };
+        assert_eq!(last.principal_payment, before.remaining_principal);
+        assert_eq!(last.payment_amount, before.remaining_principal + last.interest_payment);
+    }
+
+    #[test]
+    fn finds_every_broken_invariant() {
+        let mut schedule = schedule();
+        schedule.payments[10].principal_payment += Money::from_cents(1, money::Currency::Usd);
+        schedule.total_paid += Money::dollars(1.0);
+        let last = schedule.payments.len() - 1;
+        schedule.payments[last].remaining_principal = Money::dollars(0.02);
+
+        let violations = schedule.verify().unwrap_err();
+        assert!(matches!(violations[0], Violation::Split { payment_number: 11, .. }));
+        assert!(matches!(violations[1], Violation::Balance { payment_number: 11, .. }));
+        assert!(matches!(violations[2], Violation::Balance { payment_number: 360, .. }));
+        assert_eq!(violations[3], Violation::Unpaid(Money::dollars(0.02)));
+        assert!(matches!(violations[4], Violation::Total { name: "total paid", .. }));
+        assert_eq!(violations.len(), 5);
+        assert!(violations[3].to_string().contains("still owed"));
+    }
+}
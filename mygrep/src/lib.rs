@@ -1,52 +1,129 @@
 use std::error::Error;
 use std::{fs, env};
+use std::io::{self, Write};
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.file_path)?;
+    let mut results = Vec::new();
+    for path in &config.file_paths {
+        let contents = fs::read_to_string(path)?;
+        let lines = search_lines(&config.query, &contents, config.ignore_case)
+            .into_iter()
+            .map(|(number, line)| (number, line.to_string()))
+            .collect();
+        results.push(FileMatches { path: path.clone(), lines });
+    }
 
-    let results = if config.ignore_case {
-        search_case_insensitive(&config.query, &contents)
+    let mut out = io::stdout().lock();
+    if config.group {
+        write_grouped(&mut out, &results)?;
     } else {
-        search(&config.query, &contents)
-    };
+        write_flat(&mut out, &results)?;
+    }
+
+    Ok(())
+}
+
+// the matching lines of one file, with 1-based line numbers
+pub struct FileMatches {
+    pub path: String,
+    pub lines: Vec<(usize, String)>,
+}
 
-    for line in results {
-        println!("{line}");
+pub fn write_flat(out: &mut impl Write, results: &[FileMatches]) -> io::Result<()> {
+    // one file prints bare lines like it always has; several get a path: prefix per line
+    let prefix = results.len() > 1;
+    for file in results {
+        for (_, line) in &file.lines {
+            if prefix {
+                writeln!(out, "{}:{}", file.path, line)?;
+            } else {
+                writeln!(out, "{line}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn write_grouped(out: &mut impl Write, results: &[FileMatches]) -> io::Result<()> {
+    // each file with matches gets its path as a heading, a blank line between files,
+    // and a summary of the whole search at the end
+    let mut first = true;
+    for file in results.iter().filter(|f| !f.lines.is_empty()) {
+        if !first {
+            writeln!(out)?;
+        }
+        first = false;
+        let plural = if file.lines.len() == 1 { "" } else { "es" };
+        writeln!(out, "{} ({} match{})", file.path, file.lines.len(), plural)?;
+        for (number, line) in &file.lines {
+            writeln!(out, "{number}:{line}")?;
+        }
     }
 
+    let matches: usize = results.iter().map(|f| f.lines.len()).sum();
+    let matched_files = results.iter().filter(|f| !f.lines.is_empty()).count();
+    if !first {
+        writeln!(out)?;
+    }
+    writeln!(out, "{} match{} in {} of {} file{}",
+        matches, if matches == 1 { "" } else { "es" },
+        matched_files, results.len(), if results.len() == 1 { "" } else { "s" })?;
     Ok(())
 }
 
 pub struct Config {
     pub query: String,
-    pub file_path: String,
+    pub file_paths: Vec<String>,
     pub ignore_case: bool,
+    // print a heading per file instead of a path on every line
+    pub group: bool,
 }
 
 impl Config {
     pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
         args.next(); // skip the program name item
 
-        let query = match args.next() {
+        let mut group = false;
+        let mut positional = Vec::new();
+        for arg in args {
+            match arg.as_str() {
+                "--group" => group = true,
+                _ => positional.push(arg),
+            }
+        }
+        let mut positional = positional.into_iter();
+
+        let query = match positional.next() {
             Some(arg) => arg,
             None => return Err("Didn't get a query string"),
         };
 
-        let file_path = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a file path"),
-        };
+        let file_paths: Vec<String> = positional.collect();
+        if file_paths.is_empty() {
+            return Err("Didn't get a file path");
+        }
 
         let ignore_case = env::var("IGNORE_CASE").is_ok();
 
         Ok(Config { 
             query, 
-            file_path, 
+            file_paths, 
             ignore_case, 
+            group,
         })
     }
 }
 
+pub fn search_lines<'a>(query: &str, contents: &'a str, ignore_case: bool) -> Vec<(usize, &'a str)> {
+    let lowered = query.to_lowercase();
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| if ignore_case { line.to_lowercase().contains(&lowered) } else { line.contains(query) })
+        .map(|(i, line)| (i + 1, line))
+        .collect()
+}
+
 pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
     contents
         .lines()
@@ -92,4 +169,46 @@ Trust me.";
             vec!["Rust:", "Trust me."], 
             search_case_insensitive(query, contents));
     }
+
+    fn matches(path: &str, lines: &[(usize, &str)]) -> FileMatches {
+        FileMatches { path: path.to_string(), lines: lines.iter().map(|(n, l)| (*n, l.to_string())).collect() }
+    }
+
+    #[test]
+    fn config_takes_many_files_and_group() {
+        let args = ["mygrep", "--group", "rust", "a.txt", "b.txt"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        assert!(config.group);
+        assert_eq!(config.query, "rust");
+        assert_eq!(config.file_paths, vec!["a.txt", "b.txt"]);
+        assert!(Config::build(["mygrep", "rust", "--group"].map(String::from).into_iter()).is_err());
+    }
+
+    #[test]
+    fn grouped_output() {
+        let contents = "Rust:\nsafe, fast, productive.\nTrust me.";
+        assert_eq!(search_lines("rust", contents, true), vec![(1, "Rust:"), (3, "Trust me.")]);
+
+        let results = [
+            matches("poem.txt", &[(1, "Rust:"), (3, "Trust me.")]),
+            matches("empty.txt", &[]),
+            matches("notes.txt", &[(7, "rust belt")]),
+        ];
+        let mut out = Vec::new();
+        write_grouped(&mut out, &results).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\
+poem.txt (2 matches)
+1:Rust:
+3:Trust me.
+
+notes.txt (1 match)
+7:rust belt
+
+3 matches in 2 of 3 files
+");
+
+        let mut out = Vec::new();
+        write_flat(&mut out, &results).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "poem.txt:Rust:\npoem.txt:Trust me.\nnotes.txt:rust belt\n");
+    }
 }
\ No newline at end of file
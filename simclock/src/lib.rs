@@ -0,0 +1,158 @@
+// a clock that only moves when told to, so several models can be stepped through the
+// same simulated timeline. models take a &Clock wherever they'd otherwise call Utc::now()
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    now: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClockError {
+    // time only runs forwards
+    Backwards { now: DateTime<Utc>, requested: DateTime<Utc> },
+    OutOfRange,
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockError::Backwards { now, requested } =>
+                write!(f, "cannot move the clock back from {} to {}", now.date_naive(), requested.date_naive()),
+            ClockError::OutOfRange => write!(f, "date is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ClockError {}
+
+impl Clock {
+    pub fn starting_at(now: DateTime<Utc>) -> Self {
+        Clock { now }
+    }
+
+    pub fn starting_on(date: NaiveDate) -> Self {
+        Clock::starting_at(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+    }
+
+    pub fn system() -> Self {
+        // frozen at the moment it was created, like any other Clock
+        Clock::starting_at(Utc::now())
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now.date_naive()
+    }
+
+    pub fn advance_days(&mut self, days: u64) -> Result<DateTime<Utc>, ClockError> {
+        self.now = self.now.checked_add_days(Days::new(days)).ok_or(ClockError::OutOfRange)?;
+        Ok(self.now)
+    }
+
+    pub fn advance_months(&mut self, months: u32) -> Result<DateTime<Utc>, ClockError> {
+        // the 31st advances to the last day of shorter months
+        self.now = self.now.checked_add_months(Months::new(months)).ok_or(ClockError::OutOfRange)?;
+        Ok(self.now)
+    }
+
+    pub fn jump_to(&mut self, to: DateTime<Utc>) -> Result<DateTime<Utc>, ClockError> {
+        if to < self.now {
+            return Err(ClockError::Backwards { now: self.now, requested: to });
+        }
+        self.now = to;
+        Ok(self.now)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+// something that happens every `frequency`, starting at `next`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recurrence {
+    pub next: DateTime<Utc>,
+    pub frequency: Frequency,
+    // the nth occurrence is computed from the start rather than the previous occurrence,
+    // so a monthly event on the 31st comes back to the 31st after February
+    start: DateTime<Utc>,
+    count: u32,
+}
+
+impl Recurrence {
+    pub fn new(start: DateTime<Utc>, frequency: Frequency) -> Self {
+        Recurrence { next: start, frequency, start, count: 0 }
+    }
+
+    fn nth(&self, n: u32) -> Option<DateTime<Utc>> {
+        match self.frequency {
+            Frequency::Monthly => self.start.checked_add_months(Months::new(n)),
+            Frequency::Yearly => self.start.checked_add_months(Months::new(n.checked_mul(12)?)),
+            Frequency::Daily => self.start.checked_add_days(Days::new(u64::from(n))),
+            Frequency::Weekly => self.start.checked_add_days(Days::new(u64::from(n) * 7)),
+        }
+    }
+
+    pub fn due(&mut self, clock: &Clock) -> Vec<DateTime<Utc>> {
+        // every occurrence up to and including the clock's time that hasn't been taken yet
+        let mut due = Vec::new();
+        while self.next <= clock.now() {
+            due.push(self.next);
+            self.count += 1;
+            match self.nth(self.count) {
+                Some(next) => self.next = next,
+                None => break,
+            }
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn advances_and_refuses_to_go_back() {
+        let mut clock = Clock::starting_at(date(2024, 1, 31));
+        clock.advance_months(1).unwrap();
+        assert_eq!(clock.now(), date(2024, 2, 29));
+        clock.advance_days(2).unwrap();
+        assert_eq!(clock.today(), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+        assert!(matches!(clock.jump_to(date(2024, 1, 1)), Err(ClockError::Backwards { .. })));
+        clock.jump_to(date(2030, 6, 1)).unwrap();
+        assert_eq!(clock.now(), date(2030, 6, 1));
+    }
+
+    #[test]
+    fn recurrences_keep_their_day() {
+        let mut rent = Recurrence::new(date(2024, 1, 31), Frequency::Monthly);
+        let mut clock = Clock::starting_at(date(2024, 1, 1));
+        assert!(rent.due(&clock).is_empty());
+
+        clock.jump_to(date(2024, 4, 29)).unwrap();
+        assert_eq!(rent.due(&clock), vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)]);
+        assert!(rent.due(&clock).is_empty());
+        clock.advance_days(1).unwrap();
+        assert_eq!(rent.due(&clock), vec![date(2024, 4, 30)]);
+        assert_eq!(rent.next, date(2024, 5, 31));
+
+        let mut payday = Recurrence::new(date(2024, 1, 5), Frequency::Weekly);
+        assert_eq!(payday.due(&Clock::starting_at(date(2024, 1, 26))).len(), 4);
+    }
+}
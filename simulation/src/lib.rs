@@ -0,0 +1,402 @@
+// steps a whole household through time one month at a time: paychecks in, bills and
+// mortgage payments out, interest and market growth on what's left
+use std::fmt;
+
+use bankaccounts::inflation::Inflation;
+use bankaccounts::person::Person;
+use chrono::NaiveDate;
+use money::{Money, Rounding};
+use mortgage::Mortgage;
+use simclock::Clock;
+
+#[derive(Debug, Clone)]
+pub struct Income {
+    pub name: String,
+    pub annual: Money,
+    // fraction withheld for taxes, e.g. 0.25
+    pub tax_rate: f64,
+    // fractional raise applied at the start of every simulated year
+    pub annual_raise: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Expense {
+    pub name: String,
+    pub monthly: Money,
+    pub annual_inflation: f64,
+}
+
+pub struct Household {
+    pub person: Person,
+    pub mortgages: Vec<Mortgage>,
+    pub incomes: Vec<Income>,
+    pub expenses: Vec<Expense>,
+    // index into person.accounts that pay lands in and bills come out of
+    pub checking: usize,
+    // (account, buffer): checking cash above the buffer is moved to that account each month
+    pub sweep: Option<(usize, Money)>,
+    // annual growth applied to holding prices
+    pub investment_return: f64,
+    // general inflation, used to report everything in today's dollars as well
+    pub inflation: f64,
+}
+
+impl Household {
+    pub fn new(person: Person) -> Self {
+        Household {
+            person,
+            mortgages: Vec::new(),
+            incomes: Vec::new(),
+            expenses: Vec::new(),
+            checking: 0,
+            sweep: None,
+            investment_return: 0.0,
+            inflation: 0.0,
+        }
+    }
+
+    pub fn with_mortgage(mut self, mortgage: Mortgage) -> Self {
+        self.mortgages.push(mortgage);
+        self
+    }
+
+    pub fn with_income(mut self, name: &str, annual: Money, tax_rate: f64, annual_raise: f64) -> Self {
+        self.incomes.push(Income { name: name.to_string(), annual, tax_rate, annual_raise });
+        self
+    }
+
+    pub fn with_expense(mut self, name: &str, monthly: Money, annual_inflation: f64) -> Self {
+        self.expenses.push(Expense { name: name.to_string(), monthly, annual_inflation });
+        self
+    }
+
+    pub fn with_sweep(mut self, account: usize, buffer: Money) -> Self {
+        self.sweep = Some((account, buffer));
+        self
+    }
+
+    pub fn with_investment_return(mut self, annual: f64) -> Self {
+        self.investment_return = annual;
+        self
+    }
+
+    pub fn with_inflation(mut self, annual: f64) -> Self {
+        self.inflation = annual;
+        self
+    }
+
+    pub fn mortgage_balance(&self) -> Money {
+        self.mortgages.iter().map(|m| m.remaining_principal()).sum()
+    }
+
+    pub fn net_worth(&self) -> Money {
+        self.person.net_worth() - self.mortgage_balance()
+    }
+}
+
+// the household's position at the end of one simulated month, in nominal dollars
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub date: NaiveDate,
+    pub cash: Money,
+    pub investments: Money,
+    pub mortgage_balance: Money,
+    pub net_worth: Money,
+    // expenses and mortgage payments paid this month
+    pub withdrawals: Money,
+    // nominal dollars per dollar at the start of the simulation
+    pub deflator: f64,
+}
+
+impl Snapshot {
+    pub fn real(&self, nominal: Money) -> Money {
+        nominal.mul_rate(1.0 / self.deflator, Rounding::HalfEven)
+    }
+
+    pub fn in_todays_dollars(&self) -> Snapshot {
+        Snapshot {
+            date: self.date,
+            cash: self.real(self.cash),
+            investments: self.real(self.investments),
+            mortgage_balance: self.real(self.mortgage_balance),
+            net_worth: self.real(self.net_worth),
+            withdrawals: self.real(self.withdrawals),
+            deflator: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payoff {
+    // index into Household::mortgages
+    pub mortgage: usize,
+    pub date: NaiveDate,
+    pub total_interest: Money,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountGrowth {
+    pub name: String,
+    pub start: Money,
+    pub end: Money,
+    // the end balance in today's dollars
+    pub end_real: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub snapshots: Vec<Snapshot>,
+    pub payoffs: Vec<Payoff>,
+    pub accounts: Vec<AccountGrowth>,
+    pub taxes_paid: Money,
+    // bills and mortgage payments the checking account couldn't cover
+    pub shortfalls: usize,
+    pub inflation: Inflation,
+}
+
+pub fn simulate(household: &mut Household, clock: &mut Clock, months: u32) -> Report {
+    let starting: Vec<Money> = household.person.accounts.iter().map(|a| a.get_balance()).collect();
+    let mut taxes_paid = Money::usd(0);
+    let mut shortfalls = 0;
+    let mut snapshots = Vec::new();
+    let mut payoffs: Vec<Payoff> = Vec::new();
+    let monthly_return = (1.0 + household.investment_return).powf(1.0 / 12.0);
+    let inflation = Inflation::new(household.inflation, clock.now());
+
+    // bring the mortgages up to date and start each account's interest schedule
+    for mortgage in household.mortgages.iter_mut() {
+        mortgage.generate_history(clock);
+    }
+    for account in household.person.accounts.iter_mut() {
+        account.accrue_until(clock);
+    }
+
+    for month in 1..=months {
+        if clock.advance_months(1).is_err() {
+            break;
+        }
+        if month > 1 && (month - 1).is_multiple_of(12) {
+            for income in household.incomes.iter_mut() {
+                income.annual = income.annual.mul_rate(1.0 + income.annual_raise, Rounding::HalfEven);
+            }
+            for expense in household.expenses.iter_mut() {
+                expense.monthly = expense.monthly.mul_rate(1.0 + expense.annual_inflation, Rounding::HalfEven);
+            }
+        }
+
+        let mut withdrawals = Money::usd(0);
+        let checking = &mut household.person.accounts[household.checking];
+        for income in &household.incomes {
+            let gross = income.annual.mul_rate(1.0 / 12.0, Rounding::HalfEven);
+            let tax = gross.mul_rate(income.tax_rate, Rounding::HalfEven);
+            taxes_paid += tax;
+            let _ = checking.deposit_on(clock, gross - tax, Some(&income.name));
+        }
+        for expense in &household.expenses {
+            match checking.withdraw_on(clock, expense.monthly, Some(&expense.name)) {
+                Ok(amount) => withdrawals += amount,
+                Err(_) => shortfalls += 1,
+            }
+        }
+
+        for (i, mortgage) in household.mortgages.iter_mut().enumerate() {
+            let paid = mortgage.history().len();
+            mortgage.generate_history(clock);
+            for payment in &mortgage.history()[paid..] {
+                // escrow, pmi, hoa and any late fee come out with the principal and interest
+                let note = format!("Mortgage payment #{}", payment.payment_number);
+                match checking.withdraw_on(clock, payment.total_due(), Some(&note)) {
+                    Ok(amount) => withdrawals += amount,
+                    Err(_) => shortfalls += 1,
+                }
+            }
+            if paid < mortgage.history().len() && mortgage.remaining_principal().is_zero() {
+                payoffs.push(Payoff {
+                    mortgage: i,
+                    date: clock.today(),
+                    total_interest: mortgage.history().iter().map(|p| p.interest_payment).sum(),
+                });
+            }
+        }
+
+        for account in household.person.accounts.iter_mut() {
+            shortfalls += account.advance(clock).len();
+            account.accrue_until(clock);
+        }
+
+        if let Some((to, buffer)) = household.sweep {
+            let surplus = household.person.accounts[household.checking].get_balance() - buffer;
+            if surplus.is_positive() && to != household.checking {
+                let _ = household.person.accounts[household.checking].withdraw_on(clock, surplus, Some("Sweep to savings"));
+                let _ = household.person.accounts[to].deposit_on(clock, surplus, Some("Sweep from checking"));
+            }
+        }
+
+        for holding in household.person.holdings.iter_mut() {
+            holding.price = holding.price.mul_rate(monthly_return, Rounding::HalfEven);
+        }
+
+        snapshots.push(Snapshot {
+            date: clock.today(),
+            cash: household.person.cash(),
+            investments: household.person.brokerage_value(),
+            mortgage_balance: household.mortgage_balance(),
+            net_worth: household.net_worth(),
+            withdrawals,
+            deflator: inflation.deflator(clock.now()),
+        });
+    }
+
+    let accounts = household.person.accounts.iter()
+        .zip(starting)
+        .map(|(a, start)| AccountGrowth {
+            name: a.get_name().to_string(),
+            start,
+            end: a.get_balance(),
+            end_real: inflation.real(a.get_balance(), clock.now()),
+        })
+        .collect();
+
+    Report { snapshots, payoffs, accounts, taxes_paid, shortfalls, inflation }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // with an inflation assumption, net worth and spending also show in today's dollars
+        let real = self.inflation.annual_rate != 0.0;
+        let rule = if real { "-".repeat(138) } else { "-".repeat(104) };
+        writeln!(f, "Household Simulation")?;
+        if real {
+            writeln!(f, "Real values in {} dollars at {:.1}% inflation", self.inflation.base.format("%Y-%m-%d"), self.inflation.annual_rate * 100.0)?;
+        }
+        writeln!(f, "{}", rule)?;
+        write!(f, "{:>10} | {:>14} | {:>14} | {:>14} | {:>14} | {:>14}", "Date", "Cash", "Investments", "Mortgage", "Net Worth", "Spent")?;
+        if real {
+            write!(f, " | {:>14} | {:>14}", "Real Net Worth", "Real Spent")?;
+        }
+        writeln!(f, "\n{}", rule)?;
+        // one row a year, plus the final month
+        let last = self.snapshots.len().saturating_sub(1);
+        for (i, s) in self.snapshots.iter().enumerate() {
+            if (i + 1).is_multiple_of(12) || i == last {
+                write!(f, "{:>10} | {:>14} | {:>14} | {:>14} | {:>14} | {:>14}",
+                    s.date, s.cash, s.investments, s.mortgage_balance, s.net_worth, s.withdrawals)?;
+                if real {
+                    write!(f, " | {:>14} | {:>14}", s.real(s.net_worth), s.real(s.withdrawals))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, "{}", rule)?;
+
+        for payoff in &self.payoffs {
+            writeln!(f, "Mortgage {} paid off on {} after {} of interest", payoff.mortgage + 1, payoff.date, payoff.total_interest)?;
+        }
+        for account in &self.accounts {
+            write!(f, "{}: {} -> {}", account.name, account.start, account.end)?;
+            if real {
+                write!(f, " ({} in today's dollars)", account.end_real)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "Taxes paid: {}", self.taxes_paid)?;
+        if self.shortfalls > 0 {
+            writeln!(f, "Missed payments: {}", self.shortfalls)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bankaccounts::BankAccount;
+    use chrono::{TimeZone, Utc};
+
+    fn household() -> Household {
+        let mut person = Person::new("Stephen");
+        person.accounts.push(BankAccount::new_checking("Checking", Money::dollars(5000.0), 0.0, Money::dollars(0.0), Money::dollars(0.0)));
+        person.accounts.push(BankAccount::new_checking("Savings", Money::dollars(10000.0), 0.0, Money::dollars(0.0), Money::dollars(0.0)));
+        let origin = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+        Household::new(person)
+            .with_mortgage(Mortgage::new(origin, Money::dollars(100000.0), 6.0, 10))
+            .with_income("Paycheck", Money::dollars(60000.0), 0.25, 0.0)
+            .with_expense("Living", Money::dollars(1000.0), 0.0)
+            .with_sweep(1, Money::dollars(5000.0))
+    }
+
+    #[test]
+    fn pays_off_the_mortgage_and_tracks_cash() {
+        let mut household = household();
+        let mut clock = Clock::starting_at(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap());
+        let report = simulate(&mut household, &mut clock, 12 * 12);
+
+        assert_eq!(report.shortfalls, 0);
+        assert_eq!(report.taxes_paid, Money::dollars(15000.0 * 12.0));
+        assert_eq!(report.payoffs.len(), 1);
+        assert_eq!(report.payoffs[0].date, NaiveDate::from_ymd_opt(2035, 1, 15).unwrap());
+        assert!(household.mortgage_balance().is_zero());
+
+        // every dollar in or out passed through the accounts: 3750 net pay less 1000 living
+        // each month, less every mortgage payment
+        let mortgage_paid: Money = household.mortgages[0].history().iter().map(|p| p.total_due()).sum();
+        let expected = Money::dollars(15000.0) + Money::dollars(2750.0).checked_mul(144).unwrap() - mortgage_paid;
+        assert_eq!(household.person.cash(), expected);
+        assert_eq!(report.accounts[0].end, Money::dollars(5000.0));
+        assert_eq!(report.snapshots.last().unwrap().net_worth, expected);
+    }
+
+    #[test]
+    fn escrow_and_hoa_come_out_of_checking() {
+        let origin = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+        let mut household = household();
+        household.mortgages[0] = Mortgage::new(origin, Money::dollars(100000.0), 6.0, 10)
+            .with_property_tax(Money::dollars(2400.0))
+            .with_insurance(Money::dollars(1200.0))
+            .with_hoa(Money::dollars(150.0));
+        let mut clock = Clock::starting_on(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+        simulate(&mut household, &mut clock, 12);
+
+        // $200 tax, $100 insurance and $150 hoa on top of principal and interest each month
+        let history = household.mortgages[0].history();
+        assert_eq!(history[0].total_due(), history[0].payment_amount + Money::dollars(450.0));
+        let mortgage_paid: Money = history.iter().map(|p| p.total_due()).sum();
+        let expected = Money::dollars(15000.0) + Money::dollars(2750.0).checked_mul(12).unwrap() - mortgage_paid;
+        assert_eq!(household.person.cash(), expected);
+    }
+
+    #[test]
+    fn raises_and_inflation_apply_yearly() {
+        let mut household = household().with_investment_return(0.12);
+        household.incomes[0].annual_raise = 0.10;
+        household.expenses[0].annual_inflation = 0.05;
+        let mut clock = Clock::starting_on(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+        simulate(&mut household, &mut clock, 25);
+
+        // raised twice, at months 13 and 25
+        assert_eq!(household.incomes[0].annual, Money::dollars(72600.0));
+        assert_eq!(household.expenses[0].monthly, Money::dollars(1102.5));
+    }
+
+    #[test]
+    fn reports_in_todays_dollars() {
+        let mut inflating = household().with_inflation(0.03);
+        let mut clock = Clock::starting_on(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+        let report = simulate(&mut inflating, &mut clock, 10 * 12);
+
+        let last = report.snapshots.last().unwrap();
+        assert!((last.deflator - 1.03f64.powi(10)).abs() < 1e-3);
+        let real = last.in_todays_dollars();
+        assert_eq!(real.net_worth, last.real(last.net_worth));
+        assert!(real.net_worth < last.net_worth);
+        // living costs don't inflate in this household, so each month's spending is worth less
+        assert!(real.withdrawals < last.withdrawals);
+        assert_eq!(report.accounts[1].end_real, report.inflation.real(report.accounts[1].end, clock.now()));
+        assert!(report.to_string().contains("Real Net Worth"));
+
+        let mut flat_household = household();
+        let flat = simulate(&mut flat_household, &mut Clock::starting_on(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()), 12);
+        assert_eq!(flat.snapshots[11].deflator, 1.0);
+        assert!(!flat.to_string().contains("Real"));
+    }
+}
@@ -0,0 +1,28 @@
+use bankaccounts::BankAccount;
+use bankaccounts::person::{Holding, Person};
+use chrono::{TimeZone, Utc};
+use money::Money;
+use mortgage::Mortgage;
+use simclock::Clock;
+use simulation::{simulate, Household};
+
+fn main() {
+    let mut person = Person::new("Stephen");
+    person.accounts.push(BankAccount::new_checking("Checking", Money::dollars(8000.0), 0.5, Money::dollars(500.0), Money::dollars(25.0)));
+    person.accounts.push(BankAccount::new_checking("High Yield Savings", Money::dollars(25000.0), 4.0, Money::dollars(0.0), Money::dollars(0.0)));
+    person.holdings.push(Holding { symbol: "VTI".to_string(), shares: 120.0, price: Money::dollars(250.0) });
+
+    let origin_date = Utc.with_ymd_and_hms(2023, 8, 1, 0, 0, 0).unwrap();
+    let mortgage = Mortgage::builder(origin_date, Money::dollars(479000.0), 5.5, 30).build().expect("valid mortgage terms");
+    let mut household = Household::new(person)
+        .with_mortgage(mortgage)
+        .with_income("Salary", Money::dollars(150000.0), 0.28, 0.03)
+        .with_expense("Living expenses", Money::dollars(4000.0), 0.03)
+        .with_sweep(1, Money::dollars(10000.0))
+        .with_investment_return(0.06)
+        .with_inflation(0.03);
+
+    let mut clock = Clock::starting_at(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap());
+    let report = simulate(&mut household, &mut clock, 30 * 12);
+    print!("{}", report);
+}
@@ -0,0 +1,73 @@
+// svg charts of amortization schedules: the remaining balance, the interest paid so far, and
+// how each payment splits between principal and interest, with a line per schedule
+use mortgage::AmortizationSchedule;
+use plotters::prelude::*;
+
+const SIZE: (u32, u32) = (1000, 1200);
+
+pub fn draw(path: &str, schedules: &[(String, AmortizationSchedule)]) -> Result<(), String> {
+    let root = SVGBackend::new(path, SIZE).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+    let panels = root.split_evenly((3, 1));
+
+    let dollars = |f: fn(&AmortizationSchedule) -> Vec<f64>| {
+        schedules.iter().map(|(name, schedule)| (name.as_str(), f(schedule))).collect::<Vec<_>>()
+    };
+    let balance = dollars(|s| s.payments.iter().map(|p| p.remaining_principal.to_f64()).collect());
+    let interest = dollars(|s| {
+        s.payments.iter()
+            .scan(0.0, |total, p| {
+                *total += p.interest_payment.to_f64();
+                Some(*total)
+            })
+            .collect()
+    });
+    let principal = dollars(|s| s.payments.iter().map(|p| p.principal_payment.to_f64()).collect());
+    let interest_per_payment = dollars(|s| s.payments.iter().map(|p| p.interest_payment.to_f64()).collect());
+
+    panel(&panels[0], "Remaining balance", &[(&balance, 1.0)])?;
+    panel(&panels[1], "Interest paid so far", &[(&interest, 1.0)])?;
+    // principal solid and interest faded, in each schedule's color
+    panel(&panels[2], "Principal and interest per payment", &[(&principal, 1.0), (&interest_per_payment, 0.4)])?;
+
+    root.present().map_err(|e| format!("could not write {}: {}", path, e))
+}
+
+type Series<'a> = Vec<(&'a str, Vec<f64>)>;
+
+fn panel(area: &DrawingArea<SVGBackend, plotters::coord::Shift>, title: &str, series: &[(&Series, f64)]) -> Result<(), String> {
+    let payments = series.iter().flat_map(|(lines, _)| lines.iter().map(|(_, ys)| ys.len())).max().unwrap_or(1);
+    let top = series.iter()
+        .flat_map(|(lines, _)| lines.iter().flat_map(|(_, ys)| ys.iter().copied()))
+        .fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(title, ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(80)
+        .build_cartesian_2d(1..payments.max(2), 0.0..top.max(1.0) * 1.05)
+        .map_err(|e| e.to_string())?;
+    chart.configure_mesh()
+        .x_desc("Payment")
+        .y_label_formatter(&|y| format!("${:.0}", y))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    for (lines, opacity) in series {
+        for (i, (name, ys)) in lines.iter().enumerate() {
+            let color = Palette99::pick(i).mix(*opacity);
+            let drawn = chart.draw_series(LineSeries::new(ys.iter().enumerate().map(|(n, y)| (n + 1, *y)), color.stroke_width(2)))
+                .map_err(|e| e.to_string())?;
+            // one legend entry per schedule
+            if *opacity == 1.0 {
+                drawn.label(*name).legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+            }
+        }
+    }
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())
+}
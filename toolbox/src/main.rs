@@ -0,0 +1,490 @@
+// one binary for the exercises: each subcommand hands off to the crate that does the work
+mod chart;
+
+use std::{io, path::Path, process};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use bankaccounts::BankAccount;
+use insertionsort::alloc::CountingAlloc;
+use insertionsort::bench::{self, Harness};
+use insertionsort::sort;
+use money::{FormatOptions, Money, Rounding};
+use mortgage::Mortgage;
+use mortgage::allocation::{Portfolio, Strategy};
+use mortgage::builder::MortgageBuilder;
+use mortgage::config::PortfolioConfig;
+use mortgage::scenario::{self, ScenarioOrder, ScenarioResult, ScenarioSpec};
+use mortgage::tax::TaxProfile;
+use mortgage::term;
+use simclock::Clock;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[derive(Parser)]
+#[command(name = "toolbox", about = "Runs the rust-intro exercises from one binary")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Mortgage schedules and extra-payment comparisons
+    Mortgage {
+        #[command(subcommand)]
+        command: MortgageCommand,
+    },
+    /// Bank account statements
+    Bank {
+        #[command(subcommand)]
+        command: BankCommand,
+    },
+    /// Print the lines of files that contain a query
+    Grep {
+        query: String,
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Match regardless of case
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Print each file's name once above its matches, with a summary at the end
+        #[arg(short, long)]
+        group: bool,
+    },
+    /// Time the sorting algorithms on random input
+    Sortbench {
+        /// Algorithms to run (default: all)
+        #[arg(short, long, value_delimiter = ',')]
+        algorithms: Vec<String>,
+        /// Input sizes
+        #[arg(short, long, value_delimiter = ',', default_values_t = [1_000, 10_000])]
+        sizes: Vec<usize>,
+        /// Timed runs per algorithm and size
+        #[arg(short, long, default_value_t = 5)]
+        iterations: usize,
+    },
+}
+
+#[derive(clap::Args)]
+struct Loan {
+    /// Amount borrowed, e.g. 479000 or $479,000
+    #[arg(short, long)]
+    principal: Money,
+    /// Annual interest rate in percent
+    #[arg(short, long)]
+    rate: f64,
+    /// Term in years
+    #[arg(short, long, default_value_t = 30)]
+    years: u32,
+    /// Origination date, YYYY-MM-DD. payments before today count as history
+    #[arg(long)]
+    start: Option<NaiveDate>,
+    /// First payment date, YYYY-MM-DD, for a loan that closed partway into a month
+    #[arg(long)]
+    first_payment: Option<NaiveDate>,
+}
+
+impl Loan {
+    fn builder(&self) -> MortgageBuilder {
+        let origin = self.start.map_or_else(Utc::now, midnight);
+        let mut builder = Mortgage::builder(origin, self.principal, self.rate, self.years);
+        if let Some(date) = self.first_payment {
+            builder = builder.with_first_payment(midnight(date));
+        }
+        // a loan that hasn't closed yet has no history
+        if self.start.is_some() && origin <= Utc::now() { builder.with_history(&Clock::system()) } else { builder }
+    }
+
+    fn mortgage(&self) -> Result<Mortgage, String> {
+        self.builder().build().map_err(|e| e.to_string())
+    }
+}
+
+#[derive(clap::Args)]
+struct Format {
+    /// How amounts are written out
+    #[arg(long, value_enum, default_value_t = Locale::Us)]
+    locale: Locale,
+    /// Show amounts rounded to whole dollars
+    #[arg(long)]
+    whole_dollars: bool,
+    /// How amounts are rounded to whole dollars
+    #[arg(long, value_enum, default_value_t = HalfRounding::Bankers)]
+    rounding: HalfRounding,
+}
+
+impl Format {
+    fn options(&self) -> FormatOptions {
+        let options = match self.locale {
+            Locale::Us => FormatOptions::default(),
+            Locale::European => FormatOptions::european(),
+        };
+        if !self.whole_dollars {
+            return options;
+        }
+        let rounding = match self.rounding {
+            HalfRounding::Bankers => Rounding::HalfEven,
+            HalfRounding::HalfUp => Rounding::HalfUp,
+        };
+        options.with_decimals(0, rounding)
+    }
+}
+
+#[derive(Subcommand)]
+enum MortgageCommand {
+    /// Print the full amortization schedule
+    Schedule {
+        #[command(flatten)]
+        loan: Loan,
+        /// Extra principal paid every month
+        #[arg(short, long, default_value = "0")]
+        extra: Money,
+        /// One row per calendar year instead of per payment
+        #[arg(long)]
+        yearly: bool,
+        /// What the home is worth at origination (default: the principal)
+        #[arg(long)]
+        home_value: Option<Money>,
+        /// Yearly growth in the home's value, in percent
+        #[arg(long, default_value_t = 0.0)]
+        appreciation: f64,
+        /// Marginal tax rate in percent, to estimate what deducting the interest saves
+        #[arg(long)]
+        tax_rate: Option<f64>,
+        /// Standard deduction to compare itemizing against (default: 2024, married filing jointly)
+        #[arg(long, default_value = "29200")]
+        standard_deduction: Money,
+        /// Draw the schedule to this svg file instead of printing it
+        #[arg(long)]
+        chart: Option<String>,
+        /// Yearly inflation in percent, to also show amounts in today's dollars
+        #[arg(long)]
+        inflation: Option<f64>,
+        #[command(flatten)]
+        format: Format,
+    },
+    /// Compare increasing extra payments, other rates and other terms
+    Compare {
+        #[command(flatten)]
+        loan: Loan,
+        /// Step between the compared extra payments
+        #[arg(long, default_value = "50")]
+        step: Money,
+        /// Refinance rates to compare, in percent
+        #[arg(long, value_delimiter = ',')]
+        rates: Vec<f64>,
+        /// Terms to compare, in years
+        #[arg(long, value_delimiter = ',')]
+        terms: Vec<u32>,
+        /// Also compare half the payment every two weeks against a twelfth extra each month
+        #[arg(long)]
+        half_payments: bool,
+        /// Order of the results
+        #[arg(long, value_enum, default_value_t = Order::Listed)]
+        sort: Order,
+        /// Print csv instead of text
+        #[arg(long)]
+        csv: bool,
+        /// Draw every scenario's schedule to this svg file as well
+        #[arg(long)]
+        chart: Option<String>,
+        /// Yearly inflation in percent, to also show amounts in today's dollars
+        #[arg(long)]
+        inflation: Option<f64>,
+        #[command(flatten)]
+        format: Format,
+    },
+    /// Compare buying the rate down with points against investing the cash
+    Points {
+        #[command(flatten)]
+        loan: Loan,
+        /// Discount points offered, each a percent of the principal
+        #[arg(long)]
+        points: f64,
+        /// Rate with the points, in percent
+        #[arg(long)]
+        points_rate: f64,
+        /// Yearly return on the cash if it's invested instead, in percent
+        #[arg(long = "return", default_value_t = 7.0)]
+        annual_return: f64,
+    },
+    /// Quote a refinance of what's owed today at several rates and terms
+    Rates {
+        #[command(flatten)]
+        loan: Loan,
+        /// Quoted rates, in percent
+        #[arg(long, value_delimiter = ',', required = true)]
+        rates: Vec<f64>,
+        /// Terms to quote, in years
+        #[arg(long, value_delimiter = ',', default_values_t = [15, 20, 30])]
+        terms: Vec<u32>,
+    },
+    /// Set the loan at shorter terms against the longest, with what the higher payment earns
+    Terms {
+        #[command(flatten)]
+        loan: Loan,
+        /// Terms to compare, in years
+        #[arg(long, value_delimiter = ',', default_values_t = term::COMPARED_TERMS)]
+        terms: Vec<u32>,
+    },
+    /// Split an extra monthly budget across several loans until they're all paid off
+    Payoff {
+        /// Loan files in the mortgage program's json format, named by file in the plan
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Extra paid each month across all the loans
+        #[arg(short, long)]
+        extra: Money,
+        /// Which loan gets the extra first
+        #[arg(long, value_enum, default_value_t = Allocation::Avalanche)]
+        strategy: Allocation,
+    },
+    /// Run every scenario in a planning file and rank them
+    Run {
+        /// Json file with the loans and the named scenarios to try on them
+        config: String,
+        /// What to rank the scenarios by
+        #[arg(long, value_enum, default_value_t = Order::Interest)]
+        sort: Order,
+        /// Print csv instead of text
+        #[arg(long)]
+        csv: bool,
+        #[command(flatten)]
+        format: Format,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Allocation {
+    /// Highest rate first
+    Avalanche,
+    /// Smallest balance first
+    Snowball,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Locale {
+    /// $1,234.56
+    Us,
+    /// 1.234,56 €
+    European,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HalfRounding {
+    /// Half a dollar to the even dollar
+    Bankers,
+    /// Half a dollar up
+    HalfUp,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Order {
+    Listed,
+    Interest,
+    Payoff,
+    Payment,
+    Ratio,
+}
+
+impl Order {
+    fn apply(self, results: &mut [ScenarioResult]) {
+        let order = match self {
+            Order::Listed => return,
+            Order::Interest => ScenarioOrder::TotalInterest,
+            Order::Payoff => ScenarioOrder::PayoffDate,
+            Order::Payment => ScenarioOrder::Outlay,
+            Order::Ratio => ScenarioOrder::SavingsRatio,
+        };
+        scenario::sort_scenarios(results, order);
+    }
+}
+
+#[derive(Subcommand)]
+enum BankCommand {
+    /// Print an account's transactions with a running balance
+    Statement {
+        /// Accounts file written by the bankaccounts program
+        #[arg(short, long, default_value = "output.json")]
+        file: String,
+        /// Account name (default: every account in the file)
+        #[arg(short, long)]
+        account: Option<String>,
+        /// Only transactions on or after this date, YYYY-MM-DD
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Only transactions on or before this date, YYYY-MM-DD
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+fn run_mortgage(command: MortgageCommand) -> Result<(), String> {
+    match command {
+        MortgageCommand::Schedule { loan, extra, yearly, home_value, appreciation, tax_rate, standard_deduction, chart, inflation, format } => {
+            let mut mortgage = loan.builder().with_additional_payment(extra).build().map_err(|e| e.to_string())?
+                .with_appreciation(appreciation);
+            if let Some(value) = home_value {
+                mortgage = mortgage.with_home_value(value);
+            }
+            if let Some(rate) = tax_rate {
+                mortgage = mortgage.with_tax_profile(TaxProfile::new(rate, standard_deduction));
+            }
+            if let Some(rate) = inflation {
+                mortgage = mortgage.with_inflation(rate);
+            }
+            let schedule = mortgage.generate_amortization_schedule();
+            let options = format.options();
+            println!("Monthly payment: {}", mortgage.monthly_payment().format(&options));
+            if let Some(path) = chart {
+                chart::draw(&path, &[("Schedule".to_string(), schedule)])?;
+                println!("Wrote {}", path);
+            } else if yearly {
+                print!("{:#}", schedule.formatted(options));
+            } else {
+                print!("{}", schedule.formatted(options));
+            }
+        }
+        MortgageCommand::Compare { loan, step, rates, terms, half_payments, sort, csv, chart, inflation, format } => {
+            let extras = (1..=10).map(|i| {
+                let extra = step.checked_mul(i).expect("additional payment out of range");
+                ScenarioSpec::new(&format!("{} extra", extra)).with_additional_payment(extra)
+            });
+            let rates = rates.into_iter().map(|rate| ScenarioSpec::new(&format!("{}% rate", rate)).with_rate(rate));
+            let terms = terms.into_iter().map(|years| ScenarioSpec::new(&format!("{} years", years)).with_term(years));
+            let mut mortgage = loan.mortgage()?;
+            if let Some(rate) = inflation {
+                mortgage = mortgage.with_inflation(rate);
+            }
+            let half_payments = if half_payments { scenario::half_payment_scenarios(&mortgage).to_vec() } else { Vec::new() };
+            let mut results = mortgage.compare_scenarios(std::iter::once(ScenarioSpec::new("As is"))
+                .chain(extras).chain(rates).chain(terms).chain(half_payments));
+
+            sort.apply(&mut results);
+            let options = format.options();
+            if csv {
+                scenario::write_csv_with(&results, io::stdout().lock(), &options).map_err(|e| format!("could not write csv: {}", e))?;
+            } else {
+                for result in &results {
+                    println!("{}", result.formatted(options));
+                }
+            }
+            if let Some(path) = chart {
+                let schedules: Vec<_> = results.iter()
+                    .map(|r| (r.spec.name.clone(), r.spec.apply(&mortgage).generate_amortization_schedule()))
+                    .collect();
+                chart::draw(&path, &schedules)?;
+                eprintln!("Wrote {}", path);
+            }
+        }
+        MortgageCommand::Points { loan, points, points_rate, annual_return } => {
+            let par = loan.mortgage()?;
+            let bought_down = ScenarioSpec::new("points").with_rate(points_rate).apply(&par).with_points(points);
+            print!("{}", bought_down.points_vs_investing(&par, annual_return));
+        }
+        MortgageCommand::Rates { loan, rates, terms } => {
+            print!("{}", loan.mortgage()?.rate_sensitivity(&rates, &terms));
+        }
+        MortgageCommand::Terms { loan, terms } => {
+            print!("{}", loan.mortgage()?.compare_terms(&terms));
+        }
+        MortgageCommand::Payoff { files, extra, strategy } => {
+            let mut portfolio = Portfolio::new();
+            for file in &files {
+                let mut mortgage = Mortgage::from_json(file).map_err(|e| format!("could not load {}: {}", file, e))?;
+                mortgage.validate().map_err(|e| format!("{}: {}", file, e))?;
+                mortgage.generate_history(&Clock::system());
+                let name = Path::new(file).file_stem().map_or(file.as_str(), |stem| stem.to_str().unwrap_or(file));
+                portfolio = portfolio.with_loan(name, mortgage);
+            }
+            let strategy = match strategy {
+                Allocation::Avalanche => Strategy::Avalanche,
+                Allocation::Snowball => Strategy::Snowball,
+            };
+            println!("{}", portfolio.allocate(extra, strategy));
+        }
+        MortgageCommand::Run { config, sort, csv, format } => {
+            let config = PortfolioConfig::from_json(&config).map_err(|e| format!("{}: {}", config, e))?;
+            let mut results = config.run();
+            sort.apply(&mut results);
+            let options = format.options();
+            if csv {
+                scenario::write_csv_with(&results, io::stdout().lock(), &options).map_err(|e| format!("could not write csv: {}", e))?;
+            } else {
+                for (rank, result) in results.iter().enumerate() {
+                    println!("{}. {}", rank + 1, result.formatted(options));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_bank(command: BankCommand) -> Result<(), String> {
+    let BankCommand::Statement { file, account, from, to } = command;
+    let accounts = BankAccount::load_accounts_from_json(&file).map_err(|e| format!("could not load {}: {}", file, e))?;
+    let selected: Vec<&BankAccount> = match &account {
+        Some(name) => accounts.iter().filter(|a| a.get_name().eq_ignore_ascii_case(name)).collect(),
+        None => accounts.iter().collect(),
+    };
+    if selected.is_empty() {
+        return Err(format!("no account named {} in {}", account.unwrap_or_default(), file));
+    }
+
+    // the end date is inclusive, so it runs to the end of that day
+    let end = to.map(|d| midnight(d + chrono::Days::new(1)) - chrono::Duration::nanoseconds(1));
+    for account in selected {
+        println!("{}\n", account.generate_statement(from.map(midnight), end));
+    }
+    Ok(())
+}
+
+fn run_sortbench(algorithms: Vec<String>, sizes: Vec<usize>, iterations: usize) -> Result<(), String> {
+    let known = sort::algorithms::<i32>();
+    if let Some(name) = algorithms.iter().find(|n| !known.iter().any(|a| a.name == n.as_str())) {
+        let names: Vec<&str> = known.iter().map(|a| a.name).collect();
+        return Err(format!("unknown algorithm '{}', expected one of: {}", name, names.join(", ")));
+    }
+
+    let harness = Harness::new(1, iterations);
+    for size in sizes {
+        let data = bench::random_vec(size, size as u64);
+        let mut results: Vec<_> = sort::baselines::<i32>()
+            .into_iter()
+            .chain(sort::algorithms::<i32>())
+            .filter(|a| a.name == sort::BASELINE || algorithms.is_empty() || algorithms.iter().any(|n| n == a.name))
+            .map(|a| harness.run(a.name, "random", a.sort, &data))
+            .collect();
+        bench::compute_speedups(&mut results, sort::BASELINE);
+        for result in &results {
+            result.print();
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Mortgage { command } => run_mortgage(command),
+        Command::Bank { command } => run_bank(command),
+        Command::Grep { query, files, ignore_case, group } => {
+            let config = mygrep::Config { query, file_paths: files, ignore_case, group };
+            mygrep::run(config).map_err(|e| e.to_string())
+        }
+        Command::Sortbench { algorithms, sizes, iterations } => run_sortbench(algorithms, sizes, iterations),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
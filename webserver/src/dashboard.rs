@@ -0,0 +1,105 @@
+// read-only pages for one Person's finances. the person is loaded from the json file on
+// every request, so the pages always show whatever the bankaccounts code last saved
+use std::env;
+use std::fmt::Write;
+
+use actix_web::HttpResponse;
+use bankaccounts::person::Person;
+use serde::Serialize;
+
+const RECENT_TRANSACTIONS: usize = 10;
+
+fn person_file() -> String {
+    env::var("PERSON_FILE").unwrap_or_else(|_| "person.json".to_string())
+}
+
+fn load_person() -> Result<Person, HttpResponse> {
+    let path = person_file();
+    Person::load_from_json(&path).map_err(|e| {
+        if e.is_io() {
+            HttpResponse::NotFound().body(format!("No portfolio found at {}", path))
+        } else {
+            HttpResponse::InternalServerError().body(format!("Could not read {}: {}", path, e))
+        }
+    })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+pub async fn get_dashboard() -> HttpResponse {
+    let person = match load_person() {
+        Ok(person) => person,
+        Err(response) => return response,
+    };
+
+    let mut page = format!(
+        "<title>{name}'s portfolio</title>\n<h1>{name}</h1>\n<p>Net worth: <b>{}</b> ({} cash, {} invested)</p>\n",
+        person.net_worth(),
+        person.cash(),
+        person.brokerage_value(),
+        name = escape(&person.name),
+    );
+
+    page.push_str("<h2>Accounts</h2>\n<table>\n<tr><th>Account</th><th>Type</th><th>Balance</th></tr>\n");
+    for account in &person.accounts {
+        let _ = writeln!(page, "<tr><td>{}</td><td>{}</td><td align=\"right\">{}</td></tr>",
+            escape(account.get_name()), account.kind(), account.get_balance());
+    }
+    page.push_str("</table>\n");
+
+    page.push_str("<h2>Brokerage allocation</h2>\n<p><a href=\"/dashboard/allocation.json\">pie chart data</a></p>\n");
+    page.push_str("<table>\n<tr><th>Holding</th><th>Value</th><th>Share</th></tr>\n");
+    for slice in person.allocation() {
+        let _ = writeln!(page, "<tr><td>{}</td><td align=\"right\">{}</td><td align=\"right\">{:.1}%</td></tr>",
+            escape(&slice.label), slice.value, slice.percent);
+    }
+    page.push_str("</table>\n");
+
+    page.push_str("<h2>Recent transactions</h2>\n<table>\n<tr><th>Date</th><th>Account</th><th>Type</th><th>Amount</th><th>Description</th></tr>\n");
+    for (account, transaction) in person.recent_transactions(RECENT_TRANSACTIONS) {
+        let _ = writeln!(page, "<tr><td>{}</td><td>{}</td><td>{:?}</td><td align=\"right\">{}</td><td>{}</td></tr>",
+            transaction.timestamp.format("%Y-%m-%d"),
+            escape(account),
+            transaction.transaction_type,
+            transaction.signed_amount(),
+            escape(transaction.description.as_deref().unwrap_or("")));
+    }
+    page.push_str("</table>\n");
+
+    page.push_str("<h2>Net worth trend</h2>\n<p><a href=\"/dashboard/networth.json\">trend data</a></p>\n");
+    page.push_str("<table>\n<tr><th>Date</th><th>Net worth</th></tr>\n");
+    for (date, worth) in person.net_worth_history() {
+        let _ = writeln!(page, "<tr><td>{}</td><td align=\"right\">{}</td></tr>", date, worth);
+    }
+    page.push_str("</table>\n");
+
+    HttpResponse::Ok().content_type("text/html").body(page)
+}
+
+pub async fn get_allocation() -> HttpResponse {
+    match load_person() {
+        Ok(person) => HttpResponse::Ok().json(person.allocation()),
+        Err(response) => response,
+    }
+}
+
+#[derive(Serialize)]
+struct TrendPoint {
+    date: String,
+    net_worth: money::Money,
+}
+
+pub async fn get_net_worth() -> HttpResponse {
+    match load_person() {
+        Ok(person) => {
+            let points: Vec<TrendPoint> = person.net_worth_history()
+                .into_iter()
+                .map(|(date, net_worth)| TrendPoint { date: date.to_string(), net_worth })
+                .collect();
+            HttpResponse::Ok().json(points)
+        }
+        Err(response) => response,
+    }
+}
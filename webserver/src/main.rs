@@ -1,5 +1,9 @@
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use serde::Deserialize;
+use money::Money;
+
+mod rest;
+mod dashboard;
 
 #[derive(Deserialize)]
 struct MortgageForm {
@@ -10,10 +14,16 @@ struct MortgageForm {
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let server = HttpServer::new( || { 
+    let store = web::Data::new(rest::AccountStore::from_env());
+    let server = HttpServer::new(move || {
         App::new()
+            .app_data(store.clone())
+            .configure(rest::configure)
             .route("/", web::get().to(get_index))
             .route("/handle", web::post().to(handle_form))
+            .route("/dashboard", web::get().to(dashboard::get_dashboard))
+            .route("/dashboard/allocation.json", web::get().to(dashboard::get_allocation))
+            .route("/dashboard/networth.json", web::get().to(dashboard::get_net_worth))
     });
 
     println!("Serving on http://localhost:3000...");
@@ -51,7 +61,7 @@ async fn get_index() -> HttpResponse {
 }
 
 async fn handle_form(form: web::Form<MortgageForm>) -> impl Responder {
-    let payment = match form.payment.parse::<u32>() {
+    let payment = match form.payment.parse::<Money>() {
         Ok(val) => val,
         Err(_) => return HttpResponse::BadRequest().body("Invalid payment value"),
     };
@@ -60,7 +70,10 @@ async fn handle_form(form: web::Form<MortgageForm>) -> impl Responder {
         Err(_) => return HttpResponse::BadRequest().body("Invalid year value"),
     };
 
-    let total_paid = payment as u32 * (years as u32 * 12);
+    let total_paid = match payment.checked_mul(i64::from(years) * 12) {
+        Ok(val) => val,
+        Err(e) => return HttpResponse::BadRequest().body(format!("Invalid payment value: {}", e)),
+    };
 
     let response = format!("Name: {}. Additional Payment: {}", form.name, total_paid);
 
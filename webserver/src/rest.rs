@@ -0,0 +1,262 @@
+// json endpoints under /api. bodies are the shared types from the api crate, so the client
+// crate can call these without hand-written json. accounts live in a json file like the
+// bankaccounts program's output.json
+use std::env;
+use std::sync::Mutex;
+
+use actix_web::{web, HttpResponse};
+use api::{AccountDetail, AccountSummary, AccountUpdate, Amortization, AmortizationRequest, ApiError,
+    FutureValue, FutureValueRequest, NewAccount, PaymentRow};
+use bankaccounts::BankAccount;
+use money::{Currency, Money, Rounding};
+use mortgage::Mortgage;
+use simclock::Clock;
+
+pub struct AccountStore {
+    path: String,
+    // held across each load-modify-save so concurrent requests don't lose writes
+    lock: Mutex<()>,
+}
+
+impl AccountStore {
+    pub fn new(path: &str) -> Self {
+        AccountStore { path: path.to_string(), lock: Mutex::new(()) }
+    }
+
+    pub fn from_env() -> Self {
+        AccountStore::new(&env::var("ACCOUNTS_FILE").unwrap_or_else(|_| "accounts.json".to_string()))
+    }
+
+    fn load(&self) -> Result<Vec<BankAccount>, HttpResponse> {
+        match BankAccount::load_accounts_from_json(&self.path) {
+            Ok(accounts) => Ok(accounts),
+            // no file yet is just no accounts
+            Err(e) if e.is_io() => Ok(Vec::new()),
+            Err(e) => Err(error(HttpResponse::InternalServerError(), format!("could not read {}: {}", self.path, e))),
+        }
+    }
+
+    fn save(&self, accounts: &[BankAccount]) -> Result<(), HttpResponse> {
+        BankAccount::write_accounts_json_to_file(accounts, &self.path)
+            .map_err(|e| error(HttpResponse::InternalServerError(), format!("could not write {}: {}", self.path, e)))
+    }
+}
+
+fn error(mut builder: actix_web::dev::HttpResponseBuilder, message: String) -> HttpResponse {
+    builder.json(ApiError { error: message })
+}
+
+fn not_found(name: &str) -> HttpResponse {
+    error(HttpResponse::NotFound(), format!("no account named {}", name))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/api/solve_fv", web::post().to(solve_fv))
+        .route("/api/amortization", web::post().to(amortization))
+        .route("/api/accounts", web::get().to(list_accounts))
+        .route("/api/accounts", web::post().to(create_account))
+        .route("/api/accounts/{name}", web::get().to(get_account))
+        .route("/api/accounts/{name}", web::put().to(update_account))
+        .route("/api/accounts/{name}", web::delete().to(delete_account));
+}
+
+pub fn future_value(request: &FutureValueRequest) -> Result<FutureValue, String> {
+    let currency = request.present_value.currency();
+    let months = i64::from(request.years) * 12;
+    let r = request.annual_rate / 100.0 / 12.0;
+    let growth = (1.0 + r).powf(months as f64);
+    // an ordinary annuity of the contributions on top of the compounded lump sum
+    let annuity = if r == 0.0 { months as f64 } else { (growth - 1.0) / r };
+    let amount = request.present_value.to_f64() * growth + request.monthly_contribution.to_f64() * annuity;
+
+    let future_value = Money::from_f64(amount, currency, Rounding::HalfEven).map_err(|e| e.to_string())?;
+    let contributions = request.monthly_contribution.checked_mul(months).map_err(|e| e.to_string())?;
+    let interest = future_value.checked_sub(request.present_value)
+        .and_then(|m| m.checked_sub(contributions))
+        .map_err(|e| e.to_string())?;
+    Ok(FutureValue { future_value, contributions, interest })
+}
+
+async fn solve_fv(request: web::Json<FutureValueRequest>) -> HttpResponse {
+    match future_value(&request) {
+        Ok(fv) => HttpResponse::Ok().json(fv),
+        Err(e) => error(HttpResponse::BadRequest(), e),
+    }
+}
+
+async fn amortization(request: web::Json<AmortizationRequest>) -> HttpResponse {
+    let clock = Clock::starting_on(request.start);
+    let mortgage = match Mortgage::builder(clock.now(), request.principal, request.annual_rate, request.years)
+        .with_additional_payment(request.extra_payment)
+        .build() {
+        Ok(mortgage) => mortgage,
+        Err(e) => return error(HttpResponse::BadRequest(), e.to_string()),
+    };
+    let schedule = mortgage.schedule_from(&clock);
+
+    let payments: Vec<PaymentRow> = schedule.payments.iter()
+        .map(|p| PaymentRow {
+            number: p.payment_number,
+            date: p.payment_date.date_naive(),
+            payment: p.payment_amount,
+            principal: p.principal_payment,
+            interest: p.interest_payment,
+            remaining: p.remaining_principal,
+        })
+        .collect();
+    HttpResponse::Ok().json(Amortization {
+        monthly_payment: mortgage.monthly_payment(),
+        total_interest: schedule.total_interest_paid,
+        total_paid: schedule.total_paid,
+        payoff_date: payments.last().map_or(request.start, |p| p.date),
+        payments,
+    })
+}
+
+async fn list_accounts(store: web::Data<AccountStore>) -> HttpResponse {
+    let _guard = store.lock.lock().unwrap();
+    match store.load() {
+        Ok(accounts) => HttpResponse::Ok().json(accounts.iter().map(AccountSummary::from).collect::<Vec<_>>()),
+        Err(response) => response,
+    }
+}
+
+async fn create_account(store: web::Data<AccountStore>, new: web::Json<NewAccount>) -> HttpResponse {
+    if new.name.trim().is_empty() {
+        return error(HttpResponse::BadRequest(), "account name is required".to_string());
+    }
+    if new.balance.is_negative() || new.balance.currency() != Currency::Usd {
+        return error(HttpResponse::BadRequest(), format!("invalid opening balance {}", new.balance));
+    }
+
+    let _guard = store.lock.lock().unwrap();
+    let mut accounts = match store.load() {
+        Ok(accounts) => accounts,
+        Err(response) => return response,
+    };
+    if accounts.iter().any(|a| a.get_name() == new.name) {
+        return error(HttpResponse::Conflict(), format!("an account named {} already exists", new.name));
+    }
+    let account = new.open();
+    let summary = AccountSummary::from(&account);
+    accounts.push(account);
+    match store.save(&accounts) {
+        Ok(()) => HttpResponse::Created().json(summary),
+        Err(response) => response,
+    }
+}
+
+async fn get_account(store: web::Data<AccountStore>, name: web::Path<String>) -> HttpResponse {
+    let _guard = store.lock.lock().unwrap();
+    match store.load() {
+        Ok(accounts) => match accounts.iter().find(|a| a.get_name() == name.as_str()) {
+            Some(account) => HttpResponse::Ok().json(AccountDetail::from(account)),
+            None => not_found(&name),
+        },
+        Err(response) => response,
+    }
+}
+
+async fn update_account(store: web::Data<AccountStore>, name: web::Path<String>, update: web::Json<AccountUpdate>) -> HttpResponse {
+    let _guard = store.lock.lock().unwrap();
+    let mut accounts = match store.load() {
+        Ok(accounts) => accounts,
+        Err(response) => return response,
+    };
+    if let Some(new_name) = &update.name
+        && new_name != name.as_str()
+        && accounts.iter().any(|a| a.get_name() == new_name)
+    {
+        return error(HttpResponse::Conflict(), format!("an account named {} already exists", new_name));
+    }
+    let account = match accounts.iter_mut().find(|a| a.get_name() == name.as_str()) {
+        Some(account) => account,
+        None => return not_found(&name),
+    };
+    if let Some(new_name) = &update.name {
+        account.name = new_name.clone();
+    }
+    if let Some(rate) = update.interest_rate {
+        account.interest_rate = rate;
+    }
+    let summary = AccountSummary::from(&*account);
+    match store.save(&accounts) {
+        Ok(()) => HttpResponse::Ok().json(summary),
+        Err(response) => response,
+    }
+}
+
+async fn delete_account(store: web::Data<AccountStore>, name: web::Path<String>) -> HttpResponse {
+    let _guard = store.lock.lock().unwrap();
+    let mut accounts = match store.load() {
+        Ok(accounts) => accounts,
+        Err(response) => return response,
+    };
+    let before = accounts.len();
+    accounts.retain(|a| a.get_name() != name.as_str());
+    if accounts.len() == before {
+        return not_found(&name);
+    }
+    match store.save(&accounts) {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(response) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::StatusCode;
+    use api::NewAccountKind;
+
+    #[test]
+    fn future_value_compounds_monthly() {
+        let fv = future_value(&FutureValueRequest {
+            present_value: Money::dollars(10000.0),
+            monthly_contribution: Money::dollars(100.0),
+            annual_rate: 6.0,
+            years: 10,
+        }).unwrap();
+        // 10000 * 1.005^120 + 100 * (1.005^120 - 1) / 0.005
+        assert_eq!(fv.future_value, Money::dollars(34581.90));
+        assert_eq!(fv.contributions, Money::dollars(12000.0));
+        assert_eq!(fv.interest, Money::dollars(12581.90));
+    }
+
+    #[actix_rt::test]
+    async fn account_crud() {
+        let path = env::temp_dir().join(format!("webserver-api-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = web::Data::new(AccountStore::new(path.to_str().unwrap()));
+        let mut app = test::init_service(App::new().app_data(store.clone()).configure(configure)).await;
+
+        let new = NewAccount {
+            name: "Rainy Day".to_string(),
+            balance: Money::dollars(500.0),
+            interest_rate: 4.0,
+            kind: NewAccountKind::Checking { overdraft_limit: Money::dollars(0.0), overdraft_fee: Money::dollars(0.0) },
+        };
+        let request = test::TestRequest::post().uri("/api/accounts").set_json(&new).to_request();
+        assert_eq!(test::call_service(&mut app, request).await.status(), StatusCode::CREATED);
+        let request = test::TestRequest::post().uri("/api/accounts").set_json(&new).to_request();
+        assert_eq!(test::call_service(&mut app, request).await.status(), StatusCode::CONFLICT);
+
+        let update = AccountUpdate { interest_rate: Some(4.5), ..AccountUpdate::default() };
+        let request = test::TestRequest::put().uri("/api/accounts/Rainy%20Day").set_json(&update).to_request();
+        let summary: AccountSummary = test::read_response_json(&mut app, request).await;
+        assert_eq!(summary.interest_rate, 4.5);
+
+        let request = test::TestRequest::get().uri("/api/accounts/Rainy%20Day").to_request();
+        let detail: AccountDetail = test::read_response_json(&mut app, request).await;
+        assert_eq!(detail.summary.balance, Money::dollars(500.0));
+        assert_eq!(detail.summary.kind, "Checking");
+
+        let request = test::TestRequest::delete().uri("/api/accounts/Rainy%20Day").to_request();
+        assert_eq!(test::call_service(&mut app, request).await.status(), StatusCode::NO_CONTENT);
+        let request = test::TestRequest::get().uri("/api/accounts").to_request();
+        let listed: Vec<AccountSummary> = test::read_response_json(&mut app, request).await;
+        assert!(listed.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}